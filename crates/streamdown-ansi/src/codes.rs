@@ -95,6 +95,15 @@ pub const CLEAR_SCREEN: &str = "\x1b[2J";
 /// Move cursor to position. Usage: `format!("\x1b[{};{}H", row, col)`
 pub const CURSOR_POSITION: &str = "\x1b[H";
 
+/// Begin synchronized output (DEC mode 2026).
+///
+/// The terminal holds off repainting until [`SYNC_END`], so multi-step
+/// redraws appear atomically. Terminals without support ignore it.
+pub const SYNC_START: &str = "\x1b[?2026h";
+
+/// End synchronized output (DEC mode 2026).
+pub const SYNC_END: &str = "\x1b[?2026l";
+
 /// Superscript digit Unicode code points.
 /// Index corresponds to digit (0-9).
 pub const SUPER: [u32; 10] = [
@@ -14,9 +14,9 @@ pub const ESCAPE: &str = r"\x1b\[[0-9;]*[mK]";
 /// Regex pattern for all ANSI escape sequences including OSC.
 /// Matches:
 /// - CSI sequences: \x1b[...letter
-/// - OSC sequences: \x1b]...;\
+/// - OSC sequences: \x1b]...;\ (with or without parameters, e.g. OSC 8 `id=`)
 /// - Simple escapes: \x1b)
-pub const ANSIESCAPE: &str = r"\x1b(?:\[[0-9;?]*[a-zA-Z]|\][0-9]*;;.*?\\|\))";
+pub const ANSIESCAPE: &str = r"\x1b(?:\[[0-9;?]*[a-zA-Z]|\][0-9]*;[^;\x1b]*;.*?\\|\))";
 
 /// Compiled regex for ESCAPE pattern.
 static ESCAPE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(ESCAPE).unwrap());
@@ -237,6 +237,7 @@ pub fn ansi_collapse(code_list: &[String], _inp: &str) -> Vec<String> {
 
         let mut i = 0;
         while i < params.len() {
+            #[allow(clippy::collapsible_match)]
             match params[i] {
                 0 => {
                     // Reset all
@@ -408,6 +409,8 @@ mod tests {
         assert_eq!(visible("\x1b[1mBold\x1b[0m"), "Bold");
         assert_eq!(visible("No codes"), "No codes");
         assert_eq!(visible("\x1b[38;2;255;0;0mRed\x1b[0m"), "Red");
+        assert_eq!(visible("\x1b]8;;http://x\x1b\\Link\x1b]8;;\x1b\\"), "Link");
+        assert_eq!(visible("\x1b]8;id=fn-1;#fn-1\x1b\\¹\x1b]8;;\x1b\\"), "¹");
     }
 
     #[test]
@@ -56,6 +56,27 @@ pub struct ComputedStyle {
     /// List indent string.
     pub list_indent: String,
 
+    /// Bullet glyphs for each list nesting level.
+    pub bullets: Vec<String>,
+
+    /// Ordered list numbering style names for each nesting level.
+    pub numbering: Vec<String>,
+
+    /// Note alert color (preset name or hex).
+    pub alert_note: String,
+
+    /// Tip alert color (preset name or hex).
+    pub alert_tip: String,
+
+    /// Important alert color (preset name or hex).
+    pub alert_important: String,
+
+    /// Warning alert color (preset name or hex).
+    pub alert_warning: String,
+
+    /// Caution alert color (preset name or hex).
+    pub alert_caution: String,
+
     /// Full ANSI foreground escape for dark.
     pub dark_fg: String,
 
@@ -158,6 +179,13 @@ impl ComputedStyle {
             link,
             codepad,
             list_indent,
+            bullets: config.bullets.clone(),
+            numbering: config.numbering.clone(),
+            alert_note: config.alert_note.clone(),
+            alert_tip: config.alert_tip.clone(),
+            alert_important: config.alert_important.clone(),
+            alert_warning: config.alert_warning.clone(),
+            alert_caution: config.alert_caution.clone(),
             dark_fg,
             dark_bg,
             mid_fg,
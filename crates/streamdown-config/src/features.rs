@@ -45,6 +45,11 @@ pub struct FeaturesConfig {
     /// Default: true
     #[serde(default = "default_true")]
     pub links: bool,
+
+    /// Markdown dialect: "commonmark", "gfm" or "classic".
+    /// Default: "classic" (streamdown's own extensions)
+    #[serde(default = "default_dialect")]
+    pub dialect: String,
 }
 
 impl Default for FeaturesConfig {
@@ -57,6 +62,7 @@ impl Default for FeaturesConfig {
             savebrace: true,
             images: true,
             links: true,
+            dialect: default_dialect(),
         }
     }
 }
@@ -80,6 +86,7 @@ impl FeaturesConfig {
         self.savebrace = other.savebrace;
         self.images = other.images;
         self.links = other.links;
+        self.dialect = other.dialect.clone();
     }
 
     /// Create a new FeaturesConfig with all features enabled.
@@ -92,6 +99,7 @@ impl FeaturesConfig {
             savebrace: true,
             images: true,
             links: true,
+            dialect: default_dialect(),
         }
     }
 
@@ -105,6 +113,7 @@ impl FeaturesConfig {
             savebrace: false,
             images: false,
             links: false,
+            dialect: default_dialect(),
         }
     }
 }
@@ -117,6 +126,10 @@ fn default_timeout() -> f64 {
     0.1
 }
 
+fn default_dialect() -> String {
+    "classic".to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -131,6 +144,7 @@ mod tests {
         assert!(features.savebrace);
         assert!(features.images);
         assert!(features.links);
+        assert_eq!(features.dialect, "classic");
     }
 
     #[test]
@@ -143,6 +157,7 @@ mod tests {
             Savebrace = false
             Images = false
             Links = false
+            Dialect = "gfm"
         "#;
 
         let features: FeaturesConfig = toml::from_str(toml_str).unwrap();
@@ -153,6 +168,7 @@ mod tests {
         assert!(!features.savebrace);
         assert!(!features.images);
         assert!(!features.links);
+        assert_eq!(features.dialect, "gfm");
     }
 
     #[test]
@@ -134,6 +134,40 @@ pub struct StyleConfig {
     /// Default: "native"
     #[serde(default = "default_syntax")]
     pub syntax: String,
+
+    /// Bullet glyph for each list nesting level, repeating for deeper levels.
+    /// Default: ["•", "◦", "▪", "‣"]
+    #[serde(default = "default_bullets")]
+    pub bullets: Vec<String>,
+
+    /// Ordered list numbering style for each nesting level, repeating for
+    /// deeper levels: "decimal", "lower-alpha", "upper-alpha",
+    /// "lower-roman" or "upper-roman".
+    /// Default: ["decimal"]
+    #[serde(default = "default_numbering")]
+    pub numbering: Vec<String>,
+
+    /// Alert colors, as Colodore preset names or hex values. Unlike the
+    /// other colors they do not follow the base hue.
+    /// Default: "light_blue"
+    #[serde(default = "default_alert_note")]
+    pub alert_note: String,
+
+    /// Default: "light_green"
+    #[serde(default = "default_alert_tip")]
+    pub alert_tip: String,
+
+    /// Default: "purple"
+    #[serde(default = "default_alert_important")]
+    pub alert_important: String,
+
+    /// Default: "yellow"
+    #[serde(default = "default_alert_warning")]
+    pub alert_warning: String,
+
+    /// Default: "red"
+    #[serde(default = "default_alert_caution")]
+    pub alert_caution: String,
 }
 
 impl Default for StyleConfig {
@@ -152,6 +186,13 @@ impl Default for StyleConfig {
             grey: HsvMultiplier::grey(),
             bright: HsvMultiplier::bright(),
             syntax: "native".to_string(),
+            bullets: default_bullets(),
+            numbering: default_numbering(),
+            alert_note: default_alert_note(),
+            alert_tip: default_alert_tip(),
+            alert_important: default_alert_important(),
+            alert_warning: default_alert_warning(),
+            alert_caution: default_alert_caution(),
         }
     }
 }
@@ -172,6 +213,13 @@ impl StyleConfig {
         self.grey = other.grey;
         self.bright = other.bright;
         self.syntax.clone_from(&other.syntax);
+        self.bullets.clone_from(&other.bullets);
+        self.numbering.clone_from(&other.numbering);
+        self.alert_note.clone_from(&other.alert_note);
+        self.alert_tip.clone_from(&other.alert_tip);
+        self.alert_important.clone_from(&other.alert_important);
+        self.alert_warning.clone_from(&other.alert_warning);
+        self.alert_caution.clone_from(&other.alert_caution);
     }
 
     /// Get the base HSV values as (H, S, V) tuple.
@@ -215,6 +263,34 @@ fn default_syntax() -> String {
     "native".to_string()
 }
 
+fn default_bullets() -> Vec<String> {
+    ["•", "◦", "▪", "‣"].map(String::from).to_vec()
+}
+
+fn default_numbering() -> Vec<String> {
+    vec!["decimal".to_string()]
+}
+
+fn default_alert_note() -> String {
+    "light_blue".to_string()
+}
+
+fn default_alert_tip() -> String {
+    "light_green".to_string()
+}
+
+fn default_alert_important() -> String {
+    "purple".to_string()
+}
+
+fn default_alert_warning() -> String {
+    "yellow".to_string()
+}
+
+fn default_alert_caution() -> String {
+    "red".to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -229,6 +305,8 @@ mod tests {
         assert_eq!(style.width, 0);
         assert_eq!(style.hsv, [0.8, 0.5, 0.5]);
         assert_eq!(style.syntax, "native");
+        assert_eq!(style.bullets, ["•", "◦", "▪", "‣"]);
+        assert_eq!(style.numbering, ["decimal"]);
     }
 
     #[test]
@@ -253,6 +331,9 @@ mod tests {
             HSV = [0.5, 0.6, 0.7]
             Dark = { H = 1.0, S = 2.0, V = 0.5 }
             Syntax = "monokai"
+            Bullets = ["-", "+"]
+            Numbering = ["decimal", "lower-alpha", "lower-roman"]
+            AlertWarning = "orange"
         "#;
 
         let style: StyleConfig = toml::from_str(toml_str).unwrap();
@@ -263,6 +344,10 @@ mod tests {
         assert_eq!(style.hsv, [0.5, 0.6, 0.7]);
         assert!((style.dark.s - 2.0).abs() < f64::EPSILON);
         assert_eq!(style.syntax, "monokai");
+        assert_eq!(style.bullets, ["-", "+"]);
+        assert_eq!(style.numbering, ["decimal", "lower-alpha", "lower-roman"]);
+        assert_eq!(style.alert_warning, "orange");
+        assert_eq!(style.alert_note, "light_blue");
     }
 
     #[test]
@@ -107,8 +107,9 @@ pub struct ParseState {
     // === List state ===
     /// Stack of ordered list numbers for nested lists
     pub ordered_list_numbers: Vec<usize>,
-    /// Stack of (indent, type) for nested lists
-    pub list_item_stack: Vec<(usize, ListType)>,
+    /// Stack of (indent, type, delimiter) for nested lists, where the
+    /// delimiter is the bullet character or the `.`/`)` after a number
+    pub list_item_stack: Vec<(usize, ListType, char)>,
     /// Text indentation for list content
     pub list_indent_text: usize,
 
@@ -424,20 +425,30 @@ impl ParseState {
     ///
     /// * `indent` - Indentation level of the list
     /// * `list_type` - Type of list (Bullet or Ordered)
-    pub fn push_list(&mut self, indent: usize, list_type: ListType) {
-        self.list_item_stack.push((indent, list_type));
+    /// * `delimiter` - Bullet character, or `.`/`)` for ordered lists
+    pub fn push_list(&mut self, indent: usize, list_type: ListType, delimiter: char) {
+        self.list_item_stack.push((indent, list_type, delimiter));
         if list_type == ListType::Ordered {
             self.ordered_list_numbers.push(1);
         }
         self.in_list = true;
     }
 
+    /// Push a new ordered list level whose numbering starts at `start`.
+    pub fn push_ordered_list(&mut self, indent: usize, start: usize, delimiter: char) {
+        self.push_list(indent, ListType::Ordered, delimiter);
+        if let Some(number) = self.ordered_list_numbers.last_mut() {
+            *number = start;
+        }
+    }
+
     /// Pop the current list level from the stack.
     ///
-    /// Returns the popped (indent, type) tuple if the stack was non-empty.
-    pub fn pop_list(&mut self) -> Option<(usize, ListType)> {
+    /// Returns the popped (indent, type, delimiter) tuple if the stack was
+    /// non-empty.
+    pub fn pop_list(&mut self) -> Option<(usize, ListType, char)> {
         let result = self.list_item_stack.pop();
-        if let Some((_, ListType::Ordered)) = result {
+        if let Some((_, ListType::Ordered, _)) = result {
             self.ordered_list_numbers.pop();
         }
         self.in_list = !self.list_item_stack.is_empty();
@@ -501,6 +512,69 @@ impl ParseState {
             self.block_type = None;
         }
     }
+
+    /// Append raw input bytes to the input buffer.
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Take the next complete line out of the input buffer.
+    ///
+    /// The trailing `\n` (and `\r`, if any) is stripped. Invalid UTF-8 in a
+    /// complete line is replaced with U+FFFD. Returns `None` if no newline
+    /// has been buffered yet.
+    pub fn take_line(&mut self) -> Option<String> {
+        let pos = self.buffer.iter().position(|&b| b == b'\n')?;
+        let mut bytes: Vec<u8> = self.buffer.drain(..=pos).collect();
+        bytes.pop();
+        if bytes.last() == Some(&b'\r') {
+            bytes.pop();
+        }
+        Some(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Take whatever is left in the input buffer as a final line.
+    ///
+    /// Used at end of input, where an incomplete UTF-8 sequence can no
+    /// longer be completed and is replaced with U+FFFD.
+    pub fn take_partial_line(&mut self) -> Option<String> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+        let bytes = std::mem::take(&mut self.buffer);
+        let line = String::from_utf8_lossy(&bytes);
+        Some(line.strip_suffix('\r').unwrap_or(&line).to_string())
+    }
+
+    /// Decode the buffered partial line without consuming it.
+    ///
+    /// A trailing incomplete UTF-8 sequence is left out, since the rest of
+    /// the codepoint may still arrive.
+    pub fn partial_line(&self) -> String {
+        let end = self.buffer.len() - incomplete_utf8_tail(&self.buffer);
+        String::from_utf8_lossy(&self.buffer[..end]).into_owned()
+    }
+}
+
+/// Length of a trailing UTF-8 sequence that is valid so far but incomplete.
+fn incomplete_utf8_tail(bytes: &[u8]) -> usize {
+    // A UTF-8 sequence is at most 4 bytes, so only the last 3 can be a
+    // dangling prefix.
+    for back in 1..=bytes.len().min(3) {
+        let byte = bytes[bytes.len() - back];
+        if byte & 0xC0 == 0x80 {
+            // Continuation byte, keep looking for the lead byte
+            continue;
+        }
+        let needed = match byte {
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => return 0,
+        };
+        return if needed > back { back } else { 0 };
+    }
+    0
 }
 
 #[cfg(test)]
@@ -585,15 +659,21 @@ mod tests {
     fn test_list_operations() {
         let mut state = ParseState::new();
 
-        state.push_list(0, ListType::Ordered);
+        state.push_list(0, ListType::Ordered, '.');
         assert!(state.in_list);
         assert_eq!(state.list_depth(), 1);
         assert_eq!(state.next_list_number(), Some(1));
         assert_eq!(state.next_list_number(), Some(2));
 
-        state.push_list(2, ListType::Bullet);
+        state.push_list(2, ListType::Bullet, '-');
         assert_eq!(state.list_depth(), 2);
 
+        state.push_ordered_list(4, 7, ')');
+        assert_eq!(state.next_list_number(), Some(7));
+        assert_eq!(state.next_list_number(), Some(8));
+        state.pop_list();
+        assert_eq!(state.next_list_number(), Some(3));
+
         state.pop_list();
         assert_eq!(state.list_depth(), 1);
         assert!(state.in_list);
@@ -638,4 +718,30 @@ mod tests {
         assert_eq!(state.block_depth, 0);
         assert!(state.block_type.is_none());
     }
+
+    #[test]
+    fn test_take_line() {
+        let mut state = ParseState::new();
+        state.push_bytes(b"one\r\ntw");
+        assert_eq!(state.take_line().as_deref(), Some("one"));
+        assert_eq!(state.take_line(), None);
+        assert_eq!(state.partial_line(), "tw");
+
+        state.push_bytes(b"o\n");
+        assert_eq!(state.take_line().as_deref(), Some("two"));
+        assert!(state.buffer.is_empty());
+    }
+
+    #[test]
+    fn test_partial_line_holds_incomplete_utf8() {
+        let mut state = ParseState::new();
+        let bytes = "a→".as_bytes();
+        state.push_bytes(&bytes[..2]);
+        assert_eq!(state.partial_line(), "a");
+
+        state.push_bytes(&bytes[2..]);
+        assert_eq!(state.partial_line(), "a→");
+        assert_eq!(state.take_partial_line().as_deref(), Some("a→"));
+        assert_eq!(state.take_partial_line(), None);
+    }
 }
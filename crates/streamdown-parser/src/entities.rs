@@ -55,42 +55,53 @@ static HTML_ENTITIES: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::
     m
 });
 
-/// Decode HTML entities in a string
+/// Decode HTML entities in a string.
+///
+/// The text is scanned once, so decoded output is never decoded again:
+/// `&amp;lt;` becomes `&lt;`. Unknown or malformed entities are kept as
+/// written.
 pub fn decode_html_entities(text: &str) -> String {
-    let mut result = text.to_string();
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
 
-    // Replace named entities
-    for (entity, replacement) in HTML_ENTITIES.iter() {
-        result = result.replace(entity, replacement);
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match decode_entity(rest) {
+            Some((decoded, len)) => {
+                result.push_str(&decoded);
+                rest = &rest[len..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
     }
 
-    // Handle numeric entities like &#169; or &#x00A9;
-    // Decimal: &#123;
-    while let Some(start) = result.find("&#") {
-        if let Some(end) = result[start..].find(';') {
-            let entity = &result[start..start + end + 1];
-            let num_str = &entity[2..entity.len() - 1];
+    result.push_str(rest);
+    result
+}
 
-            let codepoint = if num_str.starts_with('x') || num_str.starts_with('X') {
-                // Hex: &#x00A9;
-                u32::from_str_radix(&num_str[1..], 16).ok()
-            } else {
-                // Decimal: &#169;
-                num_str.parse::<u32>().ok()
-            };
+/// Decode the entity at the start of `text`, returning the replacement
+/// and the length of the entity.
+fn decode_entity(text: &str) -> Option<(String, usize)> {
+    // Longest entities are numeric: `&#x10FFFF;`
+    let end = text.char_indices().take(12).find(|(_, c)| *c == ';')?.0;
+    let entity = &text[..=end];
 
-            if let Some(cp) = codepoint
-                && let Some(c) = char::from_u32(cp)
-            {
-                result = result.replace(entity, &c.to_string());
-                continue;
-            }
-        }
-        // If we couldn't parse it, break to avoid infinite loop
-        break;
+    if let Some(replacement) = HTML_ENTITIES.get(entity) {
+        return Some((replacement.to_string(), entity.len()));
     }
 
-    result
+    // Numeric: &#169; or &#x00A9;
+    let num_str = entity.strip_prefix("&#")?.strip_suffix(';')?;
+    let codepoint = match num_str.strip_prefix(['x', 'X']) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => num_str.parse::<u32>().ok()?,
+    };
+    let c = char::from_u32(codepoint)?;
+    Some((c.to_string(), entity.len()))
 }
 
 #[cfg(test)]
@@ -118,4 +129,17 @@ mod tests {
             "Copyright © 2024"
         );
     }
+
+    #[test]
+    fn test_decoded_once() {
+        assert_eq!(decode_html_entities("&amp;lt;"), "&lt;");
+        assert_eq!(decode_html_entities("&amp;#169;"), "&#169;");
+    }
+
+    #[test]
+    fn test_malformed_entities_kept() {
+        assert_eq!(decode_html_entities("a & b"), "a & b");
+        assert_eq!(decode_html_entities("&#zz; &#169;"), "&#zz; ©");
+        assert_eq!(decode_html_entities("&unknown; &"), "&unknown; &");
+    }
 }
@@ -0,0 +1,268 @@
+//! Front matter at the start of a document.
+//!
+//! Static site generators put metadata before the markdown, fenced by
+//! `---` (YAML) or `+++` (TOML):
+//!
+//! ```text
+//! ---
+//! title: Getting started
+//! tags: [intro, setup]
+//! ---
+//! ```
+//!
+//! The parser only collects the raw block; [`front_matter_fields`] reads
+//! its top-level keys for display. It is not a YAML or TOML parser: nested
+//! values are flattened into a single line.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Regex for a top-level YAML key: `key: value`
+static YAML_KEY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([A-Za-z_][\w.-]*)\s*:(?:\s+(.*))?$").unwrap());
+
+/// Regex for a TOML key: `key = value`
+static TOML_KEY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^([A-Za-z_][\w.-]*|"[^"]*")\s*=\s*(.*)$"#).unwrap());
+
+/// Regex for a TOML table header: `[table]` or `[[array]]`
+static TOML_TABLE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[\[?\s*([^\]]+?)\s*\]\]?\s*$").unwrap());
+
+/// Language of a front matter block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrontMatterFormat {
+    /// `---` fenced YAML
+    Yaml,
+    /// `+++` fenced TOML
+    Toml,
+}
+
+impl FrontMatterFormat {
+    /// Format opened by a delimiter line, if `line` is one.
+    pub fn from_delimiter(line: &str) -> Option<Self> {
+        match line.trim_end() {
+            "---" => Some(FrontMatterFormat::Yaml),
+            "+++" => Some(FrontMatterFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// The line that opens a block of this format.
+    pub fn delimiter(&self) -> &'static str {
+        match self {
+            FrontMatterFormat::Yaml => "---",
+            FrontMatterFormat::Toml => "+++",
+        }
+    }
+
+    /// Language name, for syntax highlighting.
+    pub fn language(&self) -> &'static str {
+        match self {
+            FrontMatterFormat::Yaml => "yaml",
+            FrontMatterFormat::Toml => "toml",
+        }
+    }
+
+    /// Whether `line` closes a block of this format. YAML may also close
+    /// with `...`.
+    pub(crate) fn is_closing(&self, line: &str) -> bool {
+        let line = line.trim_end();
+        line == self.delimiter() || (*self == FrontMatterFormat::Yaml && line == "...")
+    }
+
+    /// Whether `line` can open the body of a block of this format: a key,
+    /// a comment, or a TOML table header.
+    ///
+    /// Checked on the line after the opening delimiter, so a document that
+    /// merely starts with a `---` rule is not mistaken for front matter.
+    pub(crate) fn starts_body(&self, line: &str) -> bool {
+        if line.starts_with('#') {
+            return true;
+        }
+        match self {
+            FrontMatterFormat::Yaml => YAML_KEY_RE.is_match(line),
+            FrontMatterFormat::Toml => TOML_KEY_RE.is_match(line) || TOML_TABLE_RE.is_match(line),
+        }
+    }
+}
+
+/// Read the fields of a front matter block as (key, value) pairs, in order.
+///
+/// Values are unquoted; lists are joined with `, `. Nested YAML values
+/// are flattened onto one line, and keys in TOML tables are prefixed with
+/// the table name (`extra.author`).
+pub fn front_matter_fields(format: FrontMatterFormat, raw: &str) -> Vec<(String, String)> {
+    match format {
+        FrontMatterFormat::Yaml => yaml_fields(raw),
+        FrontMatterFormat::Toml => toml_fields(raw),
+    }
+}
+
+fn yaml_fields(raw: &str) -> Vec<(String, String)> {
+    // (key, parts, separator joining the parts)
+    let mut fields: Vec<(String, Vec<String>, &str)> = Vec::new();
+    for line in raw.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if let Some(caps) = YAML_KEY_RE.captures(line) {
+            let value = caps.get(2).map_or("", |m| m.as_str());
+            let value = strip_comment(value);
+            let key = caps[1].to_string();
+            match value {
+                // Block scalars continue on the indented lines as text
+                "|" | ">" | "|-" | ">-" => fields.push((key, Vec::new(), " ")),
+                "" => fields.push((key, Vec::new(), ", ")),
+                _ => fields.push((key, vec![scalar_or_list(value)], ", ")),
+            }
+        } else if let Some((_, parts, _)) = fields.last_mut() {
+            // An indented continuation: list item, nested key or text
+            let item = line.trim().trim_start_matches("- ").trim();
+            parts.push(unquote(strip_comment(item)).to_string());
+        }
+    }
+    fields
+        .into_iter()
+        .map(|(key, parts, separator)| (key, parts.join(separator)))
+        .collect()
+}
+
+fn toml_fields(raw: &str) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    let mut table: Option<String> = None;
+    for line in raw.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(caps) = TOML_TABLE_RE.captures(line) {
+            table = Some(caps[1].to_string());
+        } else if let Some(caps) = TOML_KEY_RE.captures(line) {
+            let key = unquote(&caps[1]);
+            let key = match &table {
+                Some(table) => format!("{}.{}", table, key),
+                None => key.to_string(),
+            };
+            fields.push((key, scalar_or_list(strip_comment(&caps[2]))));
+        }
+    }
+    fields
+}
+
+/// Drop a trailing `# comment` from an unquoted value.
+fn strip_comment(value: &str) -> &str {
+    let value = value.trim();
+    if value.starts_with(['"', '\'']) {
+        return value;
+    }
+    match value.find(" #") {
+        Some(i) => value[..i].trim_end(),
+        None => value,
+    }
+}
+
+/// Remove matching surrounding quotes.
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+/// Unquote a scalar, or join the items of an inline `[a, b]` list.
+fn scalar_or_list(value: &str) -> String {
+    match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        Some(items) => items
+            .split(',')
+            .map(|item| unquote(item.trim()))
+            .filter(|item| !item.is_empty())
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => unquote(value).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(format: FrontMatterFormat, raw: &str) -> Vec<(String, String)> {
+        front_matter_fields(format, raw)
+    }
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (key.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn test_delimiters() {
+        assert_eq!(
+            FrontMatterFormat::from_delimiter("---"),
+            Some(FrontMatterFormat::Yaml)
+        );
+        assert_eq!(
+            FrontMatterFormat::from_delimiter("+++ "),
+            Some(FrontMatterFormat::Toml)
+        );
+        assert_eq!(FrontMatterFormat::from_delimiter("----"), None);
+        assert!(FrontMatterFormat::Yaml.is_closing("..."));
+        assert!(!FrontMatterFormat::Toml.is_closing("..."));
+    }
+
+    #[test]
+    fn test_starts_body() {
+        assert!(FrontMatterFormat::Yaml.starts_body("title: Hello"));
+        assert!(FrontMatterFormat::Yaml.starts_body("draft:"));
+        assert!(FrontMatterFormat::Yaml.starts_body("# comment"));
+        assert!(!FrontMatterFormat::Yaml.starts_body(""));
+        assert!(!FrontMatterFormat::Yaml.starts_body("Some prose: with a colon"));
+        assert!(FrontMatterFormat::Toml.starts_body("title = \"Hello\""));
+        assert!(FrontMatterFormat::Toml.starts_body("[extra]"));
+        assert!(!FrontMatterFormat::Toml.starts_body("title: Hello"));
+    }
+
+    #[test]
+    fn test_yaml_fields() {
+        let raw = "title: \"Getting started\"\n\
+                   date: 2024-05-01 # published\n\
+                   tags: [intro, 'setup']\n\
+                   authors:\n  - Ada\n  - Grace\n\
+                   summary: >\n  A short\n  guide\n\
+                   draft: false";
+        assert_eq!(
+            fields(FrontMatterFormat::Yaml, raw),
+            vec![
+                pair("title", "Getting started"),
+                pair("date", "2024-05-01"),
+                pair("tags", "intro, setup"),
+                pair("authors", "Ada, Grace"),
+                pair("summary", "A short guide"),
+                pair("draft", "false"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toml_fields() {
+        let raw = "title = 'Hello'\n\
+                   weight = 10 # sort order\n\
+                   tags = [\"a\", \"b\"]\n\n\
+                   [extra]\n\
+                   author = \"Ada\"";
+        assert_eq!(
+            fields(FrontMatterFormat::Toml, raw),
+            vec![
+                pair("title", "Hello"),
+                pair("weight", "10"),
+                pair("tags", "a, b"),
+                pair("extra.author", "Ada"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Inline HTML and HTML blocks.
+//!
+//! Models often emit a little HTML. A safe subset is mapped to terminal
+//! representations:
+//! - `<br>` is a hard line break
+//! - `<kbd>` is drawn as a key cap
+//! - `<sup>` / `<sub>` become Unicode super/subscripts
+//! - `<details>` / `<summary>` become a box around their content
+//! - `<!-- comments -->` are hidden
+//!
+//! Any other tag is escaped (kept as literal text) or stripped according to
+//! the [`HtmlPolicy`].
+
+use crate::tokenizer::Token;
+use regex::Regex;
+use std::sync::LazyLock;
+use streamdown_ansi::codes::SUPER;
+
+/// Regex for inline HTML: comments, the supported elements, and any other
+/// opening or closing tag
+static INLINE_HTML_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)<!--.*?-->|<kbd>(.*?)</kbd>|<sup>(.*?)</sup>|<sub>(.*?)</sub>|(<br\s*/?>)|</?[a-z][a-z0-9-]*(?:\s[^<>]*)?/?>",
+    )
+    .unwrap()
+});
+
+/// Regex for a `<details>` line, optionally with its summary
+static DETAILS_START_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*<details(?:\s[^>]*)?>\s*(?:<summary>(.*?)</summary>)?\s*$").unwrap()
+});
+
+/// Regex for a `<summary>` line
+static SUMMARY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^\s*<summary>(.*?)</summary>\s*$").unwrap());
+
+/// Regex for a `</details>` line
+static DETAILS_END_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^\s*</details>\s*$").unwrap());
+
+/// What to do with HTML tags outside the supported subset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HtmlPolicy {
+    /// Keep the tag as literal text
+    #[default]
+    Escape,
+    /// Drop the tag, keeping any text between tags
+    Strip,
+}
+
+/// A line of block-level HTML.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum HtmlBlock {
+    /// `<details>`, with the summary if it is on the same line
+    DetailsStart(Option<String>),
+    /// `<summary>...</summary>`
+    Summary(String),
+    /// `</details>`
+    DetailsEnd,
+}
+
+/// Recognize a line of block-level HTML.
+pub(crate) fn parse_html_block(line: &str) -> Option<HtmlBlock> {
+    if let Some(caps) = DETAILS_START_RE.captures(line) {
+        let summary = caps.get(1).map(|m| m.as_str().trim().to_string());
+        return Some(HtmlBlock::DetailsStart(summary));
+    }
+    if let Some(caps) = SUMMARY_RE.captures(line) {
+        return Some(HtmlBlock::Summary(caps[1].trim().to_string()));
+    }
+    DETAILS_END_RE
+        .is_match(line)
+        .then_some(HtmlBlock::DetailsEnd)
+}
+
+/// Find inline HTML in a line, as (start, end, token) extractions.
+pub(crate) fn find_inline_html(line: &str) -> Vec<(usize, usize, Token)> {
+    INLINE_HTML_RE
+        .captures_iter(line)
+        .map(|cap| {
+            let m = cap.get(0).unwrap();
+            let raw = m.as_str();
+            let token = if raw.starts_with("<!--") {
+                Token::HtmlComment
+            } else if let Some(key) = cap.get(1) {
+                Token::Kbd(key.as_str().to_string())
+            } else if let Some(text) = cap.get(2) {
+                Token::Superscript(text.as_str().to_string())
+            } else if let Some(text) = cap.get(3) {
+                Token::Subscript(text.as_str().to_string())
+            } else if cap.get(4).is_some() {
+                Token::HardBreak
+            } else {
+                Token::HtmlTag(raw.to_string())
+            };
+            (m.start(), m.end(), token)
+        })
+        .collect()
+}
+
+/// Find the end of an HTML comment.
+///
+/// Returns the byte offset just past `-->`, searching from `from`.
+pub(crate) fn comment_end(line: &str, from: usize) -> Option<usize> {
+    line.get(from..)?.find("-->").map(|i| from + i + 3)
+}
+
+/// Superscript form of a character, if Unicode has one.
+fn superscript_char(c: char) -> Option<char> {
+    match c {
+        '0'..='9' => char::from_u32(SUPER[c as usize - '0' as usize]),
+        '+' => Some('⁺'),
+        '-' => Some('⁻'),
+        '=' => Some('⁼'),
+        '(' => Some('⁽'),
+        ')' => Some('⁾'),
+        'a' => Some('ᵃ'),
+        'b' => Some('ᵇ'),
+        'c' => Some('ᶜ'),
+        'd' => Some('ᵈ'),
+        'e' => Some('ᵉ'),
+        'f' => Some('ᶠ'),
+        'g' => Some('ᵍ'),
+        'h' => Some('ʰ'),
+        'i' => Some('ⁱ'),
+        'j' => Some('ʲ'),
+        'k' => Some('ᵏ'),
+        'l' => Some('ˡ'),
+        'm' => Some('ᵐ'),
+        'n' => Some('ⁿ'),
+        'o' => Some('ᵒ'),
+        'p' => Some('ᵖ'),
+        'r' => Some('ʳ'),
+        's' => Some('ˢ'),
+        't' => Some('ᵗ'),
+        'u' => Some('ᵘ'),
+        'v' => Some('ᵛ'),
+        'w' => Some('ʷ'),
+        'x' => Some('ˣ'),
+        'y' => Some('ʸ'),
+        'z' => Some('ᶻ'),
+        _ => None,
+    }
+}
+
+/// Subscript form of a character, if Unicode has one.
+fn subscript_char(c: char) -> Option<char> {
+    match c {
+        '0'..='9' => char::from_u32(0x2080 + (c as u32 - '0' as u32)),
+        '+' => Some('₊'),
+        '-' => Some('₋'),
+        '=' => Some('₌'),
+        '(' => Some('₍'),
+        ')' => Some('₎'),
+        'a' => Some('ₐ'),
+        'e' => Some('ₑ'),
+        'h' => Some('ₕ'),
+        'i' => Some('ᵢ'),
+        'j' => Some('ⱼ'),
+        'k' => Some('ₖ'),
+        'l' => Some('ₗ'),
+        'm' => Some('ₘ'),
+        'n' => Some('ₙ'),
+        'o' => Some('ₒ'),
+        'p' => Some('ₚ'),
+        'r' => Some('ᵣ'),
+        's' => Some('ₛ'),
+        't' => Some('ₜ'),
+        'u' => Some('ᵤ'),
+        'v' => Some('ᵥ'),
+        'x' => Some('ₓ'),
+        _ => None,
+    }
+}
+
+/// Convert text using a per-character mapping, or fall back to a
+/// `marker(text)` spelling if any character has no mapped form.
+fn convert(text: &str, map: fn(char) -> Option<char>, marker: char) -> String {
+    match text.chars().map(map).collect::<Option<String>>() {
+        Some(converted) => converted,
+        None if text.chars().count() == 1 => format!("{}{}", marker, text),
+        None => format!("{}({})", marker, text),
+    }
+}
+
+/// Render text as Unicode superscript (`x<sup>2</sup>` -> `x²`).
+///
+/// Text with characters that have no superscript form is spelled `^(text)`.
+pub fn to_superscript(text: &str) -> String {
+    convert(text, superscript_char, '^')
+}
+
+/// Render text as Unicode subscript (`H<sub>2</sub>O` -> `H₂O`).
+///
+/// Text with characters that have no subscript form is spelled `_(text)`.
+pub fn to_subscript(text: &str) -> String {
+    convert(text, subscript_char, '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(line: &str) -> Vec<Token> {
+        find_inline_html(line)
+            .into_iter()
+            .map(|(_, _, t)| t)
+            .collect()
+    }
+
+    #[test]
+    fn test_find_inline_html() {
+        assert_eq!(
+            tokens("a<br>b<BR/>c<br />"),
+            vec![Token::HardBreak, Token::HardBreak, Token::HardBreak]
+        );
+        assert_eq!(
+            tokens("Press <kbd>Ctrl</kbd>+<kbd>C</kbd>"),
+            vec![Token::Kbd("Ctrl".to_string()), Token::Kbd("C".to_string())]
+        );
+        assert_eq!(
+            tokens("x<sup>2</sup> H<sub>2</sub>O <!-- hidden -->"),
+            vec![
+                Token::Superscript("2".to_string()),
+                Token::Subscript("2".to_string()),
+                Token::HtmlComment,
+            ]
+        );
+        assert_eq!(
+            tokens(r#"<span class="x">hi</span> <bread>"#),
+            vec![
+                Token::HtmlTag(r#"<span class="x">"#.to_string()),
+                Token::HtmlTag("</span>".to_string()),
+                Token::HtmlTag("<bread>".to_string()),
+            ]
+        );
+        // Not tags
+        assert!(tokens("a < b > c, <https://x.com>, <3").is_empty());
+    }
+
+    #[test]
+    fn test_parse_html_block() {
+        assert_eq!(
+            parse_html_block("<details>"),
+            Some(HtmlBlock::DetailsStart(None))
+        );
+        assert_eq!(
+            parse_html_block("<details open><summary>More</summary>"),
+            Some(HtmlBlock::DetailsStart(Some("More".to_string())))
+        );
+        assert_eq!(
+            parse_html_block("  <summary> Click </summary>"),
+            Some(HtmlBlock::Summary("Click".to_string()))
+        );
+        assert_eq!(parse_html_block("</details>"), Some(HtmlBlock::DetailsEnd));
+        assert_eq!(parse_html_block("<details>text"), None);
+    }
+
+    #[test]
+    fn test_super_and_subscript() {
+        assert_eq!(to_superscript("2"), "²");
+        assert_eq!(to_superscript("-10"), "⁻¹⁰");
+        assert_eq!(to_superscript("n+1"), "ⁿ⁺¹");
+        assert_eq!(to_superscript("th"), "ᵗʰ");
+        assert_eq!(to_superscript("q"), "^q");
+        assert_eq!(to_superscript("TH"), "^(TH)");
+        assert_eq!(to_subscript("2"), "₂");
+        assert_eq!(to_subscript("max"), "ₘₐₓ");
+        assert_eq!(to_subscript("ij"), "ᵢⱼ");
+        assert_eq!(to_subscript("by"), "_(by)");
+    }
+
+    #[test]
+    fn test_comment_end() {
+        assert_eq!(comment_end("<!-- a --> b", 4), Some(10));
+        assert_eq!(comment_end("still open", 0), None);
+    }
+}
@@ -2,34 +2,174 @@
 //!
 //! This module handles parsing of inline markdown formatting including
 //! bold, italic, underline, strikethrough, inline code, links, images,
-//! and footnotes.
+//! and footnotes, plus the highlight, super/subscript and emoji shortcode
+//! extensions.
+//!
+//! A line parses into [`InlineSpan`]s: elements tagged with the emphasis
+//! active around them, so formatting nests freely (`**bold with *italic*
+//! and `code`**`). Link and highlighted text is parsed into spans of its
+//! own.
 
+use crate::html::{HtmlPolicy, to_subscript, to_superscript};
+use crate::options::Extensions;
 use crate::tokenizer::{Token, Tokenizer};
+use std::collections::HashMap;
 use streamdown_ansi::codes::digit_to_superscript;
 
-/// Result of parsing inline content.
+/// Emphasis applied to a span of inline content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Hash)]
+pub struct InlineStyle {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikeout: bool,
+}
+
+impl InlineStyle {
+    /// No emphasis.
+    pub const PLAIN: Self = Self {
+        bold: false,
+        italic: false,
+        underline: false,
+        strikeout: false,
+    };
+    /// Bold only.
+    pub const BOLD: Self = Self {
+        bold: true,
+        ..Self::PLAIN
+    };
+    /// Italic only.
+    pub const ITALIC: Self = Self {
+        italic: true,
+        ..Self::PLAIN
+    };
+    /// Underline only.
+    pub const UNDERLINE: Self = Self {
+        underline: true,
+        ..Self::PLAIN
+    };
+    /// Strikethrough only.
+    pub const STRIKEOUT: Self = Self {
+        strikeout: true,
+        ..Self::PLAIN
+    };
+
+    /// Whether no emphasis is applied.
+    pub fn is_plain(&self) -> bool {
+        *self == Self::PLAIN
+    }
+
+    /// The emphasis of both styles combined.
+    pub fn union(self, other: Self) -> Self {
+        Self {
+            bold: self.bold || other.bold,
+            italic: self.italic || other.italic,
+            underline: self.underline || other.underline,
+            strikeout: self.strikeout || other.strikeout,
+        }
+    }
+}
+
+/// An inline element with the emphasis active around it.
+///
+/// Spans nested in a link carry their own emphasis on top of the link's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineSpan {
+    pub style: InlineStyle,
+    pub element: InlineElement,
+}
+
+impl InlineSpan {
+    /// Create a span.
+    pub fn new(element: InlineElement, style: InlineStyle) -> Self {
+        Self { style, element }
+    }
+
+    /// Create an unstyled span.
+    pub fn plain(element: InlineElement) -> Self {
+        Self::new(element, InlineStyle::PLAIN)
+    }
+
+    /// Create a span of text.
+    pub fn text(text: impl Into<String>, style: InlineStyle) -> Self {
+        Self::new(InlineElement::Text(text.into()), style)
+    }
+}
+
+/// The text of `spans` without any formatting.
+pub fn plain_text(spans: &[InlineSpan]) -> String {
+    spans
+        .iter()
+        .map(|span| match &span.element {
+            InlineElement::Text(text)
+            | InlineElement::Code(text)
+            | InlineElement::Kbd(text)
+            | InlineElement::Superscript(text)
+            | InlineElement::Subscript(text)
+            | InlineElement::Emoji(text) => text.clone(),
+            InlineElement::Link { content, .. }
+            | InlineElement::LinkReference { content, .. }
+            | InlineElement::Highlight(content) => plain_text(content),
+            InlineElement::Image { alt, .. } => alt.clone(),
+            InlineElement::Footnote(marker) => marker.clone(),
+            InlineElement::HardBreak => " ".to_string(),
+        })
+        .collect()
+}
+
+/// An element of inline content.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InlineElement {
-    /// Plain text
+    /// Text
     Text(String),
-    /// Bold text
-    Bold(String),
-    /// Italic text
-    Italic(String),
-    /// Bold and italic text
-    BoldItalic(String),
-    /// Underlined text
-    Underline(String),
-    /// Strikethrough text
-    Strikeout(String),
     /// Inline code
     Code(String),
-    /// A link
-    Link { text: String, url: String },
+    /// A link, its text parsed into spans
+    Link {
+        content: Vec<InlineSpan>,
+        url: String,
+    },
+    /// A reference link whose definition has not been seen yet
+    LinkReference {
+        content: Vec<InlineSpan>,
+        label: String,
+    },
     /// An image
     Image { alt: String, url: String },
     /// Footnote reference (as superscript)
     Footnote(String),
+    /// Hard line break
+    HardBreak,
+    /// Keyboard key
+    Kbd(String),
+    /// Highlighted text, parsed into spans
+    Highlight(Vec<InlineSpan>),
+    /// Superscript, in Unicode superscript characters where possible
+    Superscript(String),
+    /// Subscript, in Unicode subscript characters where possible
+    Subscript(String),
+    /// Emoji from a shortcode
+    Emoji(String),
+}
+
+/// Target of a link reference definition: `[label]: url "title"`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkDefinition {
+    /// Link destination
+    pub url: String,
+    /// Optional title
+    pub title: Option<String>,
+}
+
+/// Normalize a link label for matching.
+///
+/// Labels match case-insensitively, with runs of whitespace collapsed.
+pub fn normalize_link_label(label: &str) -> String {
+    label
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
 }
 
 /// State for tracking active formatting.
@@ -54,13 +194,14 @@ impl FormatState {
         Self::default()
     }
 
-    #[allow(dead_code)]
-    fn any_active(&self) -> bool {
-        self.bold
-            || self.italic
-            || self.underline
-            || self.strikeout
-            || self.code_backticks.is_some()
+    /// Emphasis currently active.
+    fn style(&self) -> InlineStyle {
+        InlineStyle {
+            bold: self.bold,
+            italic: self.italic,
+            underline: self.underline,
+            strikeout: self.strikeout,
+        }
     }
 
     fn reset(&mut self) {
@@ -76,14 +217,20 @@ impl FormatState {
 /// Inline markdown parser.
 ///
 /// Parses inline formatting and returns structured elements.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct InlineParser {
     tokenizer: Tokenizer,
     state: FormatState,
+    /// Link reference definitions by normalized label
+    link_definitions: HashMap<String, LinkDefinition>,
     /// Whether to process links
     pub process_links: bool,
     /// Whether to process images  
     pub process_images: bool,
+    /// What to do with unsupported HTML tags
+    pub html_policy: HtmlPolicy,
+    /// Syntax recognised on top of CommonMark
+    pub extensions: Extensions,
 }
 
 impl Default for InlineParser {
@@ -98,8 +245,11 @@ impl InlineParser {
         Self {
             tokenizer: Tokenizer::new(),
             state: FormatState::new(),
+            link_definitions: HashMap::new(),
             process_links: true,
             process_images: true,
+            html_policy: HtmlPolicy::default(),
+            extensions: Extensions::default(),
         }
     }
 
@@ -108,21 +258,71 @@ impl InlineParser {
         Self {
             tokenizer: Tokenizer::with_settings(process_links, process_images),
             state: FormatState::new(),
+            link_definitions: HashMap::new(),
             process_links,
             process_images,
+            html_policy: HtmlPolicy::default(),
+            extensions: Extensions::default(),
         }
     }
 
-    /// Parse a line of markdown and return inline elements.
+    /// Define a link reference label.
+    ///
+    /// The first definition of a label wins; returns false if `label` was
+    /// already defined.
+    pub fn define_link(&mut self, label: &str, definition: LinkDefinition) -> bool {
+        let key = normalize_link_label(label);
+        if self.link_definitions.contains_key(&key) {
+            return false;
+        }
+        self.link_definitions.insert(key, definition);
+        true
+    }
+
+    /// Forget the definition of a link reference label.
+    pub(crate) fn undefine_link(&mut self, label: &str) {
+        self.link_definitions.remove(&normalize_link_label(label));
+    }
+
+    /// Look up the definition of a link reference label.
+    pub fn link_definition(&self, label: &str) -> Option<&LinkDefinition> {
+        self.link_definitions.get(&normalize_link_label(label))
+    }
+
+    /// Forget all link reference definitions.
+    pub fn clear_link_definitions(&mut self) {
+        self.link_definitions.clear();
+    }
+
+    /// Parse a line of markdown and return inline spans.
     ///
     /// This is the main entry point for inline parsing.
-    pub fn parse(&mut self, line: &str) -> Vec<InlineElement> {
+    pub fn parse(&mut self, line: &str) -> Vec<InlineSpan> {
+        // The settings are public, so pass on their current values
+        self.tokenizer.process_links = self.process_links;
+        self.tokenizer.process_images = self.process_images;
+        self.tokenizer.extensions = self.extensions;
         let tokens = self.tokenizer.tokenize(line);
         self.parse_tokens(&tokens)
     }
 
-    /// Parse a sequence of tokens into inline elements.
-    fn parse_tokens(&mut self, tokens: &[Token]) -> Vec<InlineElement> {
+    /// Parse link text into spans. Links can't nest, so the text is
+    /// parsed without link processing.
+    fn parse_link_text(&self, text: &str) -> Vec<InlineSpan> {
+        self.parse_nested(text, false)
+    }
+
+    /// Parse text nested in another element into spans of its own.
+    fn parse_nested(&self, text: &str, process_links: bool) -> Vec<InlineSpan> {
+        let mut parser = InlineParser::with_settings(process_links, self.process_images);
+        parser.link_definitions = self.link_definitions.clone();
+        parser.html_policy = self.html_policy;
+        parser.extensions = self.extensions;
+        parser.parse(text)
+    }
+
+    /// Parse a sequence of tokens into inline spans.
+    fn parse_tokens(&mut self, tokens: &[Token]) -> Vec<InlineSpan> {
         let mut elements = Vec::new();
         let mut buffer = String::new();
         let mut i = 0;
@@ -139,7 +339,10 @@ impl InlineParser {
                         // Trim single leading/trailing space (Markdown spec)
                         let code = code.strip_prefix(' ').unwrap_or(&code);
                         let code = code.strip_suffix(' ').unwrap_or(code);
-                        elements.push(InlineElement::Code(code.to_string()));
+                        elements.push(InlineSpan::new(
+                            InlineElement::Code(code.to_string()),
+                            self.state.style(),
+                        ));
                         self.state.code_backticks = None;
                     }
                     _ => {
@@ -234,12 +437,19 @@ impl InlineParser {
                         self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
                     }
 
-                    if self.state.underline && self.state.italic {
-                        self.state.underline = false;
-                        self.state.italic = false;
-                    } else if !self.state.underline && !self.state.italic {
-                        self.state.underline = true;
-                        self.state.italic = true;
+                    // Underline and italic, or bold and italic in CommonMark
+                    let state = &mut self.state;
+                    let strong = if self.extensions.underline {
+                        &mut state.underline
+                    } else {
+                        &mut state.bold
+                    };
+                    if *strong && state.italic {
+                        *strong = false;
+                        state.italic = false;
+                    } else if !*strong && !state.italic {
+                        *strong = true;
+                        state.italic = true;
                     } else {
                         buffer.push_str("___");
                     }
@@ -249,7 +459,11 @@ impl InlineParser {
                     if !buffer.is_empty() {
                         self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
                     }
-                    self.state.underline = !self.state.underline;
+                    if self.extensions.underline {
+                        self.state.underline = !self.state.underline;
+                    } else {
+                        self.state.bold = !self.state.bold;
+                    }
                 }
 
                 Token::Underscore => {
@@ -272,40 +486,145 @@ impl InlineParser {
                     }
                 }
 
-                Token::DoubleTilde => {
+                Token::DoubleTilde if self.extensions.strikethrough => {
                     if !buffer.is_empty() {
                         self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
                     }
                     self.state.strikeout = !self.state.strikeout;
                 }
 
+                Token::Tilde
+                    if self.extensions.strikethrough
+                        && self.extensions.single_tilde_strikethrough =>
+                {
+                    if !buffer.is_empty() {
+                        self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
+                    }
+                    self.state.strikeout = !self.state.strikeout;
+                }
+
+                Token::DoubleTilde | Token::Tilde => {
+                    buffer.push_str(token.marker_str().unwrap_or_default());
+                }
+
                 Token::Link { text, url } => {
                     if !buffer.is_empty() {
                         self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
                     }
-                    elements.push(InlineElement::Link {
-                        text: text.clone(),
-                        url: url.clone(),
-                    });
+                    let content = self.parse_link_text(text);
+                    self.emit(
+                        &mut elements,
+                        InlineElement::Link {
+                            content,
+                            url: url.clone(),
+                        },
+                    );
+                }
+
+                Token::Autolink { text, url } => {
+                    if !buffer.is_empty() {
+                        self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
+                    }
+                    let content = vec![InlineSpan::text(text.clone(), InlineStyle::PLAIN)];
+                    self.emit(
+                        &mut elements,
+                        InlineElement::Link {
+                            content,
+                            url: url.clone(),
+                        },
+                    );
+                }
+
+                Token::RefLink { text, label } => {
+                    if !buffer.is_empty() {
+                        self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
+                    }
+                    let content = self.parse_link_text(text);
+                    let element = match self.link_definition(label) {
+                        Some(definition) => InlineElement::Link {
+                            content,
+                            url: definition.url.clone(),
+                        },
+                        None => InlineElement::LinkReference {
+                            content,
+                            label: label.clone(),
+                        },
+                    };
+                    self.emit(&mut elements, element);
                 }
 
                 Token::Image { alt, url } => {
                     if !buffer.is_empty() {
                         self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
                     }
-                    elements.push(InlineElement::Image {
-                        alt: alt.clone(),
-                        url: url.clone(),
-                    });
+                    self.emit(
+                        &mut elements,
+                        InlineElement::Image {
+                            alt: alt.clone(),
+                            url: url.clone(),
+                        },
+                    );
+                }
+
+                Token::HardBreak => {
+                    if !buffer.is_empty() {
+                        self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
+                    }
+                    self.emit(&mut elements, InlineElement::HardBreak);
+                }
+
+                Token::Kbd(key) => {
+                    if !buffer.is_empty() {
+                        self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
+                    }
+                    self.emit(&mut elements, InlineElement::Kbd(key.clone()));
+                }
+
+                Token::Superscript(text) => {
+                    if !buffer.is_empty() {
+                        self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
+                    }
+                    self.emit(
+                        &mut elements,
+                        InlineElement::Superscript(to_superscript(text)),
+                    );
+                }
+
+                Token::Subscript(text) => {
+                    if !buffer.is_empty() {
+                        self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
+                    }
+                    self.emit(&mut elements, InlineElement::Subscript(to_subscript(text)));
+                }
+
+                Token::Highlight(text) => {
+                    if !buffer.is_empty() {
+                        self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
+                    }
+                    let content = self.parse_nested(text, self.process_links);
+                    self.emit(&mut elements, InlineElement::Highlight(content));
                 }
 
-                Token::Footnote(num) => {
+                Token::Emoji(emoji) => {
                     if !buffer.is_empty() {
                         self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
                     }
-                    // Convert number to superscript
-                    let superscript = number_to_superscript(*num);
-                    elements.push(InlineElement::Footnote(superscript));
+                    self.emit(&mut elements, InlineElement::Emoji(emoji.clone()));
+                }
+
+                Token::HtmlComment => {}
+
+                Token::HtmlTag(raw) => {
+                    if self.html_policy == HtmlPolicy::Escape {
+                        buffer.push_str(raw);
+                    }
+                }
+
+                Token::Footnote(label) => {
+                    if !buffer.is_empty() {
+                        self.emit_formatted(&mut elements, std::mem::take(&mut buffer));
+                    }
+                    self.emit(&mut elements, InlineElement::Footnote(footnote_marker(label)));
                 }
             }
 
@@ -321,7 +640,7 @@ impl InlineParser {
         if self.state.code_backticks.is_some() {
             let code = std::mem::take(&mut self.state.code_buffer);
             if !code.is_empty() {
-                elements.push(InlineElement::Code(code));
+                self.emit(&mut elements, InlineElement::Code(code));
             }
             self.state.code_backticks = None;
         }
@@ -332,24 +651,15 @@ impl InlineParser {
         elements
     }
 
-    /// Emit formatted text based on current state.
-    fn emit_formatted(&self, elements: &mut Vec<InlineElement>, text: String) {
-        if text.is_empty() {
-            return;
-        }
+    /// Emit an element with the current emphasis.
+    fn emit(&self, elements: &mut Vec<InlineSpan>, element: InlineElement) {
+        elements.push(InlineSpan::new(element, self.state.style()));
+    }
 
-        if self.state.bold && self.state.italic {
-            elements.push(InlineElement::BoldItalic(text));
-        } else if self.state.bold {
-            elements.push(InlineElement::Bold(text));
-        } else if self.state.italic {
-            elements.push(InlineElement::Italic(text));
-        } else if self.state.underline {
-            elements.push(InlineElement::Underline(text));
-        } else if self.state.strikeout {
-            elements.push(InlineElement::Strikeout(text));
-        } else {
-            elements.push(InlineElement::Text(text));
+    /// Emit text with the current emphasis.
+    fn emit_formatted(&self, elements: &mut Vec<InlineSpan>, text: String) {
+        if !text.is_empty() {
+            self.emit(elements, InlineElement::Text(text));
         }
     }
 
@@ -359,13 +669,14 @@ impl InlineParser {
     }
 }
 
-/// Convert a number to superscript string.
-fn number_to_superscript(num: u32) -> String {
-    num.to_string()
+/// Footnote marker for a label: digits become superscript, named labels
+/// are kept as they are.
+fn footnote_marker(label: &str) -> String {
+    label
         .chars()
-        .map(|c| {
-            let digit = c.to_digit(10).unwrap_or(0) as u8;
-            digit_to_superscript(digit)
+        .map(|c| match c.to_digit(10) {
+            Some(digit) => digit_to_superscript(digit as u8),
+            None => c,
         })
         .collect()
 }
@@ -375,86 +686,98 @@ fn number_to_superscript(num: u32) -> String {
 /// This is a convenience function that parses a line and returns
 /// the formatted result as ANSI-styled text.
 pub fn format_line(line: &str, process_links: bool, process_images: bool) -> String {
+    let mut parser = InlineParser::with_settings(process_links, process_images);
+    let spans = parser.parse(line);
+    let mut result = String::new();
+    format_spans(&spans, InlineStyle::PLAIN, &mut result);
+    result
+}
+
+/// Append spans as ANSI-styled text, on top of the `outer` emphasis.
+fn format_spans(spans: &[InlineSpan], outer: InlineStyle, result: &mut String) {
     use streamdown_ansi::codes::*;
     use streamdown_ansi::style::*;
 
-    let mut parser = InlineParser::with_settings(process_links, process_images);
-    let elements = parser.parse(line);
-
-    let mut result = String::new();
+    for span in spans {
+        let style = outer.union(span.style);
+        let codes = [
+            (style.bold, BOLD),
+            (style.italic, ITALIC),
+            (style.underline, UNDERLINE),
+            (style.strikeout, STRIKEOUT),
+        ];
+        for (_, (on, _)) in codes.iter().filter(|(active, _)| *active) {
+            result.push_str(on);
+        }
 
-    for element in elements {
-        match element {
-            InlineElement::Text(s) => result.push_str(&s),
-            InlineElement::Bold(s) => {
-                result.push_str(BOLD.0);
-                result.push_str(&s);
-                result.push_str(BOLD.1);
-            }
-            InlineElement::Italic(s) => {
-                result.push_str(ITALIC.0);
-                result.push_str(&s);
-                result.push_str(ITALIC.1);
-            }
-            InlineElement::BoldItalic(s) => {
-                result.push_str(BOLD.0);
-                result.push_str(ITALIC.0);
-                result.push_str(&s);
-                result.push_str(ITALIC.1);
-                result.push_str(BOLD.1);
-            }
-            InlineElement::Underline(s) => {
-                result.push_str(UNDERLINE.0);
-                result.push_str(&s);
-                result.push_str(UNDERLINE.1);
-            }
-            InlineElement::Strikeout(s) => {
-                result.push_str(STRIKEOUT.0);
-                result.push_str(&s);
-                result.push_str(STRIKEOUT.1);
-            }
+        match &span.element {
+            InlineElement::Text(s)
+            | InlineElement::Footnote(s)
+            | InlineElement::Superscript(s)
+            | InlineElement::Subscript(s)
+            | InlineElement::Emoji(s) => result.push_str(s),
             InlineElement::Code(s) => {
                 result.push_str(DIM_ON);
-                result.push_str(&s);
+                result.push_str(s);
                 result.push_str(DIM_OFF);
             }
-            InlineElement::Link { text, url } => {
+            InlineElement::Link { content, url } => {
                 result.push_str(LINK.0);
-                result.push_str(&url);
+                result.push_str(url);
                 result.push('\x1b');
                 result.push_str(UNDERLINE.0);
-                result.push_str(&text);
+                format_spans(content, style, result);
                 result.push_str(UNDERLINE.1);
                 result.push_str(LINK.1);
             }
+            InlineElement::LinkReference { content, .. } => {
+                result.push_str(UNDERLINE.0);
+                format_spans(content, style, result);
+                result.push_str(UNDERLINE.1);
+            }
             InlineElement::Image { alt, url: _ } => {
                 result.push_str(DIM_ON);
                 result.push_str("[\u{1F5BC} ");
-                result.push_str(&alt);
+                result.push_str(alt);
                 result.push(']');
                 result.push_str(DIM_OFF);
             }
-            InlineElement::Footnote(s) => {
-                result.push_str(&s);
+            InlineElement::HardBreak => result.push(' '),
+            InlineElement::Highlight(content) => {
+                result.push_str(REVERSE_ON);
+                format_spans(content, style, result);
+                result.push_str(REVERSE_OFF);
+            }
+            InlineElement::Kbd(s) => {
+                result.push_str(BOLD.0);
+                result.push_str(s);
+                result.push_str(BOLD.1);
             }
         }
-    }
 
-    result
+        for (_, (_, off)) in codes.iter().rev().filter(|(active, _)| *active) {
+            result.push_str(off);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn text(s: &str) -> InlineSpan {
+        InlineSpan::text(s, InlineStyle::PLAIN)
+    }
+
+    fn styled(s: &str, style: InlineStyle) -> InlineSpan {
+        InlineSpan::text(s, style)
+    }
+
     #[test]
     fn test_parse_plain_text() {
         let mut parser = InlineParser::new();
         let elements = parser.parse("Hello world");
-        assert_eq!(
-            elements,
-            vec![InlineElement::Text("Hello world".to_string())]
-        );
+        assert_eq!(elements, vec![text("Hello world")]);
     }
 
     #[test]
@@ -464,9 +787,9 @@ mod tests {
         assert_eq!(
             elements,
             vec![
-                InlineElement::Text("Hello ".to_string()),
-                InlineElement::Bold("bold".to_string()),
-                InlineElement::Text(" world".to_string()),
+                text("Hello "),
+                styled("bold", InlineStyle::BOLD),
+                text(" world"),
             ]
         );
     }
@@ -478,9 +801,9 @@ mod tests {
         assert_eq!(
             elements,
             vec![
-                InlineElement::Text("Hello ".to_string()),
-                InlineElement::Italic("italic".to_string()),
-                InlineElement::Text(" world".to_string()),
+                text("Hello "),
+                styled("italic", InlineStyle::ITALIC),
+                text(" world"),
             ]
         );
     }
@@ -492,9 +815,9 @@ mod tests {
         assert_eq!(
             elements,
             vec![
-                InlineElement::Text("Hello ".to_string()),
-                InlineElement::BoldItalic("bold italic".to_string()),
-                InlineElement::Text(" world".to_string()),
+                text("Hello "),
+                styled("bold italic", InlineStyle::BOLD.union(InlineStyle::ITALIC)),
+                text(" world"),
             ]
         );
     }
@@ -506,9 +829,9 @@ mod tests {
         assert_eq!(
             elements,
             vec![
-                InlineElement::Text("Hello ".to_string()),
-                InlineElement::Strikeout("strike".to_string()),
-                InlineElement::Text(" world".to_string()),
+                text("Hello "),
+                styled("strike", InlineStyle::STRIKEOUT),
+                text(" world"),
             ]
         );
     }
@@ -520,9 +843,9 @@ mod tests {
         assert_eq!(
             elements,
             vec![
-                InlineElement::Text("Use ".to_string()),
-                InlineElement::Code("code".to_string()),
-                InlineElement::Text(" here".to_string()),
+                text("Use "),
+                InlineSpan::plain(InlineElement::Code("code".to_string())),
+                text(" here"),
             ]
         );
     }
@@ -534,9 +857,9 @@ mod tests {
         assert_eq!(
             elements,
             vec![
-                InlineElement::Text("Use ".to_string()),
-                InlineElement::Code("`backticks`".to_string()),
-                InlineElement::Text(" here".to_string()),
+                text("Use "),
+                InlineSpan::plain(InlineElement::Code("`backticks`".to_string())),
+                text(" here"),
             ]
         );
     }
@@ -547,9 +870,9 @@ mod tests {
         let elements = parser.parse("Check [this](http://example.com) out");
 
         assert!(elements.iter().any(|e| matches!(
-            e,
-            InlineElement::Link { text, url }
-            if text == "this" && url == "http://example.com"
+            &e.element,
+            InlineElement::Link { content, url }
+            if plain_text(content) == "this" && url == "http://example.com"
         )));
     }
 
@@ -559,7 +882,7 @@ mod tests {
         let elements = parser.parse("See ![alt text](http://img.png) here");
 
         assert!(elements.iter().any(|e| matches!(
-            e,
+            &e.element,
             InlineElement::Image { alt, url }
             if alt == "alt text" && url == "http://img.png"
         )));
@@ -573,7 +896,7 @@ mod tests {
         assert!(
             elements
                 .iter()
-                .any(|e| matches!(e, InlineElement::Footnote(s) if s == "¹"))
+                .any(|e| matches!(&e.element, InlineElement::Footnote(s) if s == "¹"))
         );
     }
 
@@ -585,7 +908,7 @@ mod tests {
         assert!(
             elements
                 .iter()
-                .any(|e| matches!(e, InlineElement::Footnote(s) if s == "⁴²"))
+                .any(|e| matches!(&e.element, InlineElement::Footnote(s) if s == "⁴²"))
         );
     }
 
@@ -594,10 +917,7 @@ mod tests {
         let mut parser = InlineParser::new();
         let elements = parser.parse("some_variable_name");
         // Underscores in middle of word should not trigger formatting
-        assert_eq!(
-            elements,
-            vec![InlineElement::Text("some_variable_name".to_string())]
-        );
+        assert_eq!(elements, vec![text("some_variable_name")]);
     }
 
     #[test]
@@ -606,40 +926,28 @@ mod tests {
         // Previously this would incorrectly parse "_search tool" as italic
         let mut parser = InlineParser::new();
         let elements = parser.parse("use sem_search tool");
-        assert_eq!(
-            elements,
-            vec![InlineElement::Text("use sem_search tool".to_string())]
-        );
+        assert_eq!(elements, vec![text("use sem_search tool")]);
     }
 
     #[test]
     fn test_underscore_at_start_of_text() {
         let mut parser = InlineParser::new();
         let elements = parser.parse("sem_search");
-        assert_eq!(
-            elements,
-            vec![InlineElement::Text("sem_search".to_string())]
-        );
+        assert_eq!(elements, vec![text("sem_search")]);
     }
 
     #[test]
     fn test_underscore_at_end_of_text() {
         let mut parser = InlineParser::new();
         let elements = parser.parse("sem_search is useful");
-        assert_eq!(
-            elements,
-            vec![InlineElement::Text("sem_search is useful".to_string())]
-        );
+        assert_eq!(elements, vec![text("sem_search is useful")]);
     }
 
     #[test]
     fn test_multiple_underscores_in_text() {
         let mut parser = InlineParser::new();
         let elements = parser.parse("use my_var_name here");
-        assert_eq!(
-            elements,
-            vec![InlineElement::Text("use my_var_name here".to_string())]
-        );
+        assert_eq!(elements, vec![text("use my_var_name here")]);
     }
 
     #[test]
@@ -650,9 +958,9 @@ mod tests {
         assert_eq!(
             elements,
             vec![
-                InlineElement::Text("this is ".to_string()),
-                InlineElement::Italic("italic".to_string()),
-                InlineElement::Text(" text".to_string()),
+                text("this is "),
+                styled("italic", InlineStyle::ITALIC),
+                text(" text"),
             ]
         );
     }
@@ -664,10 +972,7 @@ mod tests {
         let elements = parser.parse("word _italic_");
         assert_eq!(
             elements,
-            vec![
-                InlineElement::Text("word ".to_string()),
-                InlineElement::Italic("italic".to_string()),
-            ]
+            vec![text("word "), styled("italic", InlineStyle::ITALIC),]
         );
     }
 
@@ -679,8 +984,208 @@ mod tests {
         assert_eq!(
             elements,
             vec![
-                InlineElement::Text("use my_func for ".to_string()),
-                InlineElement::Italic("emphasis".to_string()),
+                text("use my_func for "),
+                styled("emphasis", InlineStyle::ITALIC),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_reference_link() {
+        let mut parser = InlineParser::new();
+        let defined = parser.define_link(
+            "The  Docs",
+            LinkDefinition {
+                url: "https://docs.rs".to_string(),
+                title: None,
+            },
+        );
+        assert!(defined);
+
+        let elements = parser.parse("[docs][the docs] and [later][]");
+        assert_eq!(
+            elements,
+            vec![
+                InlineSpan::plain(InlineElement::Link {
+                    content: vec![text("docs")],
+                    url: "https://docs.rs".to_string(),
+                }),
+                text(" and "),
+                InlineSpan::plain(InlineElement::LinkReference {
+                    content: vec![text("later")],
+                    label: "later".to_string(),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_define_link_first_wins() {
+        let mut parser = InlineParser::new();
+        let first = LinkDefinition {
+            url: "/first".to_string(),
+            title: Some("First".to_string()),
+        };
+        let second = LinkDefinition {
+            url: "/second".to_string(),
+            title: None,
+        };
+        assert!(parser.define_link("a", first.clone()));
+        assert!(!parser.define_link("A", second));
+        assert_eq!(parser.link_definition("a"), Some(&first));
+    }
+
+    #[test]
+    fn test_parse_inline_html() {
+        let mut parser = InlineParser::new();
+        let elements =
+            parser.parse("**E = mc<sup>2</sup>**<br>Press <kbd>q</kbd><!-- hi --> <div>");
+        assert_eq!(
+            elements,
+            vec![
+                styled("E = mc", InlineStyle::BOLD),
+                InlineSpan::new(
+                    InlineElement::Superscript("²".to_string()),
+                    InlineStyle::BOLD,
+                ),
+                InlineSpan::plain(InlineElement::HardBreak),
+                text("Press "),
+                InlineSpan::plain(InlineElement::Kbd("q".to_string())),
+                text(" <div>"),
+            ]
+        );
+
+        parser.html_policy = HtmlPolicy::Strip;
+        let elements = parser.parse("<span>kept</span> `<b>code</b>`");
+        assert_eq!(
+            elements,
+            vec![
+                text("kept "),
+                InlineSpan::plain(InlineElement::Code("<b>code</b>".to_string())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_emphasis() {
+        let mut parser = InlineParser::new();
+        let bold_italic = InlineStyle::BOLD.union(InlineStyle::ITALIC);
+        let elements = parser.parse("**bold with *italic* and `code`** ~~__both__~~");
+        assert_eq!(
+            elements,
+            vec![
+                styled("bold with ", InlineStyle::BOLD),
+                styled("italic", bold_italic),
+                styled(" and ", InlineStyle::BOLD),
+                InlineSpan::new(InlineElement::Code("code".to_string()), InlineStyle::BOLD),
+                text(" "),
+                styled("both", InlineStyle::STRIKEOUT.union(InlineStyle::UNDERLINE)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_dialect_emphasis() {
+        let mut parser = InlineParser::new();
+        assert_eq!(
+            parser.parse("__a__ ~2~"),
+            vec![
+                styled("a", InlineStyle::UNDERLINE),
+                text(" "),
+                InlineSpan::plain(InlineElement::Subscript("₂".to_string())),
+            ]
+        );
+
+        parser.extensions = Extensions::gfm();
+        assert_eq!(
+            parser.parse("__a__ ~2~"),
+            vec![
+                styled("a", InlineStyle::BOLD),
+                text(" "),
+                styled("2", InlineStyle::STRIKEOUT),
+            ]
+        );
+
+        parser.extensions = Extensions::commonmark();
+        assert_eq!(parser.parse("~~a~~"), vec![text("~~a~~")]);
+    }
+
+    #[test]
+    fn test_parse_extended_inline() {
+        let mut parser = InlineParser::new();
+        let elements = parser.parse("==**new** [docs](u)== x^2^ H~2~O :rocket:");
+        assert_eq!(
+            elements,
+            vec![
+                InlineSpan::plain(InlineElement::Highlight(vec![
+                    styled("new", InlineStyle::BOLD),
+                    text(" "),
+                    InlineSpan::plain(InlineElement::Link {
+                        content: vec![text("docs")],
+                        url: "u".to_string(),
+                    }),
+                ])),
+                text(" x"),
+                InlineSpan::plain(InlineElement::Superscript("²".to_string())),
+                text(" H"),
+                InlineSpan::plain(InlineElement::Subscript("₂".to_string())),
+                text("O "),
+                InlineSpan::plain(InlineElement::Emoji("🚀".to_string())),
+            ]
+        );
+        assert_eq!(plain_text(&elements), "new docs x² H₂O 🚀");
+
+        parser.extensions = Extensions::commonmark();
+        assert_eq!(
+            parser.parse("==a== x^2^ :rocket:"),
+            vec![text("==a== x^2^ :rocket:")]
+        );
+    }
+
+    #[test]
+    fn test_parse_formatted_links() {
+        let mut parser = InlineParser::new();
+        let elements = parser.parse("**[a *b* `c`](https://x.com)** <https://y.com/__init__>");
+        assert_eq!(
+            elements,
+            vec![
+                InlineSpan::new(
+                    InlineElement::Link {
+                        content: vec![
+                            text("a "),
+                            styled("b", InlineStyle::ITALIC),
+                            text(" "),
+                            InlineSpan::plain(InlineElement::Code("c".to_string())),
+                        ],
+                        url: "https://x.com".to_string(),
+                    },
+                    InlineStyle::BOLD
+                ),
+                text(" "),
+                // Autolink text is never parsed for formatting
+                InlineSpan::plain(InlineElement::Link {
+                    content: vec![text("https://y.com/__init__")],
+                    url: "https://y.com/__init__".to_string(),
+                }),
+            ]
+        );
+        assert_eq!(plain_text(&elements), "a b c https://y.com/__init__");
+    }
+
+    #[test]
+    fn test_parse_escapes_and_entities() {
+        let mut parser = InlineParser::new();
+        let elements = parser.parse(r"\*a\* [b \_c\_ &amp; d](https://x.com) `&amp; \*`");
+        assert_eq!(
+            elements,
+            vec![
+                text("*a* "),
+                InlineSpan::plain(InlineElement::Link {
+                    content: vec![text("b _c_ & d")],
+                    url: "https://x.com".to_string(),
+                }),
+                text(" "),
+                InlineSpan::plain(InlineElement::Code(r"&amp; \*".to_string())),
             ]
         );
     }
@@ -694,11 +1199,12 @@ mod tests {
     }
 
     #[test]
-    fn test_number_to_superscript() {
-        assert_eq!(number_to_superscript(0), "⁰");
-        assert_eq!(number_to_superscript(1), "¹");
-        assert_eq!(number_to_superscript(2), "²");
-        assert_eq!(number_to_superscript(42), "⁴²");
-        assert_eq!(number_to_superscript(123), "¹²³");
+    fn test_footnote_marker() {
+        assert_eq!(footnote_marker("0"), "⁰");
+        assert_eq!(footnote_marker("1"), "¹");
+        assert_eq!(footnote_marker("2"), "²");
+        assert_eq!(footnote_marker("42"), "⁴²");
+        assert_eq!(footnote_marker("123"), "¹²³");
+        assert_eq!(footnote_marker("note"), "note");
     }
 }
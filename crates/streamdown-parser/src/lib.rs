@@ -11,7 +11,7 @@
 //! # Example
 //!
 //! ```
-//! use streamdown_parser::{Parser, ParseEvent};
+//! use streamdown_parser::{Parser, ParseEvent, plain_text};
 //!
 //! let mut parser = Parser::new();
 //!
@@ -19,22 +19,46 @@
 //! for event in parser.parse_line("# Hello World") {
 //!     match event {
 //!         ParseEvent::Heading { level, content } => {
-//!             println!("H{}: {}", level, content);
+//!             println!("H{}: {}", level, plain_text(&content));
 //!         }
 //!         _ => {}
 //!     }
 //! }
 //! ```
+//!
+//! Raw byte chunks can be fed directly with [`Parser::feed`]; lines and
+//! UTF-8 codepoints may be split across chunks:
+//!
+//! ```
+//! use streamdown_parser::{Parser, ParseEvent};
+//!
+//! let mut parser = Parser::new();
+//! assert!(parser.feed(b"# Hel").is_empty());
+//! let events = parser.feed(b"lo\n");
+//! assert!(matches!(&events[0], ParseEvent::Heading { level: 1, .. }));
+//! ```
 
+pub mod emoji;
 pub mod entities;
+pub mod front_matter;
+pub mod html;
 pub mod inline;
+pub mod options;
 pub mod tokenizer;
 
 pub use entities::decode_html_entities;
-pub use inline::{InlineElement, InlineParser, format_line};
+pub use front_matter::{FrontMatterFormat, front_matter_fields};
+pub use html::{HtmlPolicy, to_subscript, to_superscript};
+pub use inline::{
+    InlineElement, InlineParser, InlineSpan, InlineStyle, LinkDefinition, format_line,
+    normalize_link_label, plain_text,
+};
+pub use options::{Dialect, Extensions, ParserOptions};
 pub use tokenizer::{Token, Tokenizer, cjk_count, is_cjk, not_text};
 
+use html::HtmlBlock;
 use regex::Regex;
+use std::ops::RangeInclusive;
 use std::sync::LazyLock;
 use streamdown_core::{BlockType, Code, ListType, ParseState};
 
@@ -42,9 +66,9 @@ use streamdown_core::{BlockType, Code, ListType, ParseState};
 // Regex patterns
 // =============================================================================
 
-/// Regex for code fence: ``` or ~~~ or <pre>
+/// Regex for code fence: ``` or ~~~ or <pre>, then the info string
 static CODE_FENCE_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^\s*(```+|~~~+|<pre>)\s*([^\s]*)\s*$").unwrap());
+    LazyLock::new(|| Regex::new(r"^\s*(```+|~~~+|<pre>)(.*)$").unwrap());
 
 /// Regex for code fence end (also matches </pre>)
 static CODE_FENCE_END_RE: LazyLock<Regex> =
@@ -56,22 +80,45 @@ static SPACE_CODE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^    \s*[^
 /// Regex for headings
 static HEADING_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(#{1,6})\s+(.*)$").unwrap());
 
-/// Regex for list items: handles -, *, +, +---, and 1. style
+/// Regex for list items: handles -, *, +, +---, 1. and 1) style
 static LIST_ITEM_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^(\s*)([+*-]|\+-+|\d+\.)\s+(.*)$").unwrap());
-
-/// Regex for blockquotes and think blocks (including unicode variants)
-static BLOCK_RE: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^\s*((>\s*)+|[◁<].?think[>▷]|</?.?think[>▷]?)(.*)$").unwrap());
+    LazyLock::new(|| Regex::new(r"^(\s*)([+*-]|\+-+|\d{1,9}[.)])\s+(.*)$").unwrap());
+
+/// Regex for a task list marker at the start of list item content
+static TASK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[([ xX])\](?:\s+(.*))?$").unwrap());
+
+/// Regex for think block tags (including unicode variants)
+static THINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*([◁<].?think[>▷]|</?.?think[>▷]?)(.*)$").unwrap());
+
+/// Regex for a link reference definition: `[label]: url "title"`, with
+/// the title optionally in single quotes or parentheses
+static LINK_DEF_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^ {0,3}\[([^\]^][^\]]*)\]:\s*(?:<([^>]*)>|(\S+))(?:\s+(?:"([^"]*)"|'([^']*)'|\(([^)]*)\)))?\s*$"#,
+    )
+    .unwrap()
+});
+
+/// Regex for a GitHub alert marker opening a quote: `[!NOTE]`, with an
+/// optional title after it
+static ALERT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^\s*\[!(note|tip|important|warning|caution)\]\s*(.*)$").unwrap()
+});
+
+/// Regex for a footnote definition: `[^1]: text` or `[^note]: text`
+static FOOTNOTE_DEF_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[\^([^\s\[\]]+)\]:\s*(.*)$").unwrap());
 
 /// Regex for horizontal rules
 static HR_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(---+|\*\*\*+|___+)\s*$").unwrap());
 
-/// Regex for table rows
-static TABLE_ROW_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s*\|(.+)\|\s*$").unwrap());
+/// Regex for a setext heading underline: === (level 1) or --- (level 2)
+static SETEXT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^ {0,3}(=+|-+)\s*$").unwrap());
 
-/// Regex for table separator (only contains |, -, :, spaces)
-static TABLE_SEP_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[\s|:-]+$").unwrap());
+/// Regex for a table delimiter cell: ---, :---, ---:, :---:
+static TABLE_DELIM_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^:?-+:?$").unwrap());
 
 // =============================================================================
 // Types
@@ -88,8 +135,10 @@ pub enum ListBullet {
     Plus,
     /// Expandable plus: +---
     PlusExpand,
-    /// Ordered number
+    /// Ordered number: `1.`
     Ordered(usize),
+    /// Ordered number with a parenthesis: `1)`
+    OrderedParen(usize),
 }
 
 impl ListBullet {
@@ -107,13 +156,156 @@ impl ListBullet {
                 let num = s.trim_end_matches('.').parse().ok()?;
                 Some(ListBullet::Ordered(num))
             }
+            s if s.ends_with(')') => {
+                let num = s.trim_end_matches(')').parse().ok()?;
+                Some(ListBullet::OrderedParen(num))
+            }
             _ => None,
         }
     }
 
     /// Check if this is an ordered bullet.
     pub fn is_ordered(&self) -> bool {
-        matches!(self, ListBullet::Ordered(_))
+        self.number().is_some()
+    }
+
+    /// The number of an ordered bullet.
+    pub fn number(&self) -> Option<usize> {
+        match self {
+            ListBullet::Ordered(n) | ListBullet::OrderedParen(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// The same bullet with its number replaced by `number`.
+    pub fn with_number(self, number: usize) -> Self {
+        match self {
+            ListBullet::Ordered(_) => ListBullet::Ordered(number),
+            ListBullet::OrderedParen(_) => ListBullet::OrderedParen(number),
+            bullet => bullet,
+        }
+    }
+
+    /// The character ending the marker: the bullet itself, or the `.` or
+    /// `)` after a number.
+    pub fn delimiter(&self) -> char {
+        match self {
+            ListBullet::Dash => '-',
+            ListBullet::Asterisk => '*',
+            ListBullet::Plus | ListBullet::PlusExpand => '+',
+            ListBullet::Ordered(_) => '.',
+            ListBullet::OrderedParen(_) => ')',
+        }
+    }
+}
+
+/// Column alignment from a table separator row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Alignment {
+    /// No alignment marker: `---`
+    #[default]
+    None,
+    /// Left aligned: `:---`
+    Left,
+    /// Centered: `:---:`
+    Center,
+    /// Right aligned: `---:`
+    Right,
+}
+
+impl Alignment {
+    /// Parse a separator cell such as `:---:`.
+    pub fn parse(cell: &str) -> Self {
+        let cell = cell.trim();
+        match (cell.starts_with(':'), cell.len() > 1 && cell.ends_with(':')) {
+            (true, true) => Alignment::Center,
+            (true, false) => Alignment::Left,
+            (false, true) => Alignment::Right,
+            (false, false) => Alignment::None,
+        }
+    }
+}
+
+/// Kind of a GitHub-style alert (`> [!NOTE]`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+}
+
+impl AlertKind {
+    /// Parse an alert marker name such as `NOTE` (case-insensitive).
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "note" => Some(AlertKind::Note),
+            "tip" => Some(AlertKind::Tip),
+            "important" => Some(AlertKind::Important),
+            "warning" => Some(AlertKind::Warning),
+            "caution" => Some(AlertKind::Caution),
+            _ => None,
+        }
+    }
+
+    /// Title shown when the alert doesn't give its own.
+    pub fn default_title(&self) -> &'static str {
+        match self {
+            AlertKind::Note => "Note",
+            AlertKind::Tip => "Tip",
+            AlertKind::Important => "Important",
+            AlertKind::Warning => "Warning",
+            AlertKind::Caution => "Caution",
+        }
+    }
+}
+
+/// Attributes from a code fence's info string, besides the language,
+/// as in ``` ```rust title="src/main.rs" {1,3-5} linenos ```.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeInfo {
+    /// Title shown in the block header: `title="src/main.rs"`
+    pub title: Option<String>,
+    /// 1-based line ranges to highlight: `{1,3-5}`
+    pub highlight: Vec<RangeInclusive<usize>>,
+    /// Show line numbers: `linenos` (or `showLineNumbers`)
+    pub line_numbers: bool,
+}
+
+impl CodeInfo {
+    /// Split an info string into the language and the other attributes.
+    ///
+    /// The language is the first word unless it is an attribute itself;
+    /// line ranges may also be attached to it, as in `rust{1,3}`.
+    pub fn parse(info: &str) -> (Option<String>, Self) {
+        let mut info_attrs = Self::default();
+        let mut language = None;
+
+        for (i, word) in info_words(info).into_iter().enumerate() {
+            let word = if i == 0 && !word.starts_with('{') && !word.contains('=') {
+                let (lang, rest) = word.split_at(word.find('{').unwrap_or(word.len()));
+                language = Some(lang.to_string()).filter(|l| !l.is_empty());
+                rest.to_string()
+            } else {
+                word
+            };
+
+            if let Some(ranges) = word.strip_prefix('{').and_then(|w| w.strip_suffix('}')) {
+                info_attrs.highlight.extend(parse_line_ranges(ranges));
+            } else if let Some(title) = word.strip_prefix("title=") {
+                info_attrs.title = Some(title.to_string()).filter(|t| !t.is_empty());
+            } else if word == "linenos" || word == "showLineNumbers" {
+                info_attrs.line_numbers = true;
+            }
+        }
+
+        (language, info_attrs)
+    }
+
+    /// Whether 1-based line `line` is marked for highlighting.
+    pub fn is_highlighted(&self, line: usize) -> bool {
+        self.highlight.iter().any(|range| range.contains(&line))
     }
 }
 
@@ -146,32 +338,100 @@ pub enum ParseEvent {
         url: String,
     },
     Footnote(String),
+    /// A reference link whose definition has not arrived yet; a later
+    /// `LinkDefinition` or `UnresolvedLinkReference` with the same label
+    /// fills it in
+    LinkReference {
+        text: String,
+        label: String,
+    },
+    /// A hard line break (`<br>`)
+    HardBreak,
+    /// A keyboard key (`<kbd>`)
+    Kbd(String),
 
     // === Block-level elements ===
+    /// Front matter at the start of the document, without its delimiters
+    FrontMatter {
+        format: FrontMatterFormat,
+        raw: String,
+    },
     Heading {
         level: u8,
-        content: String,
+        content: Vec<InlineSpan>,
+    },
+    /// A setext heading whose text was already emitted as the previous
+    /// paragraph line; renderers that drew that line should retract it
+    SetextHeading {
+        level: u8,
+        content: Vec<InlineSpan>,
     },
     CodeBlockStart {
         language: Option<String>,
         indent: usize,
+        /// Title, line highlights and line numbers from the info string
+        info: CodeInfo,
     },
     CodeBlockLine(String),
     CodeBlockEnd,
     ListItem {
         indent: usize,
         bullet: ListBullet,
-        content: String,
+        content: Vec<InlineSpan>,
+        /// Task list state: `Some(false)` for `[ ]`, `Some(true)` for `[x]`
+        checked: Option<bool>,
+    },
+    /// The following blocks belong to the open list item at `indent`,
+    /// until the next list item or `ListEnd`
+    ListContinuation {
+        indent: usize,
     },
     ListEnd,
-    TableHeader(Vec<String>),
-    TableRow(Vec<String>),
-    TableSeparator,
+    /// Header cells, each parsed into spans
+    TableHeader(Vec<Vec<InlineSpan>>),
+    /// Body cells, each parsed into spans
+    TableRow(Vec<Vec<InlineSpan>>),
+    TableSeparator(Vec<Alignment>),
     TableEnd,
+    /// A footnote's note, `[^1]: text`; renderers collect these rather
+    /// than printing them in place
+    FootnoteDefinition {
+        label: String,
+        content: Vec<InlineSpan>,
+    },
+    /// The definition of a label used by earlier `LinkReference`s.
+    /// Definitions nothing is waiting for are not emitted
+    LinkDefinition {
+        label: String,
+        url: String,
+        title: Option<String>,
+    },
+    /// A label used by `LinkReference`s that was still undefined at the
+    /// end of the document
+    UnresolvedLinkReference {
+        label: String,
+    },
+    /// Opens a `<details>` box; everything up to the matching `DetailsEnd`
+    /// is its content
+    DetailsStart,
+    /// The `<summary>` of the open `<details>` box
+    DetailsSummary(Vec<InlineSpan>),
+    /// Closes the innermost `<details>` box
+    DetailsEnd,
+    /// Opens one blockquote level. Everything up to the matching
+    /// `BlockquoteEnd` is the quote's content, parsed as blocks
     BlockquoteStart {
         depth: usize,
     },
-    BlockquoteLine(String),
+    /// Opens a blockquote level whose first line was an alert marker
+    /// (`> [!NOTE] Optional title`). Closed by `BlockquoteEnd` like any
+    /// other level
+    AlertStart {
+        kind: AlertKind,
+        title: Option<Vec<InlineSpan>>,
+        depth: usize,
+    },
+    /// Closes the innermost blockquote level
     BlockquoteEnd,
     ThinkBlockStart,
     ThinkBlockLine(String),
@@ -180,7 +440,10 @@ pub enum ParseEvent {
     EmptyLine,
     Newline,
     Prompt(String),
-    InlineElements(Vec<InlineElement>),
+    /// Inline content the flat events above can't express: combined
+    /// emphasis, formatted code, links and other elements, and links with
+    /// formatted text
+    InlineElements(Vec<InlineSpan>),
 }
 
 impl ParseEvent {
@@ -201,6 +464,10 @@ impl ParseEvent {
                 | ParseEvent::Link { .. }
                 | ParseEvent::Image { .. }
                 | ParseEvent::Footnote(_)
+                | ParseEvent::LinkReference { .. }
+                | ParseEvent::HardBreak
+                | ParseEvent::Kbd(_)
+                | ParseEvent::InlineElements(_)
         )
     }
 }
@@ -210,17 +477,71 @@ impl ParseEvent {
 // =============================================================================
 
 /// Streaming markdown parser.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Parser {
     state: ParseState,
     inline_parser: InlineParser,
     code_fence: Option<String>,
     table_state: Option<TableState>,
+    /// Column count of the current table, from its header row
+    table_columns: usize,
+    /// A line held until the next one shows whether it is a table header
+    /// (rows without outer pipes) or a setext heading (with lookahead)
+    held_line: Option<String>,
+    /// Hold the first line of each paragraph so setext headings can be
+    /// emitted as plain headings instead of being retracted
+    setext_lookahead: bool,
+    /// Whether the last line was a paragraph line
+    in_paragraph: bool,
+    /// A one-line paragraph already emitted that a setext underline on the
+    /// next line would turn into a heading
+    setext_candidate: Option<String>,
     events: Vec<ParseEvent>,
     /// Track previous empty line for collapsing
     prev_was_empty: bool,
     /// Deferred list close: set on empty line, resolved on next non-empty line
     list_pending_close: bool,
+    /// Content column of the current item at each list level
+    item_columns: Vec<usize>,
+    /// List level whose item content the last lines continued
+    list_continuation: Option<usize>,
+    /// Whether the open quote is nested in the continued list item
+    item_quote: bool,
+    /// Normalized labels of emitted link references awaiting a definition
+    deferred_links: Vec<String>,
+    /// Inside an HTML comment that spans lines
+    in_html_comment: bool,
+    /// Number of open `<details>` boxes
+    details_depth: usize,
+    /// Front matter being collected: its format and the lines so far
+    front_matter: Option<(FrontMatterFormat, Vec<String>)>,
+    /// Syntax recognised on top of CommonMark
+    extensions: Extensions,
+}
+
+/// Parser state saved while previewing a line.
+///
+/// Only what parsing a single line can change is kept; the link
+/// definitions, which only grow, are undone by label instead.
+struct LineSnapshot {
+    state: ParseState,
+    code_fence: Option<String>,
+    table_state: Option<TableState>,
+    table_columns: usize,
+    held_line: Option<String>,
+    in_paragraph: bool,
+    setext_candidate: Option<String>,
+    prev_was_empty: bool,
+    list_pending_close: bool,
+    item_columns: Vec<usize>,
+    list_continuation: Option<usize>,
+    item_quote: bool,
+    deferred_links: Vec<String>,
+    in_html_comment: bool,
+    details_depth: usize,
+    front_matter: Option<(FrontMatterFormat, Vec<String>)>,
+    /// Label the previewed line would define, if it is not defined yet
+    new_link: Option<String>,
 }
 
 impl Default for Parser {
@@ -237,12 +558,32 @@ impl Parser {
             inline_parser: InlineParser::new(),
             code_fence: None,
             table_state: None,
+            table_columns: 0,
+            held_line: None,
+            setext_lookahead: true,
+            in_paragraph: false,
+            setext_candidate: None,
             events: Vec::new(),
             prev_was_empty: false,
             list_pending_close: false,
+            item_columns: Vec::new(),
+            list_continuation: None,
+            item_quote: false,
+            deferred_links: Vec::new(),
+            in_html_comment: false,
+            details_depth: 0,
+            front_matter: None,
+            extensions: Extensions::default(),
         }
     }
 
+    /// Create a parser for a dialect or set of extensions.
+    pub fn with_options(options: ParserOptions) -> Self {
+        let mut parser = Self::new();
+        parser.set_options(options);
+        parser
+    }
+
     /// Create a parser with a custom ParseState.
     pub fn with_state(state: ParseState) -> Self {
         let inline_parser = InlineParser::with_settings(state.links, state.images);
@@ -251,9 +592,22 @@ impl Parser {
             inline_parser,
             code_fence: None,
             table_state: None,
+            table_columns: 0,
+            held_line: None,
+            setext_lookahead: true,
+            in_paragraph: false,
+            setext_candidate: None,
             events: Vec::new(),
             prev_was_empty: false,
             list_pending_close: false,
+            item_columns: Vec::new(),
+            list_continuation: None,
+            item_quote: false,
+            deferred_links: Vec::new(),
+            in_html_comment: false,
+            details_depth: 0,
+            front_matter: None,
+            extensions: Extensions::default(),
         }
     }
 
@@ -264,6 +618,28 @@ impl Parser {
         &mut self.state
     }
 
+    /// The options the parser is using.
+    pub fn options(&self) -> ParserOptions {
+        ParserOptions {
+            extensions: self.extensions,
+            code_spaces: self.state.code_spaces,
+            html_policy: self.inline_parser.html_policy,
+        }
+    }
+
+    /// Switch to a dialect or set of extensions.
+    pub fn set_options(&mut self, options: ParserOptions) {
+        self.set_extensions(options.extensions);
+        self.set_code_spaces(options.code_spaces);
+        self.set_html_policy(options.html_policy);
+    }
+
+    /// Choose the syntax recognised on top of CommonMark.
+    pub fn set_extensions(&mut self, extensions: Extensions) {
+        self.extensions = extensions;
+        self.inline_parser.extensions = extensions;
+    }
+
     pub fn set_process_links(&mut self, enabled: bool) {
         self.state.links = enabled;
         self.inline_parser.process_links = enabled;
@@ -274,25 +650,181 @@ impl Parser {
         self.inline_parser.process_images = enabled;
     }
 
+    /// Choose what happens to HTML tags outside the supported subset.
+    pub fn set_html_policy(&mut self, policy: HtmlPolicy) {
+        self.inline_parser.html_policy = policy;
+    }
+
     /// Enable space-indented code blocks (4 spaces = code).
     pub fn set_code_spaces(&mut self, enabled: bool) {
         self.state.code_spaces = enabled;
     }
 
+    /// Hold back the first line of each paragraph until the next line.
+    ///
+    /// A setext underline (`===` / `---`) after a one-line paragraph turns
+    /// it into a heading. Without lookahead the paragraph line has already
+    /// been emitted, so the parser emits [`ParseEvent::SetextHeading`] and
+    /// the renderer retracts the printed line; that only works on a
+    /// terminal. With lookahead, the default, the parser emits a plain
+    /// [`ParseEvent::Heading`] instead, at the cost of a one-line delay.
+    pub fn set_setext_lookahead(&mut self, enabled: bool) {
+        self.setext_lookahead = enabled;
+    }
+
     /// Parse a single line and return events.
     pub fn parse_line(&mut self, line: &str) -> Vec<ParseEvent> {
         self.events.clear();
 
+        // Front matter can only open on the first line
+        if std::mem::take(&mut self.state.first_line)
+            && self.extensions.front_matter
+            && let Some(format) = FrontMatterFormat::from_delimiter(line)
+        {
+            self.front_matter = Some((format, Vec::new()));
+            return Vec::new();
+        }
+        if self.front_matter.is_some() {
+            return self.parse_front_matter_line(line);
+        }
+
+        // Think blocks take raw lines until their end tag
+        if self.state.block_type == Some(BlockType::Think) {
+            self.parse_in_think_block(line);
+            return self.take_events();
+        }
+
+        // Quote markers select the container the rest of the line belongs
+        // to; inside a code block only its own container's markers count
+        let max_depth = if self.state.is_in_code() {
+            self.state.block_depth
+        } else {
+            usize::MAX
+        };
+        let nested = self.nested_quote_line(line);
+        let line = nested.as_deref().unwrap_or(line);
+        let (depth, content) = split_quote_prefix(line, max_depth);
+        if depth > 0 && self.state.first_indent.is_none() {
+            self.state.first_indent = Some(line.chars().take_while(|c| c.is_whitespace()).count());
+        }
+        if depth != self.state.block_depth {
+            if self.state.block_depth == 0 {
+                self.item_quote = nested.is_some();
+            }
+            self.set_quote_depth(depth);
+            if depth == 0 {
+                self.item_quote = false;
+            }
+
+            // A quote opened by an alert marker is a callout
+            if let Some(&ParseEvent::BlockquoteStart { depth }) = self.events.last()
+                && self.extensions.alerts
+                && let Some((kind, title)) = parse_alert_marker(content)
+            {
+                self.events.pop();
+                let title = title.map(|title| self.parse_spans(&title));
+                self.events
+                    .push(ParseEvent::AlertStart { kind, title, depth });
+                return self.take_events();
+            }
+        }
+
+        let mut events = self.take_events();
+        events.extend(self.parse_container_line(content));
+        events
+    }
+
+    /// Collect a line of front matter.
+    fn parse_front_matter_line(&mut self, line: &str) -> Vec<ParseEvent> {
+        let Some((format, lines)) = self.front_matter.as_mut() else {
+            return Vec::new();
+        };
+        if format.is_closing(line) {
+            let event = ParseEvent::FrontMatter {
+                format: *format,
+                raw: lines.join("\n"),
+            };
+            self.front_matter = None;
+            return vec![event];
+        }
+        if !lines.is_empty() || format.starts_body(line) {
+            lines.push(line.to_string());
+            return Vec::new();
+        }
+
+        // Not front matter after all: the document just starts with a rule
+        let mut events = self.abandon_front_matter();
+        events.extend(self.parse_line(line));
+        events
+    }
+
+    /// Parse the lines collected as front matter as ordinary markdown.
+    fn abandon_front_matter(&mut self) -> Vec<ParseEvent> {
+        let Some((format, lines)) = self.front_matter.take() else {
+            return Vec::new();
+        };
+        let mut events = self.parse_line(format.delimiter());
+        for line in lines {
+            events.extend(self.parse_line(&line));
+        }
+        events
+    }
+
+    /// Parse the content of a line inside its innermost container.
+    fn parse_container_line(&mut self, line: &str) -> Vec<ParseEvent> {
+        // A held line is decided by this one
+        if let Some(held) = self.held_line.take() {
+            if self.try_start_table(&held, line) {
+                return self.take_events();
+            }
+            if !self.in_paragraph
+                && let Some(level) = setext_level(line)
+            {
+                let content = self.parse_spans(held.trim());
+                self.events.push(ParseEvent::Heading { level, content });
+                return self.take_events();
+            }
+            // Neither: emit the held line as ordinary text
+            self.parse_block_line(&held, false);
+            let mut events = self.take_events();
+            events.extend(self.parse_container_line(line));
+            return events;
+        }
+
+        // An underline turns the paragraph line just emitted into a heading
+        if let Some(content) = self.setext_candidate.take()
+            && let Some(level) = setext_level(line)
+        {
+            self.in_paragraph = false;
+            let content = self.parse_spans(&content);
+            self.events
+                .push(ParseEvent::SetextHeading { level, content });
+            return self.take_events();
+        }
+
         // Handle code blocks first (they consume everything)
         if self.state.is_in_code() {
             self.parse_in_code_block(line);
             return std::mem::take(&mut self.events);
         }
 
-        // Handle think blocks
-        if self.state.block_type == Some(BlockType::Think) {
-            self.parse_in_think_block(line);
-            return std::mem::take(&mut self.events);
+        // HTML comments are hidden, however many lines they span
+        if self.in_html_comment || line.trim_start().starts_with("<!--") {
+            let from = if self.in_html_comment {
+                0
+            } else {
+                line.find("<!--").unwrap_or(0) + 4
+            };
+            let Some(end) = html::comment_end(line, from) else {
+                self.in_html_comment = true;
+                return self.take_events();
+            };
+            self.in_html_comment = false;
+            let rest = &line[end..];
+            if rest.trim().is_empty() {
+                return self.take_events();
+            }
+            return self.parse_container_line(rest);
         }
 
         // Check for empty line (with collapsing) - BEFORE indent stripping
@@ -305,6 +837,38 @@ impl Parser {
         self.prev_was_empty = false;
         self.state.last_line_empty = false;
 
+        // Check for space-indented code BEFORE first-indent stripping
+        // (so we don't accidentally strip the 4-space indent)
+        if self.try_parse_space_code(line, was_prev_empty) {
+            self.in_paragraph = false;
+            self.resolve_pending_list_close();
+            return self.take_events();
+        }
+
+        // Now apply first-indent stripping for other constructs; quote
+        // content is already relative to its markers
+        let line = if self.state.block_depth == 0 {
+            self.strip_first_indent(line)
+        } else {
+            line.to_string()
+        };
+        // Lines indented to an open list item's content continue that item;
+        // inside a quote nested in the item, the quote markers already did
+        let line = if self.item_quote {
+            line
+        } else {
+            self.continue_list_item(&line).unwrap_or(line)
+        };
+        self.parse_block_line(&line, true);
+
+        self.take_events()
+    }
+
+    /// Parse a non-empty line outside code and think blocks.
+    ///
+    /// `allow_hold` is false when replaying a held line that turned out not
+    /// to be a table header or setext heading.
+    fn parse_block_line(&mut self, line: &str, allow_hold: bool) {
         // Classify what this line matches — used to consolidate
         // resolve_pending_list_close() into a single call site.
         enum LineMatch {
@@ -313,30 +877,37 @@ impl Parser {
             OtherConstruct,
         }
 
-        // Check for space-indented code BEFORE first-indent stripping
-        // (so we don't accidentally strip the 4-space indent)
-        if self.try_parse_space_code(line, was_prev_empty) {
+        // After an empty line anything but another list item ends the
+        // list; close it before this line's events
+        if self.list_pending_close && !LIST_ITEM_RE.is_match(line) {
             self.resolve_pending_list_close();
-            return self.take_events();
         }
 
-        // Now apply first-indent stripping for other constructs
-        let line = self.strip_first_indent(line);
+        // Any other block ends an open table before its own events
+        if self.table_state.is_some() && !self.continues_table(line) {
+            self.end_table();
+        }
 
         // Try block-level constructs in order.
         // Each try_parse_* has side effects, so the identical return values are intentional.
         #[allow(clippy::if_same_then_else)]
-        let matched = if self.try_parse_code_fence(&line) {
+        let matched = if self.try_parse_code_fence(line) {
             LineMatch::OtherConstruct
-        } else if self.try_parse_block(&line) {
+        } else if self.state.block_depth == 0 && self.try_parse_think(line) {
             LineMatch::OtherConstruct
-        } else if self.try_parse_heading(&line) {
+        } else if self.try_parse_html_block(line) {
             LineMatch::OtherConstruct
-        } else if self.try_parse_hr(&line) {
+        } else if self.try_parse_link_definition(line) {
             LineMatch::OtherConstruct
-        } else if self.try_parse_list_item(&line) {
+        } else if self.try_parse_footnote_definition(line) {
+            LineMatch::OtherConstruct
+        } else if self.try_parse_heading(line) {
+            LineMatch::OtherConstruct
+        } else if self.try_parse_hr(line) {
+            LineMatch::OtherConstruct
+        } else if self.try_parse_list_item(line) {
             LineMatch::ListItem
-        } else if self.try_parse_table(&line) {
+        } else if self.try_parse_table(line, allow_hold) {
             LineMatch::OtherConstruct
         } else {
             LineMatch::None
@@ -353,14 +924,37 @@ impl Parser {
             }
         }
 
-        if let LineMatch::None = matched {
-            // Exit special contexts for plain text
-            self.exit_block_contexts();
-            // Parse as inline content
-            self.parse_inline_content(&line);
+        match matched {
+            LineMatch::None => {}
+            // The item's text is a paragraph later lines can continue
+            LineMatch::ListItem => return,
+            LineMatch::OtherConstruct => {
+                self.in_paragraph = false;
+                return;
+            }
         }
 
-        self.take_events()
+        // Only a one-line paragraph can become a setext heading
+        let first_line = !self.in_paragraph;
+        if first_line && allow_hold && self.setext_lookahead {
+            self.held_line = Some(line.to_string());
+            return;
+        }
+
+        // Text continuing a list item's paragraph, indented or lazy, stays
+        // in the item
+        if self.in_paragraph && self.state.in_list {
+            self.continue_item_paragraph();
+        }
+        // Exit special contexts for plain text
+        self.exit_block_contexts();
+        // Parse as inline content
+        self.parse_inline_content(line);
+
+        self.in_paragraph = true;
+        if first_line {
+            self.setext_candidate = Some(line.trim().to_string());
+        }
     }
 
     fn take_events(&mut self) -> Vec<ParseEvent> {
@@ -395,31 +989,20 @@ impl Parser {
     fn handle_empty_line(&mut self) -> Vec<ParseEvent> {
         // Collapse consecutive empty lines
         if self.prev_was_empty {
-            return vec![]; // Skip this empty line
+            return self.take_events(); // Skip this empty line
         }
 
         self.prev_was_empty = true;
         self.state.last_line_empty = true;
-
-        // End blockquote if in one
-        if self.state.block_depth > 0 && self.state.block_type == Some(BlockType::Quote) {
-            while self.state.block_depth > 0 {
-                self.state.exit_block();
-            }
-            self.events.push(ParseEvent::BlockquoteEnd);
-        }
+        self.in_paragraph = false;
 
         // Defer list close — a subsequent list item will keep the list alive
-        if self.state.in_list {
+        if self.state.in_list && !self.item_quote {
             self.list_pending_close = true;
         }
 
         // End table if in one
-        if self.table_state.is_some() {
-            self.table_state = None;
-            self.state.in_table = None;
-            self.events.push(ParseEvent::TableEnd);
-        }
+        self.end_table();
 
         self.events.push(ParseEvent::EmptyLine);
         self.take_events()
@@ -436,18 +1019,55 @@ impl Parser {
         }
     }
 
+    /// Close blockquote levels or open new ones until the depth is `depth`.
+    ///
+    /// Blocks open in the current container (paragraph, code block, list,
+    /// table) end before the container changes.
+    fn set_quote_depth(&mut self, depth: usize) {
+        if let Some(held) = self.held_line.take() {
+            self.parse_block_line(&held, false);
+        }
+        self.setext_candidate = None;
+        self.in_paragraph = false;
+        self.close_container_blocks();
+
+        while self.state.block_depth > depth {
+            self.state.exit_block();
+            self.events.push(ParseEvent::BlockquoteEnd);
+        }
+        while self.state.block_depth < depth {
+            self.state.enter_block(BlockType::Quote);
+            self.events.push(ParseEvent::BlockquoteStart {
+                depth: self.state.block_depth,
+            });
+        }
+    }
+
+    /// End the code block, list and table open in the current container.
+    ///
+    /// A list whose item holds the quote being opened or closed belongs to
+    /// the outer container and stays open.
+    fn close_container_blocks(&mut self) {
+        if self.state.is_in_code() {
+            self.events.push(ParseEvent::CodeBlockEnd);
+            self.state.exit_code_block();
+            self.code_fence = None;
+        }
+        self.list_pending_close = false;
+        if self.state.in_list && !self.item_quote {
+            self.exit_list_context();
+        }
+        self.end_table();
+    }
+
     /// Exit block contexts when encountering plain text.
     /// Note: `resolve_pending_list_close()` is always called before this method,
     /// so we only need to handle the non-deferred list close here.
     fn exit_block_contexts(&mut self) {
-        if self.state.in_list {
+        if self.state.in_list && self.list_continuation.is_none() {
             self.exit_list_context();
         }
-        if self.table_state.is_some() {
-            self.table_state = None;
-            self.state.in_table = None;
-            self.events.push(ParseEvent::TableEnd);
-        }
+        self.end_table();
     }
 
     // =========================================================================
@@ -460,10 +1080,13 @@ impl Parser {
             && let Some(caps) = CODE_FENCE_END_RE.captures(line)
         {
             let end_fence = caps.get(1).map(|m| m.as_str()).unwrap_or("");
-            // Match fence type: ``` with ```, </pre> with <pre>
-            let matches = (fence.starts_with('`') && end_fence.starts_with('`'))
-                || (fence.starts_with('~') && end_fence.starts_with('~'))
-                || (fence == "<pre>" && end_fence == "</pre>");
+            // A fence closes on the same character, at least as many of
+            // them as opened it; </pre> closes <pre>
+            let matches = if fence == "<pre>" {
+                end_fence == "</pre>"
+            } else {
+                end_fence.starts_with(&fence[..1]) && end_fence.len() >= fence.len()
+            };
 
             if matches {
                 self.events.push(ParseEvent::CodeBlockEnd);
@@ -486,11 +1109,17 @@ impl Parser {
             }
         }
 
-        // Emit code line (strip indent for space-indented code)
+        // Emit code line (strip indent for space-indented code, and up to
+        // the fence's indent for fenced code)
         let code_line = if self.state.in_code == Some(Code::Spaces) {
             line.chars().skip(4).collect()
         } else {
-            line.to_string()
+            let indent = line
+                .chars()
+                .take(self.state.code_indent)
+                .take_while(|c| c.is_whitespace())
+                .count();
+            line.chars().skip(indent).collect()
         };
 
         self.events.push(ParseEvent::CodeBlockLine(code_line));
@@ -499,20 +1128,31 @@ impl Parser {
     fn try_parse_code_fence(&mut self, line: &str) -> bool {
         if let Some(caps) = CODE_FENCE_RE.captures(line) {
             let fence = caps.get(1).map(|m| m.as_str()).unwrap_or("```");
-            let lang = caps.get(2).map(|m| m.as_str()).filter(|s| !s.is_empty());
+            let info_string = caps.get(2).map_or("", |m| m.as_str());
+            if fence == "<pre>" && !self.extensions.pre_fences {
+                return false;
+            }
+            // A backtick info string can't contain backticks: ```a``` is
+            // inline code
+            if fence.starts_with('`') && info_string.contains('`') {
+                return false;
+            }
+            let (lang, info) = CodeInfo::parse(info_string);
             let indent = line.chars().take_while(|c| c.is_whitespace()).count();
 
+            // Code lines are dedented by the fence's indent; inside a list
+            // item they still carry the item's indentation too
             self.code_fence = Some(fence.to_string());
-            self.state.code_indent = indent;
+            self.state.code_indent = indent + self.continued_item_column();
             self.state.enter_code_block(
                 Code::Backtick,
-                lang.map(|s| s.to_string())
-                    .or_else(|| Some("text".to_string())),
+                lang.clone().or_else(|| Some("text".to_string())),
             );
 
             self.events.push(ParseEvent::CodeBlockStart {
-                language: lang.map(|s| s.to_string()),
+                language: lang,
                 indent,
+                info,
             });
             true
         } else {
@@ -537,6 +1177,7 @@ impl Parser {
             self.events.push(ParseEvent::CodeBlockStart {
                 language: Some("text".to_string()),
                 indent: 4,
+                info: CodeInfo::default(),
             });
             // Also emit the first line (skip 4 chars, not bytes)
             let code_line: String = line.chars().skip(4).collect();
@@ -548,7 +1189,7 @@ impl Parser {
     }
 
     // =========================================================================
-    // Think/blockquote parsing
+    // Think block parsing
     // =========================================================================
 
     fn parse_in_think_block(&mut self, line: &str) {
@@ -563,62 +1204,32 @@ impl Parser {
         }
     }
 
-    fn try_parse_block(&mut self, line: &str) -> bool {
-        if let Some(caps) = BLOCK_RE.captures(line) {
-            let marker = caps.get(1).map(|m| m.as_str()).unwrap_or("");
-            let content = caps.get(3).map(|m| m.as_str()).unwrap_or("");
-
-            // Check for think block variants
-            if marker.contains("think") {
-                if marker.contains('/') {
-                    // End of think block
-                    if self.state.block_type == Some(BlockType::Think) {
-                        self.events.push(ParseEvent::ThinkBlockEnd);
-                        self.state.exit_block();
-                    }
-                    return true;
-                } else {
-                    // Start of think block
-                    self.state.enter_block(BlockType::Think);
-                    self.events.push(ParseEvent::ThinkBlockStart);
-                    if !content.trim().is_empty() {
-                        self.events
-                            .push(ParseEvent::ThinkBlockLine(content.to_string()));
-                    }
-                    return true;
-                }
-            }
-
-            // Regular blockquote
-            let depth = marker.matches('>').count();
-            if depth > 0 {
-                if self.state.block_depth != depth {
-                    if depth > self.state.block_depth {
-                        for _ in self.state.block_depth..depth {
-                            self.state.enter_block(BlockType::Quote);
-                        }
-                        self.events.push(ParseEvent::BlockquoteStart { depth });
-                    } else {
-                        for _ in depth..self.state.block_depth {
-                            self.state.exit_block();
-                        }
-                    }
-                }
-                self.events
-                    .push(ParseEvent::BlockquoteLine(content.to_string()));
-                return true;
-            }
+    fn try_parse_think(&mut self, line: &str) -> bool {
+        if !self.extensions.think_blocks {
+            return false;
         }
+        let Some(caps) = THINK_RE.captures(line) else {
+            return false;
+        };
+        let marker = caps.get(1).map(|m| m.as_str()).unwrap_or("");
+        let content = caps.get(2).map(|m| m.as_str()).unwrap_or("");
 
-        // End blockquote if we were in one and this line doesn't continue it
-        if self.state.block_depth > 0 && self.state.block_type == Some(BlockType::Quote) {
-            while self.state.block_depth > 0 {
+        if marker.contains('/') {
+            // End of think block
+            if self.state.block_type == Some(BlockType::Think) {
+                self.events.push(ParseEvent::ThinkBlockEnd);
                 self.state.exit_block();
             }
-            self.events.push(ParseEvent::BlockquoteEnd);
+        } else {
+            // Start of think block
+            self.state.enter_block(BlockType::Think);
+            self.events.push(ParseEvent::ThinkBlockStart);
+            if !content.trim().is_empty() {
+                self.events
+                    .push(ParseEvent::ThinkBlockLine(content.to_string()));
+            }
         }
-
-        false
+        true
     }
 
     // =========================================================================
@@ -631,16 +1242,76 @@ impl Parser {
             let content = caps.get(2).map(|m| m.as_str()).unwrap_or("");
             let level = hashes.len().min(6) as u8;
 
-            self.events.push(ParseEvent::Heading {
-                level,
-                content: content.to_string(),
-            });
+            let content = self.parse_spans(content);
+            self.events.push(ParseEvent::Heading { level, content });
             true
         } else {
             false
         }
     }
 
+    fn try_parse_html_block(&mut self, line: &str) -> bool {
+        let Some(block) = html::parse_html_block(line) else {
+            return false;
+        };
+        match block {
+            HtmlBlock::DetailsStart(summary) => {
+                self.close_container_blocks();
+                self.details_depth += 1;
+                self.events.push(ParseEvent::DetailsStart);
+                if let Some(summary) = summary {
+                    let summary = self.parse_spans(&summary);
+                    self.events.push(ParseEvent::DetailsSummary(summary));
+                }
+            }
+            HtmlBlock::Summary(summary) => {
+                let summary = self.parse_spans(&summary);
+                self.events.push(ParseEvent::DetailsSummary(summary));
+            }
+            // A stray closing tag is left to the inline HTML policy
+            HtmlBlock::DetailsEnd if self.details_depth == 0 => return false,
+            HtmlBlock::DetailsEnd => {
+                self.close_container_blocks();
+                self.details_depth -= 1;
+                self.events.push(ParseEvent::DetailsEnd);
+            }
+        }
+        true
+    }
+
+    fn try_parse_link_definition(&mut self, line: &str) -> bool {
+        let Some((label, definition)) = parse_link_definition(line) else {
+            return false;
+        };
+        if self.inline_parser.define_link(&label, definition.clone()) {
+            let key = normalize_link_label(&label);
+            if let Some(pos) = self.deferred_links.iter().position(|l| *l == key) {
+                self.deferred_links.remove(pos);
+                self.events.push(ParseEvent::LinkDefinition {
+                    label,
+                    url: definition.url,
+                    title: definition.title,
+                });
+            }
+        }
+        true
+    }
+
+    fn try_parse_footnote_definition(&mut self, line: &str) -> bool {
+        if !self.extensions.footnotes {
+            return false;
+        }
+        let Some(caps) = FOOTNOTE_DEF_RE.captures(line) else {
+            return false;
+        };
+        let content = self.parse_spans(caps[2].trim_end());
+        self.events.push(ParseEvent::FootnoteDefinition {
+            label: caps[1].to_string(),
+            content,
+        });
+        true
+    }
+
     fn try_parse_hr(&mut self, line: &str) -> bool {
         if HR_RE.is_match(line.trim()) {
             self.events.push(ParseEvent::HorizontalRule);
@@ -659,6 +1330,9 @@ impl Parser {
             // Use character count, not byte length, for proper multi-byte whitespace handling
             let indent = indent_str.chars().count();
             let bullet = ListBullet::parse(bullet_str).unwrap_or(ListBullet::Dash);
+            if bullet == ListBullet::PlusExpand && !self.extensions.expandable_bullets {
+                return false;
+            }
 
             // Update list_indent_text (width of bullet + space) - use char count
             self.state.list_indent_text = bullet_str.chars().count();
@@ -670,7 +1344,7 @@ impl Parser {
             };
 
             // Pop items with greater or equal indent (for same-level items)
-            while let Some((stack_indent, _)) = self.state.list_item_stack.last() {
+            while let Some((stack_indent, _, _)) = self.state.list_item_stack.last() {
                 if *stack_indent > indent {
                     self.state.pop_list();
                 } else {
@@ -678,29 +1352,66 @@ impl Parser {
                 }
             }
 
+            // A different kind of list, or a different delimiter, at the
+            // same indent is a new list
+            let delimiter = bullet.delimiter();
+            if let Some(&(stack_indent, stack_type, stack_delimiter)) =
+                self.state.list_item_stack.last()
+                && stack_indent == indent
+                && (stack_type != list_type || stack_delimiter != delimiter)
+            {
+                self.state.pop_list();
+            }
+
             // Push new level if indented further than current, or if stack is empty
             let need_push = self
                 .state
                 .list_item_stack
                 .last()
-                .map(|(i, _)| indent > *i)
+                .map(|(i, _, _)| indent > *i)
                 .unwrap_or(true);
 
             if need_push {
-                self.state.push_list(indent, list_type);
+                match bullet.number() {
+                    // An ordered list counts from its first item's number
+                    Some(start) => self.state.push_ordered_list(indent, start, delimiter),
+                    None => self.state.push_list(indent, list_type, delimiter),
+                }
             }
 
+            // Continuation lines of this item start at its content column
+            let column = line[..caps.get(3).map_or(line.len(), |m| m.start())]
+                .chars()
+                .count();
+            self.item_columns.truncate(self.state.list_depth() - 1);
+            self.item_columns.push(column);
+            self.list_continuation = None;
+
             // For ordered lists, get the next number
-            let final_bullet = if let ListBullet::Ordered(_) = bullet {
-                ListBullet::Ordered(self.state.next_list_number().unwrap_or(1))
-            } else {
-                bullet
+            let final_bullet = match bullet.number() {
+                Some(number) => bullet.with_number(self.state.next_list_number().unwrap_or(number)),
+                None => bullet,
+            };
+
+            // GFM task list items: "- [ ] todo" / "1. [x] done"
+            let task = TASK_RE
+                .captures(content)
+                .filter(|_| self.extensions.task_lists);
+            let (content, checked) = match task {
+                Some(task) => (
+                    task.get(2).map(|m| m.as_str()).unwrap_or(""),
+                    Some(&task[1] != " "),
+                ),
+                None => (content, None),
             };
 
+            self.in_paragraph = !content.trim().is_empty();
+            let content = self.parse_spans(content);
             self.events.push(ParseEvent::ListItem {
                 indent,
                 bullet: final_bullet,
-                content: content.to_string(),
+                content,
+                checked,
             });
             true
         } else {
@@ -712,76 +1423,359 @@ impl Parser {
         while self.state.in_list {
             self.state.pop_list();
         }
+        self.item_columns.clear();
+        self.list_continuation = None;
         self.events.push(ParseEvent::ListEnd);
     }
 
-    fn try_parse_table(&mut self, line: &str) -> bool {
-        if let Some(caps) = TABLE_ROW_RE.captures(line) {
-            let inner = caps.get(1).map(|m| m.as_str()).unwrap_or("");
+    /// Strip the indentation of the list item a line continues, if any.
+    ///
+    /// A line indented at least to an open item's content column belongs
+    /// to that item: deeper list levels end, and the blocks parsed from the
+    /// rest of the line follow a [`ParseEvent::ListContinuation`]. Any
+    /// other line that isn't a list item or lazy paragraph text ends a list
+    /// whose item content was being continued.
+    fn continue_list_item(&mut self, line: &str) -> Option<String> {
+        if !self.state.in_list || LIST_ITEM_RE.is_match(line) {
+            return None;
+        }
 
-            // Check if this is a separator row
-            if TABLE_SEP_RE.is_match(inner) && self.table_state == Some(TableState::Header) {
-                self.table_state = Some(TableState::Body);
-                self.state.in_table = Some(Code::Body);
-                self.events.push(ParseEvent::TableSeparator);
-                return true;
+        let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+        let Some(level) = self.item_columns.iter().rposition(|&c| c <= indent) else {
+            let lazy = self.in_paragraph && !starts_block(line, &self.extensions);
+            if self.list_continuation.is_some() && !lazy {
+                self.list_pending_close = false;
+                self.exit_list_context();
             }
+            return None;
+        };
 
-            let cells: Vec<String> = inner.split('|').map(|s| s.trim().to_string()).collect();
+        while self.state.list_depth() > level + 1 {
+            self.state.pop_list();
+        }
+        self.item_columns.truncate(level + 1);
+        self.list_pending_close = false;
+        if self.list_continuation != Some(level) {
+            self.list_continuation = Some(level);
+            let (indent, _, _) = self.state.list_item_stack[level];
+            self.events.push(ParseEvent::ListContinuation { indent });
+        }
+        Some(line.chars().skip(self.item_columns[level]).collect())
+    }
 
-            match self.table_state {
-                None => {
-                    // First row is header
-                    self.table_state = Some(TableState::Header);
-                    self.state.in_table = Some(Code::Header);
-                    self.events.push(ParseEvent::TableHeader(cells));
-                }
-                Some(TableState::Header) => {
-                    // If we see another row before separator, it's still header
-                    // (some tables have multi-line headers)
-                    self.events.push(ParseEvent::TableHeader(cells));
-                }
-                Some(TableState::Body) => {
-                    self.events.push(ParseEvent::TableRow(cells));
-                }
+    /// Strip the item indentation from a line that opens or continues a
+    /// quote nested in an open list item.
+    ///
+    /// Only top-level lists can hold such a quote; the item is continued
+    /// as for any other indented line.
+    fn nested_quote_line(&mut self, line: &str) -> Option<String> {
+        if !self.state.in_list
+            || self.state.is_in_code()
+            || (self.state.block_depth > 0 && !self.item_quote)
+        {
+            return None;
+        }
+        let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+        let level = self.item_columns.iter().rposition(|&c| c <= indent)?;
+        let rest: String = line.chars().skip(self.item_columns[level]).collect();
+        if !rest.trim_start().starts_with('>') {
+            return None;
+        }
+        self.continue_list_item(line)
+    }
+
+    /// Continue the paragraph of the innermost list item with this line.
+    fn continue_item_paragraph(&mut self) {
+        let level = self.state.list_depth() - 1;
+        self.list_pending_close = false;
+        if self.list_continuation != Some(level) {
+            self.list_continuation = Some(level);
+            let (indent, _, _) = self.state.list_item_stack[level];
+            self.events.push(ParseEvent::ListContinuation { indent });
+        }
+    }
+
+    /// Content column of the list item being continued, or 0.
+    fn continued_item_column(&self) -> usize {
+        self.list_continuation
+            .map_or(0, |level| self.item_columns[level])
+    }
+
+    fn try_parse_table(&mut self, line: &str, allow_candidate: bool) -> bool {
+        if !self.extensions.tables {
+            return false;
+        }
+        let Some(cells) = split_table_row(line) else {
+            self.end_table();
+            return false;
+        };
+
+        match self.table_state {
+            Some(TableState::Header) if is_delimiter_row(&cells) => {
+                self.table_state = Some(TableState::Body);
+                self.state.in_table = Some(Code::Body);
+                let alignments = self.fit_columns(cells.iter().map(|c| Alignment::parse(c)));
+                self.events.push(ParseEvent::TableSeparator(alignments));
+            }
+            Some(TableState::Header) => {
+                // If we see another row before separator, it's still header
+                // (some tables have multi-line headers)
+                let cells = self.parse_cells(self.fit_columns(cells));
+                self.events.push(ParseEvent::TableHeader(cells));
+            }
+            Some(TableState::Body) => {
+                let cells = self.parse_cells(self.fit_columns(cells));
+                self.events.push(ParseEvent::TableRow(cells));
+            }
+            None if has_outer_pipes(line) => {
+                // A row with both outer pipes starts a table right away
+                self.table_state = Some(TableState::Header);
+                self.state.in_table = Some(Code::Header);
+                self.table_columns = cells.len();
+                let cells = self.parse_cells(cells);
+                self.events.push(ParseEvent::TableHeader(cells));
             }
-            return true;
+            None if allow_candidate => {
+                // Without outer pipes, only a delimiter row on the next
+                // line makes this a table header
+                self.held_line = Some(line.to_string());
+            }
+            None => return false,
+        }
+        true
+    }
+
+    /// Whether `line` is another row of the open table rather than the
+    /// start of some other block.
+    fn continues_table(&self, line: &str) -> bool {
+        let other_blocks = Extensions {
+            tables: false,
+            ..self.extensions
+        };
+        split_table_row(line).is_some()
+            && !LIST_ITEM_RE.is_match(line)
+            && !starts_block(line, &other_blocks)
+    }
+
+    /// Start a table if `line` is a delimiter row matching the held header.
+    fn try_start_table(&mut self, header: &str, line: &str) -> bool {
+        if !self.extensions.tables {
+            return false;
+        }
+        let (Some(header), Some(delimiter)) = (split_table_row(header), split_table_row(line))
+        else {
+            return false;
+        };
+        if !is_delimiter_row(&delimiter) || delimiter.len() != header.len() {
+            return false;
         }
 
-        // End table if we were in one
+        self.table_state = Some(TableState::Body);
+        self.state.in_table = Some(Code::Body);
+        self.table_columns = header.len();
+        let alignments = delimiter.iter().map(|c| Alignment::parse(c)).collect();
+        let header = self.parse_cells(header);
+        self.events.push(ParseEvent::TableHeader(header));
+        self.events.push(ParseEvent::TableSeparator(alignments));
+        true
+    }
+
+    /// Pad or truncate a row to the header's column count.
+    fn fit_columns<T: Default>(&self, cells: impl IntoIterator<Item = T>) -> Vec<T> {
+        let mut cells: Vec<T> = cells.into_iter().take(self.table_columns).collect();
+        cells.resize_with(self.table_columns, T::default);
+        cells
+    }
+
+    /// End the current table, if any.
+    fn end_table(&mut self) {
         if self.table_state.is_some() {
             self.table_state = None;
             self.state.in_table = None;
             self.events.push(ParseEvent::TableEnd);
         }
+    }
+
+    /// Parse the inline content of a block into spans, deferring the
+    /// reference links whose definitions have not been seen yet.
+    fn parse_spans(&mut self, text: &str) -> Vec<InlineSpan> {
+        let spans = self.inline_parser.parse(text);
+        self.defer_link_references(&spans);
+        spans
+    }
+
+    /// Remember the labels of unresolved references in `spans`, so their
+    /// definitions are emitted when they arrive.
+    fn defer_link_references(&mut self, spans: &[InlineSpan]) {
+        for span in spans {
+            match &span.element {
+                InlineElement::LinkReference { label, .. } => {
+                    let key = normalize_link_label(label);
+                    if !self.deferred_links.contains(&key) {
+                        self.deferred_links.push(key);
+                    }
+                }
+                InlineElement::Highlight(content) => self.defer_link_references(content),
+                _ => {}
+            }
+        }
+    }
 
-        false
+    /// Parse each cell of a table row into spans.
+    fn parse_cells(&mut self, cells: Vec<String>) -> Vec<Vec<InlineSpan>> {
+        cells.iter().map(|cell| self.parse_spans(cell)).collect()
     }
 
     fn parse_inline_content(&mut self, line: &str) {
-        let elements = self.inline_parser.parse(line);
-
-        for element in elements {
-            let event = match element {
-                InlineElement::Text(s) => ParseEvent::Text(s),
-                InlineElement::Bold(s) => ParseEvent::Bold(s),
-                InlineElement::Italic(s) => ParseEvent::Italic(s),
-                InlineElement::BoldItalic(s) => ParseEvent::BoldItalic(s),
-                InlineElement::Underline(s) => ParseEvent::Underline(s),
-                InlineElement::Strikeout(s) => ParseEvent::Strikeout(s),
-                InlineElement::Code(s) => ParseEvent::InlineCode(s),
-                InlineElement::Link { text, url } => ParseEvent::Link { text, url },
-                InlineElement::Image { alt, url } => ParseEvent::Image { alt, url },
-                InlineElement::Footnote(s) => ParseEvent::Footnote(s),
-            };
-            self.events.push(event);
+        let spans = self.parse_spans(line);
+
+        for span in spans {
+            match flat_event(&span) {
+                Some(event) => self.events.push(event),
+                // Consecutive spans share one event
+                None => match self.events.last_mut() {
+                    Some(ParseEvent::InlineElements(spans)) => spans.push(span),
+                    _ => self.events.push(ParseEvent::InlineElements(vec![span])),
+                },
+            }
         }
 
         self.events.push(ParseEvent::Newline);
     }
 
+    /// Feed raw input bytes and return events for every completed line.
+    ///
+    /// Input may be split anywhere, including in the middle of a line or a
+    /// UTF-8 codepoint; the remainder is kept in [`ParseState::buffer`]
+    /// until more bytes arrive or [`finalize`](Parser::finalize) is called.
+    /// The events produced do not depend on how the input was chunked.
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<ParseEvent> {
+        self.state.push_bytes(bytes);
+
+        let mut all_events = Vec::new();
+        while let Some(line) = self.state.take_line() {
+            all_events.extend(self.parse_line(&line));
+        }
+        all_events
+    }
+
+    /// The buffered partial line that has not been terminated yet.
+    pub fn partial_line(&self) -> String {
+        self.state.partial_line()
+    }
+
+    /// Provisional events for the buffered partial line.
+    ///
+    /// Returns the events the partial line would produce if it were
+    /// complete, without changing parser state. Useful for drawing the
+    /// in-progress line; the final events come from a later
+    /// [`feed`](Parser::feed) once its newline arrives.
+    pub fn provisional_events(&mut self) -> Vec<ParseEvent> {
+        let line = self.partial_line();
+        self.preview_line(&line)
+    }
+
+    /// Events `line` would produce as the next line, without changing
+    /// parser state.
+    pub fn preview_line(&mut self, line: &str) -> Vec<ParseEvent> {
+        if line.is_empty() {
+            return Vec::new();
+        }
+        let snapshot = self.snapshot(line);
+        let events = self.parse_line(line);
+        self.restore(snapshot);
+        events
+    }
+
+    /// Save the state that parsing `line` may change.
+    fn snapshot(&mut self, line: &str) -> LineSnapshot {
+        // The input buffer is left alone by parsing, so keep it out of the copy
+        let buffer = std::mem::take(&mut self.state.buffer);
+        let state = self.state.clone();
+        self.state.buffer = buffer;
+
+        let new_link = parse_link_definition(line)
+            .map(|(label, _)| label)
+            .filter(|label| self.inline_parser.link_definition(label).is_none());
+        LineSnapshot {
+            state,
+            code_fence: self.code_fence.clone(),
+            table_state: self.table_state,
+            table_columns: self.table_columns,
+            held_line: self.held_line.clone(),
+            in_paragraph: self.in_paragraph,
+            setext_candidate: self.setext_candidate.clone(),
+            prev_was_empty: self.prev_was_empty,
+            list_pending_close: self.list_pending_close,
+            item_columns: self.item_columns.clone(),
+            list_continuation: self.list_continuation,
+            item_quote: self.item_quote,
+            deferred_links: self.deferred_links.clone(),
+            in_html_comment: self.in_html_comment,
+            details_depth: self.details_depth,
+            front_matter: self.front_matter.clone(),
+            new_link,
+        }
+    }
+
+    /// Put back the state saved by [`snapshot`](Parser::snapshot).
+    fn restore(&mut self, snapshot: LineSnapshot) {
+        let buffer = std::mem::take(&mut self.state.buffer);
+        self.state = snapshot.state;
+        self.state.buffer = buffer;
+        self.code_fence = snapshot.code_fence;
+        self.table_state = snapshot.table_state;
+        self.table_columns = snapshot.table_columns;
+        self.held_line = snapshot.held_line;
+        self.in_paragraph = snapshot.in_paragraph;
+        self.setext_candidate = snapshot.setext_candidate;
+        self.prev_was_empty = snapshot.prev_was_empty;
+        self.list_pending_close = snapshot.list_pending_close;
+        self.item_columns = snapshot.item_columns;
+        self.list_continuation = snapshot.list_continuation;
+        self.item_quote = snapshot.item_quote;
+        self.deferred_links = snapshot.deferred_links;
+        self.in_html_comment = snapshot.in_html_comment;
+        self.details_depth = snapshot.details_depth;
+        self.front_matter = snapshot.front_matter;
+        if let Some(label) = snapshot.new_link {
+            self.inline_parser.undefine_link(&label);
+        }
+    }
+
+    /// Collect the link reference definitions of a whole document.
+    ///
+    /// Call before parsing when the full input is available (e.g. a file),
+    /// so reference links resolve even when their definition comes later.
+    /// Definitions inside fenced code blocks are ignored.
+    pub fn scan_link_definitions(&mut self, content: &str) {
+        let mut fence: Option<&str> = None;
+        for line in content.lines() {
+            if let Some(caps) = CODE_FENCE_RE.captures(line) {
+                let marker = caps.get(1).map_or("", |m| m.as_str());
+                match fence {
+                    None => fence = Some(marker),
+                    Some(open) if marker.starts_with(open) && caps[2].trim().is_empty() => {
+                        fence = None
+                    }
+                    Some(_) => {}
+                }
+                continue;
+            }
+            if fence.is_none()
+                && let Some((label, definition)) = parse_link_definition(line)
+            {
+                self.inline_parser.define_link(&label, definition);
+            }
+        }
+    }
+
     /// Parse a complete document.
+    ///
+    /// Link reference definitions are collected up front, so reference
+    /// links resolve wherever their definition appears.
     pub fn parse_document(&mut self, content: &str) -> Vec<ParseEvent> {
+        self.scan_link_definitions(content);
         let mut all_events = Vec::new();
         for line in content.lines() {
             all_events.extend(self.parse_line(line));
@@ -792,50 +1786,339 @@ impl Parser {
 
     /// Finalize parsing, closing any open blocks.
     pub fn finalize(&mut self) -> Vec<ParseEvent> {
+        // Anything still buffered by `feed` is the last line of input
+        let mut pending = match self.state.take_partial_line() {
+            Some(line) => self.parse_line(&line),
+            None => Vec::new(),
+        };
+
+        // Front matter that never closed was markdown
+        pending.extend(self.abandon_front_matter());
+
         self.events.clear();
 
-        if self.state.is_in_code() {
-            self.events.push(ParseEvent::CodeBlockEnd);
-            self.state.exit_code_block();
-            self.code_fence = None;
+        // A held line with nothing after it is plain text
+        if let Some(held) = self.held_line.take() {
+            self.parse_block_line(&held, false);
         }
+        self.setext_candidate = None;
 
         if self.state.block_type == Some(BlockType::Think) {
             self.events.push(ParseEvent::ThinkBlockEnd);
             self.state.exit_block();
         }
 
-        if self.state.block_depth > 0 {
-            self.events.push(ParseEvent::BlockquoteEnd);
-            while self.state.block_depth > 0 {
-                self.state.exit_block();
+        // Close open blocks from the inside out, then any details boxes
+        // and quotes
+        if self.details_depth > 0 {
+            self.close_container_blocks();
+            for _ in 0..std::mem::take(&mut self.details_depth) {
+                self.events.push(ParseEvent::DetailsEnd);
             }
         }
+        self.set_quote_depth(0);
 
-        self.list_pending_close = false;
-        if self.state.in_list {
-            self.exit_list_context();
-        }
-
-        if self.table_state.is_some() {
-            self.table_state = None;
-            self.state.in_table = None;
-            self.events.push(ParseEvent::TableEnd);
+        for label in std::mem::take(&mut self.deferred_links) {
+            self.events
+                .push(ParseEvent::UnresolvedLinkReference { label });
         }
 
-        self.take_events()
+        pending.extend(self.take_events());
+        pending
     }
 
     /// Reset the parser to initial state.
     pub fn reset(&mut self) {
+        // Settings outlive the document
+        let (code_spaces, links, images) =
+            (self.state.code_spaces, self.state.links, self.state.images);
         self.state = ParseState::new();
+        self.state.code_spaces = code_spaces;
+        self.state.links = links;
+        self.state.images = images;
         self.inline_parser.reset();
         self.code_fence = None;
         self.table_state = None;
+        self.table_columns = 0;
+        self.held_line = None;
+        self.in_paragraph = false;
+        self.setext_candidate = None;
         self.events.clear();
         self.prev_was_empty = false;
         self.list_pending_close = false;
+        self.item_columns.clear();
+        self.list_continuation = None;
+        self.inline_parser.clear_link_definitions();
+        self.deferred_links.clear();
+        self.in_html_comment = false;
+        self.details_depth = 0;
+        self.front_matter = None;
+    }
+}
+
+// =============================================================================
+// Code fence info strings
+// =============================================================================
+
+/// Split an info string into words, keeping quoted values and `{...}`
+/// groups together. Quotes are removed.
+fn info_words(info: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote = None;
+    let mut in_braces = false;
+
+    for c in info.trim().chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'') => quote = Some(c),
+            (None, c) if c.is_whitespace() && !in_braces => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            (None, c) if c.is_whitespace() => {}
+            (None, c) => {
+                in_braces = match c {
+                    '{' => true,
+                    '}' => false,
+                    _ => in_braces,
+                };
+                word.push(c);
+            }
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Parse line ranges such as `1,3-5`. Invalid entries are skipped.
+fn parse_line_ranges(ranges: &str) -> Vec<RangeInclusive<usize>> {
+    ranges
+        .split(',')
+        .filter_map(|part| {
+            let part = part.trim();
+            let (start, end) = part.split_once('-').unwrap_or((part, part));
+            let start = start.trim().parse().ok()?;
+            let end = end.trim().parse().ok()?;
+            (start <= end).then_some(start..=end)
+        })
+        .collect()
+}
+
+// =============================================================================
+// Blockquote markers
+// =============================================================================
+
+/// Split up to `max_depth` leading `>` markers off a line.
+///
+/// Returns the quote depth and the content after the markers. A marker may
+/// be followed by one space, which belongs to the marker; further
+/// indentation is kept as part of the content.
+fn split_quote_prefix(line: &str, max_depth: usize) -> (usize, &str) {
+    let mut depth = 0;
+    let mut rest = line;
+    while depth < max_depth {
+        let Some(after) = rest.trim_start().strip_prefix('>') else {
+            break;
+        };
+        rest = after.strip_prefix([' ', '\t']).unwrap_or(after);
+        depth += 1;
+    }
+    (depth, rest)
+}
+
+// =============================================================================
+// Setext headings
+// =============================================================================
+
+/// The flat inline event for a span, if one can express it.
+fn flat_event(span: &InlineSpan) -> Option<ParseEvent> {
+    let style = span.style;
+    let event = match &span.element {
+        InlineElement::Text(s) => {
+            let s = s.clone();
+            let bold_italic = InlineStyle::BOLD.union(InlineStyle::ITALIC);
+            return match style {
+                InlineStyle::PLAIN => Some(ParseEvent::Text(s)),
+                InlineStyle::BOLD => Some(ParseEvent::Bold(s)),
+                InlineStyle::ITALIC => Some(ParseEvent::Italic(s)),
+                InlineStyle::UNDERLINE => Some(ParseEvent::Underline(s)),
+                InlineStyle::STRIKEOUT => Some(ParseEvent::Strikeout(s)),
+                _ if style == bold_italic => Some(ParseEvent::BoldItalic(s)),
+                _ => None,
+            };
+        }
+        InlineElement::Code(s) => ParseEvent::InlineCode(s.clone()),
+        InlineElement::Link { content, url } => ParseEvent::Link {
+            text: unformatted_text(content)?,
+            url: url.clone(),
+        },
+        InlineElement::LinkReference { content, label } => ParseEvent::LinkReference {
+            text: unformatted_text(content)?,
+            label: label.clone(),
+        },
+        InlineElement::Image { alt, url } => ParseEvent::Image {
+            alt: alt.clone(),
+            url: url.clone(),
+        },
+        InlineElement::Footnote(s) => ParseEvent::Footnote(s.clone()),
+        InlineElement::HardBreak => ParseEvent::HardBreak,
+        InlineElement::Kbd(s) => ParseEvent::Kbd(s.clone()),
+        // No flat events for the extensions
+        InlineElement::Highlight(_)
+        | InlineElement::Superscript(_)
+        | InlineElement::Subscript(_)
+        | InlineElement::Emoji(_) => return None,
+    };
+    style.is_plain().then_some(event)
+}
+
+/// The text of spans that are all unformatted text.
+fn unformatted_text(spans: &[InlineSpan]) -> Option<String> {
+    spans
+        .iter()
+        .map(|span| match &span.element {
+            InlineElement::Text(s) if span.style.is_plain() => Some(s.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Alert kind and title from an alert marker line.
+fn parse_alert_marker(line: &str) -> Option<(AlertKind, Option<String>)> {
+    let caps = ALERT_RE.captures(line)?;
+    let kind = AlertKind::parse(&caps[1])?;
+    let title = caps[2].trim();
+    Some((kind, (!title.is_empty()).then(|| title.to_string())))
+}
+
+/// Heading level for a setext underline, if `line` is one.
+fn setext_level(line: &str) -> Option<u8> {
+    let caps = SETEXT_RE.captures(line)?;
+    Some(if caps[1].starts_with('=') { 1 } else { 2 })
+}
+
+/// Whether `line` starts a block rather than continuing a paragraph.
+fn starts_block(line: &str, extensions: &Extensions) -> bool {
+    let line = line.trim_start();
+    CODE_FENCE_RE
+        .captures(line)
+        .is_some_and(|caps| extensions.pre_fences || &caps[1] != "<pre>")
+        || HEADING_RE.is_match(line)
+        || HR_RE.is_match(line.trim_end())
+        || (extensions.think_blocks && THINK_RE.is_match(line))
+        || html::parse_html_block(line).is_some()
+        || LINK_DEF_RE.is_match(line)
+        || (extensions.footnotes && FOOTNOTE_DEF_RE.is_match(line))
+        || (extensions.tables && split_table_row(line).is_some())
+}
+
+// =============================================================================
+// Link reference definitions
+// =============================================================================
+
+/// Parse a `[label]: url "title"` line.
+fn parse_link_definition(line: &str) -> Option<(String, LinkDefinition)> {
+    let caps = LINK_DEF_RE.captures(line)?;
+    let label = caps[1].trim();
+    if label.is_empty() {
+        return None;
     }
+    let url = caps.get(2).or_else(|| caps.get(3))?.as_str().to_string();
+    let title = (4..=6)
+        .find_map(|i| caps.get(i))
+        .map(|m| m.as_str().to_string());
+    Some((label.to_string(), LinkDefinition { url, title }))
+}
+
+// =============================================================================
+// Table rows
+// =============================================================================
+
+/// Split a table row into trimmed cells following GFM rules.
+///
+/// Outer pipes are optional, `\|` is a literal pipe and pipes inside code
+/// spans do not separate cells. Returns `None` if the line has no cell
+/// separator at all.
+fn split_table_row(line: &str) -> Option<Vec<String>> {
+    let line = line.trim();
+    let chars: Vec<char> = line.chars().collect();
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut code_run: Option<usize> = None;
+    let mut trailing_pipe = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        trailing_pipe = false;
+        match chars[i] {
+            '\\' if chars.get(i + 1) == Some(&'|') => {
+                cell.push('|');
+                i += 2;
+                continue;
+            }
+            '`' => {
+                let run = chars[i..].iter().take_while(|&&c| c == '`').count();
+                match code_run {
+                    Some(open) if open == run => code_run = None,
+                    None if has_backtick_run(&chars[i + run..], run) => code_run = Some(run),
+                    _ => {}
+                }
+                cell.extend(&chars[i..i + run]);
+                i += run;
+                continue;
+            }
+            '|' if code_run.is_none() => {
+                cells.push(std::mem::take(&mut cell).trim().to_string());
+                trailing_pipe = true;
+            }
+            c => cell.push(c),
+        }
+        i += 1;
+    }
+
+    if cells.is_empty() {
+        return None;
+    }
+    if !trailing_pipe {
+        cells.push(cell.trim().to_string());
+    }
+    if line.starts_with('|') {
+        cells.remove(0);
+    }
+    if cells.is_empty() { None } else { Some(cells) }
+}
+
+/// Whether `chars` contains a backtick run of exactly `len`.
+fn has_backtick_run(chars: &[char], len: usize) -> bool {
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i] == '`' {
+            let run = chars[i..].iter().take_while(|&&c| c == '`').count();
+            if run == len {
+                return true;
+            }
+            i += run;
+        } else {
+            i += 1;
+        }
+    }
+    false
+}
+
+/// Whether a row has both a leading and an unescaped trailing pipe.
+fn has_outer_pipes(line: &str) -> bool {
+    let line = line.trim();
+    line.len() > 1 && line.starts_with('|') && line.ends_with('|') && !line.ends_with("\\|")
+}
+
+/// Whether every cell is a delimiter cell such as `---` or `:---:`.
+fn is_delimiter_row(cells: &[String]) -> bool {
+    !cells.is_empty() && cells.iter().all(|c| TABLE_DELIM_RE.is_match(c))
 }
 
 // =============================================================================
@@ -846,144 +2129,1048 @@ impl Parser {
 mod tests {
     use super::*;
 
+    /// Inline markdown parsed into spans.
+    fn spans(text: &str) -> Vec<InlineSpan> {
+        InlineParser::new().parse(text)
+    }
+
+    /// Table cells parsed into spans.
+    fn row(cells: &[&str]) -> Vec<Vec<InlineSpan>> {
+        cells.iter().map(|cell| spans(cell)).collect()
+    }
+
+    /// A parser that emits each line as it arrives, retracting setext
+    /// headings rather than holding paragraph lines back.
+    fn streaming_parser() -> Parser {
+        let mut parser = Parser::new();
+        parser.set_setext_lookahead(false);
+        parser
+    }
+
     #[test]
     fn test_parse_heading() {
         let mut parser = Parser::new();
         let events = parser.parse_line("# Hello World");
         assert!(events.iter().any(|e| matches!(
-            e, ParseEvent::Heading { level: 1, content } if content == "Hello World"
+            e, ParseEvent::Heading { level: 1, content } if plain_text(content) == "Hello World"
+        )));
+    }
+
+    #[test]
+    fn test_parse_code_block() {
+        let mut parser = Parser::new();
+        let e1 = parser.parse_line("```rust");
+        assert!(e1.iter().any(
+            |e| matches!(e, ParseEvent::CodeBlockStart { language: Some(l), .. } if l == "rust")
+        ));
+        let e2 = parser.parse_line("let x = 1;");
+        assert!(
+            e2.iter()
+                .any(|e| matches!(e, ParseEvent::CodeBlockLine(s) if s == "let x = 1;"))
+        );
+        let e3 = parser.parse_line("```");
+        assert!(e3.iter().any(|e| matches!(e, ParseEvent::CodeBlockEnd)));
+    }
+
+    #[test]
+    fn test_code_fence_closes_on_same_char_and_length() {
+        let mut parser = Parser::new();
+        parser.parse_line("````markdown");
+        // A shorter fence is content
+        assert_eq!(
+            parser.parse_line("```rust"),
+            vec![ParseEvent::CodeBlockLine("```rust".to_string())]
+        );
+        assert_eq!(
+            parser.parse_line("```"),
+            vec![ParseEvent::CodeBlockLine("```".to_string())]
+        );
+        // So is a fence of the other character
+        assert_eq!(
+            parser.parse_line("~~~~"),
+            vec![ParseEvent::CodeBlockLine("~~~~".to_string())]
+        );
+        assert_eq!(parser.parse_line("`````"), vec![ParseEvent::CodeBlockEnd]);
+    }
+
+    #[test]
+    fn test_backticks_in_info_string_are_not_a_fence() {
+        let mut parser = Parser::new();
+        let events = parser.parse_line("```inline```");
+        assert!(
+            !events
+                .iter()
+                .any(|e| matches!(e, ParseEvent::CodeBlockStart { .. }))
+        );
+    }
+
+    #[test]
+    fn test_parse_code_info_string() {
+        let mut parser = Parser::new();
+        let events = parser.parse_line(r#"```rust title="src/main.rs" {1,3-5} linenos"#);
+        assert_eq!(
+            events,
+            vec![ParseEvent::CodeBlockStart {
+                language: Some("rust".to_string()),
+                indent: 0,
+                info: CodeInfo {
+                    title: Some("src/main.rs".to_string()),
+                    highlight: vec![1..=1, 3..=5],
+                    line_numbers: true,
+                },
+            }]
+        );
+    }
+
+    #[test]
+    fn test_code_info_parse() {
+        let (lang, info) = CodeInfo::parse("python{2, 4-6}");
+        assert_eq!(lang.as_deref(), Some("python"));
+        assert!(info.is_highlighted(2) && info.is_highlighted(5));
+        assert!(!info.is_highlighted(3) && !info.is_highlighted(7));
+
+        let (lang, info) = CodeInfo::parse("title='my file.txt' showLineNumbers");
+        assert_eq!(lang, None);
+        assert_eq!(info.title.as_deref(), Some("my file.txt"));
+        assert!(info.line_numbers);
+
+        let (lang, info) = CodeInfo::parse("");
+        assert_eq!(lang, None);
+        assert_eq!(info, CodeInfo::default());
+
+        // Malformed ranges are ignored
+        let (_, info) = CodeInfo::parse("js {x,5-2,7}");
+        assert_eq!(info.highlight, vec![7..=7]);
+    }
+
+    #[test]
+    fn test_parse_pre_tag() {
+        let mut parser = Parser::new();
+        let e1 = parser.parse_line("<pre>");
+        assert!(
+            e1.iter()
+                .any(|e| matches!(e, ParseEvent::CodeBlockStart { .. }))
+        );
+        let e2 = parser.parse_line("code");
+        assert!(e2.iter().any(|e| matches!(e, ParseEvent::CodeBlockLine(_))));
+        let e3 = parser.parse_line("</pre>");
+        assert!(e3.iter().any(|e| matches!(e, ParseEvent::CodeBlockEnd)));
+    }
+
+    #[test]
+    fn test_space_indented_code() {
+        let mut parser = Parser::new();
+        parser.set_code_spaces(true);
+        parser.parse_line(""); // Empty line first
+        let events = parser.parse_line("    let x = 1;");
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, ParseEvent::CodeBlockStart { .. }))
+        );
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, ParseEvent::CodeBlockLine(s) if s == "let x = 1;"))
+        );
+    }
+
+    #[test]
+    fn test_empty_line_collapsing() {
+        let mut parser = streaming_parser();
+        let e1 = parser.parse_line("");
+        assert!(e1.iter().any(|e| matches!(e, ParseEvent::EmptyLine)));
+        let e2 = parser.parse_line("");
+        assert!(e2.is_empty()); // Collapsed
+        let e3 = parser.parse_line("text");
+        assert!(!e3.is_empty());
+        let e4 = parser.parse_line("");
+        assert!(e4.iter().any(|e| matches!(e, ParseEvent::EmptyLine)));
+    }
+
+    #[test]
+    fn test_parse_think_block_unicode() {
+        let mut parser = Parser::new();
+        let e1 = parser.parse_line("◁think▷");
+        assert!(e1.iter().any(|e| matches!(e, ParseEvent::ThinkBlockStart)));
+    }
+
+    #[test]
+    fn test_parse_list() {
+        let mut parser = Parser::new();
+        let events = parser.parse_line("- Item one");
+        assert!(events.iter().any(|e| matches!(
+            e, ParseEvent::ListItem { bullet: ListBullet::Dash, content, .. } if plain_text(content) == "Item one"
+        )));
+    }
+
+    #[test]
+    fn test_parse_nested_list() {
+        let mut parser = Parser::new();
+        parser.parse_line("- Item 1");
+        let e2 = parser.parse_line("  - Nested");
+        // Nested item should have indent 2
+        assert!(
+            e2.iter()
+                .any(|e| matches!(e, ParseEvent::ListItem { indent: 2, .. }))
+        );
+    }
+
+    #[test]
+    fn test_parse_ordered_list_numbering() {
+        let mut parser = Parser::new();
+        parser.parse_line("1. First");
+        let e2 = parser.parse_line("2. Second");
+        // Should auto-number
+        assert!(e2.iter().any(|e| matches!(
+            e,
+            ParseEvent::ListItem {
+                bullet: ListBullet::Ordered(2),
+                ..
+            }
         )));
     }
 
     #[test]
-    fn test_parse_code_block() {
+    fn test_ordered_list_start_and_delimiter() {
+        let bullets = |doc: &str| -> Vec<ListBullet> {
+            Parser::new()
+                .parse_document(doc)
+                .into_iter()
+                .filter_map(|e| match e {
+                    ParseEvent::ListItem { bullet, .. } => Some(bullet),
+                    _ => None,
+                })
+                .collect()
+        };
+        // The first number starts the count
+        assert_eq!(
+            bullets("5. Five\n1. Six\n9. Seven"),
+            vec![
+                ListBullet::Ordered(5),
+                ListBullet::Ordered(6),
+                ListBullet::Ordered(7)
+            ]
+        );
+        assert_eq!(
+            bullets("1) One\n   3) Three\n   3) Four\n2) Two"),
+            vec![
+                ListBullet::OrderedParen(1),
+                ListBullet::OrderedParen(3),
+                ListBullet::OrderedParen(4),
+                ListBullet::OrderedParen(2),
+            ]
+        );
+        // An ordered list after a bullet list at the same level starts over
+        assert_eq!(
+            bullets("- a\n- b\n\n1. one\n2. two"),
+            vec![
+                ListBullet::Dash,
+                ListBullet::Dash,
+                ListBullet::Ordered(1),
+                ListBullet::Ordered(2)
+            ]
+        );
+        // So does one with a different delimiter
+        assert_eq!(
+            bullets("5. five\n6. six\n\n1) one\n2) two"),
+            vec![
+                ListBullet::Ordered(5),
+                ListBullet::Ordered(6),
+                ListBullet::OrderedParen(1),
+                ListBullet::OrderedParen(2)
+            ]
+        );
+        assert_eq!(ListBullet::parse("12)"), Some(ListBullet::OrderedParen(12)));
+        assert!(!LIST_ITEM_RE.is_match("1234567890. too long"));
+    }
+
+    #[test]
+    fn test_parse_setext_heading_retracts() {
+        let mut parser = streaming_parser();
+        let events = parser.parse_line("Title");
+        assert_eq!(
+            events,
+            vec![ParseEvent::Text("Title".to_string()), ParseEvent::Newline]
+        );
+        let events = parser.parse_line("=====");
+        assert_eq!(
+            events,
+            vec![ParseEvent::SetextHeading {
+                level: 1,
+                content: spans("Title"),
+            }]
+        );
+
+        parser.parse_line("");
+        parser.parse_line("Subtitle");
+        let events = parser.parse_line("---");
+        assert_eq!(
+            events,
+            vec![ParseEvent::SetextHeading {
+                level: 2,
+                content: spans("Subtitle"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_setext_underline_needs_one_line_paragraph() {
+        let mut parser = Parser::new();
+        // After an empty line, --- is still a horizontal rule
+        parser.parse_line("");
+        assert_eq!(parser.parse_line("---"), vec![ParseEvent::HorizontalRule]);
+
+        parser.parse_line("line one");
+        parser.parse_line("line two");
+        assert_eq!(parser.parse_line("---"), vec![ParseEvent::HorizontalRule]);
+
+        // Not after a heading or list item either
+        parser.parse_line("# Heading");
+        assert_eq!(parser.parse_line("---"), vec![ParseEvent::HorizontalRule]);
+        parser.parse_line("- item");
+        assert!(
+            !parser
+                .parse_line("---")
+                .iter()
+                .any(|e| matches!(e, ParseEvent::SetextHeading { .. }))
+        );
+    }
+
+    #[test]
+    fn test_parse_setext_heading_with_lookahead() {
+        let mut parser = Parser::new();
+        assert!(parser.parse_line("Title").is_empty());
+        assert_eq!(
+            parser.parse_line("==="),
+            vec![ParseEvent::Heading {
+                level: 1,
+                content: spans("Title"),
+            }]
+        );
+
+        // A held line that isn't underlined is emitted with the next line
+        assert!(parser.parse_line("First").is_empty());
+        let events = parser.parse_line("Second");
+        assert_eq!(
+            events,
+            vec![
+                ParseEvent::Text("First".to_string()),
+                ParseEvent::Newline,
+                ParseEvent::Text("Second".to_string()),
+                ParseEvent::Newline,
+            ]
+        );
+
+        // And flushed by finalize at the end of input
+        assert!(parser.parse_line("").len() == 1);
+        assert!(parser.parse_line("Last").is_empty());
+        let events = parser.finalize();
+        assert_eq!(events[0], ParseEvent::Text("Last".to_string()));
+    }
+
+    #[test]
+    fn test_pipe_row_then_underline_is_setext_heading() {
+        let mut parser = Parser::new();
+        assert!(parser.parse_line("a | b").is_empty());
+        assert_eq!(
+            parser.parse_line("---"),
+            vec![ParseEvent::Heading {
+                level: 2,
+                content: spans("a | b"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_code_block_inside_list_item() {
+        let mut parser = Parser::new();
+        let events = parser
+            .parse_document("1. Install\n\n   ```bash\n   cargo install sd\n   ```\n\n2. Run");
+        let item = |n, content: &str| ParseEvent::ListItem {
+            indent: 0,
+            bullet: ListBullet::Ordered(n),
+            content: spans(content),
+            checked: None,
+        };
+        assert_eq!(
+            events,
+            vec![
+                item(1, "Install"),
+                ParseEvent::EmptyLine,
+                ParseEvent::ListContinuation { indent: 0 },
+                ParseEvent::CodeBlockStart {
+                    language: Some("bash".to_string()),
+                    indent: 0,
+                    info: CodeInfo::default(),
+                },
+                ParseEvent::CodeBlockLine("cargo install sd".to_string()),
+                ParseEvent::CodeBlockEnd,
+                ParseEvent::EmptyLine,
+                item(2, "Run"),
+                ParseEvent::ListEnd,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_continuation_paragraph_in_nested_list() {
+        let mut parser = Parser::new();
+        let events =
+            parser.parse_document("1. First\n   - detail\n\n   More about first\n2. Second");
+        assert!(events.contains(&ParseEvent::ListContinuation { indent: 0 }));
+        assert!(events.contains(&ParseEvent::Text("More about first".to_string())));
+        assert!(events.contains(&ParseEvent::ListItem {
+            indent: 0,
+            bullet: ListBullet::Ordered(2),
+            content: spans("Second"),
+            checked: None,
+        }));
+        assert_eq!(
+            events.iter().filter(|e| **e == ParseEvent::ListEnd).count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_quote_in_list_item() {
+        let mut parser = Parser::new();
+        let events = parser.parse_document("- item\n  > quoted\n- next");
+        let item = |content: &str| ParseEvent::ListItem {
+            indent: 0,
+            bullet: ListBullet::Dash,
+            content: spans(content),
+            checked: None,
+        };
+        assert_eq!(
+            events,
+            vec![
+                item("item"),
+                ParseEvent::ListContinuation { indent: 0 },
+                ParseEvent::BlockquoteStart { depth: 1 },
+                ParseEvent::Text("quoted".to_string()),
+                ParseEvent::Newline,
+                ParseEvent::BlockquoteEnd,
+                item("next"),
+                ParseEvent::ListEnd,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unindented_line_ends_continued_list() {
+        let mut parser = streaming_parser();
+        parser.parse_line("- item");
+        parser.parse_line("");
+        parser.parse_line("  more");
+        let events = parser.parse_line("# Heading");
+        assert_eq!(events[0], ParseEvent::ListEnd);
+
+        // Also after an empty line: the list closes before the heading
+        parser.parse_line("- item");
+        parser.parse_line("");
+        let events = parser.parse_line("# Heading");
+        assert_eq!(events[0], ParseEvent::ListEnd);
+    }
+
+    #[test]
+    fn test_list_item_lazy_continuation() {
+        let mut parser = Parser::new();
+        let events = parser.parse_document("1. First\n   continued\nlazy\n2. Second\nlazy too");
+        let item = |n, content: &str| ParseEvent::ListItem {
+            indent: 0,
+            bullet: ListBullet::Ordered(n),
+            content: spans(content),
+            checked: None,
+        };
+        assert_eq!(
+            events,
+            vec![
+                item(1, "First"),
+                ParseEvent::ListContinuation { indent: 0 },
+                ParseEvent::Text("continued".to_string()),
+                ParseEvent::Newline,
+                ParseEvent::Text("lazy".to_string()),
+                ParseEvent::Newline,
+                item(2, "Second"),
+                ParseEvent::ListContinuation { indent: 0 },
+                ParseEvent::Text("lazy too".to_string()),
+                ParseEvent::Newline,
+                ParseEvent::ListEnd,
+            ]
+        );
+
+        // Only paragraph text is lazy: a heading still ends the list
+        let mut parser = Parser::new();
+        parser.parse_line("- item");
+        parser.parse_line("lazy");
+        let events = parser.parse_line("# Heading");
+        assert_eq!(events[0], ParseEvent::ListEnd);
+    }
+
+    #[test]
+    fn test_hard_line_breaks() {
+        let mut parser = Parser::new();
+        let events = parser.parse_document("one  \ntwo\\\nthree");
+        assert_eq!(
+            events,
+            vec![
+                ParseEvent::Text("one".to_string()),
+                ParseEvent::HardBreak,
+                ParseEvent::Newline,
+                ParseEvent::Text("two".to_string()),
+                ParseEvent::HardBreak,
+                ParseEvent::Newline,
+                ParseEvent::Text("three".to_string()),
+                ParseEvent::Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fenced_code_dedented_by_fence_indent() {
+        let mut parser = Parser::new();
+        parser.parse_line("text");
+        parser.parse_line("  ```");
+        assert_eq!(
+            parser.parse_line("    indented"),
+            vec![ParseEvent::CodeBlockLine("  indented".to_string())]
+        );
+        assert_eq!(
+            parser.parse_line(" x"),
+            vec![ParseEvent::CodeBlockLine("x".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_parse_task_list_items() {
+        let mut parser = Parser::new();
+        let events = parser.parse_line("- [ ] Write tests");
+        assert_eq!(
+            events,
+            vec![ParseEvent::ListItem {
+                indent: 0,
+                bullet: ListBullet::Dash,
+                content: spans("Write tests"),
+                checked: Some(false),
+            }]
+        );
+
+        let events = parser.parse_line("  - [X] Nested done");
+        assert!(matches!(
+            &events[0],
+            ParseEvent::ListItem { indent: 2, checked: Some(true), content, .. } if plain_text(content) == "Nested done"
+        ));
+
+        let events = parser.parse_line("1. [x] Ordered");
+        assert!(matches!(
+            &events[0],
+            ParseEvent::ListItem {
+                bullet: ListBullet::Ordered(1),
+                checked: Some(true),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_list_item_brackets_not_task() {
+        let mut parser = Parser::new();
+        for line in ["- [link](url)", "- [y] maybe", "- [ ]not a task"] {
+            let events = parser.parse_line(line);
+            assert!(
+                matches!(&events[0], ParseEvent::ListItem { checked: None, .. }),
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_blockquote() {
+        let mut parser = streaming_parser();
+        let events = parser.parse_line("> Quote text");
+        assert_eq!(
+            events,
+            vec![
+                ParseEvent::BlockquoteStart { depth: 1 },
+                ParseEvent::Text("Quote text".to_string()),
+                ParseEvent::Newline,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_nested_blockquote() {
         let mut parser = Parser::new();
-        let e1 = parser.parse_line("```rust");
-        assert!(e1.iter().any(
-            |e| matches!(e, ParseEvent::CodeBlockStart { language: Some(l), .. } if l == "rust")
-        ));
-        let e2 = parser.parse_line("let x = 1;");
+        let events = parser.parse_line(">> Nested quote");
         assert!(
-            e2.iter()
-                .any(|e| matches!(e, ParseEvent::CodeBlockLine(s) if s == "let x = 1;"))
+            events
+                .iter()
+                .any(|e| matches!(e, ParseEvent::BlockquoteStart { depth: 2 }))
         );
-        let e3 = parser.parse_line("```");
-        assert!(e3.iter().any(|e| matches!(e, ParseEvent::CodeBlockEnd)));
     }
 
     #[test]
-    fn test_parse_pre_tag() {
+    fn test_blockquote_depth_changes_emit_events() {
         let mut parser = Parser::new();
-        let e1 = parser.parse_line("<pre>");
-        assert!(
-            e1.iter()
-                .any(|e| matches!(e, ParseEvent::CodeBlockStart { .. }))
+        let events = parser.parse_document("> one\n> > two\n> three\nfour");
+        let quote_events: Vec<_> = events
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    ParseEvent::BlockquoteStart { .. } | ParseEvent::BlockquoteEnd
+                )
+            })
+            .collect();
+        assert_eq!(
+            quote_events,
+            vec![
+                &ParseEvent::BlockquoteStart { depth: 1 },
+                &ParseEvent::BlockquoteStart { depth: 2 },
+                &ParseEvent::BlockquoteEnd,
+                &ParseEvent::BlockquoteEnd,
+            ]
         );
-        let e2 = parser.parse_line("code");
-        assert!(e2.iter().any(|e| matches!(e, ParseEvent::CodeBlockLine(_))));
-        let e3 = parser.parse_line("</pre>");
-        assert!(e3.iter().any(|e| matches!(e, ParseEvent::CodeBlockEnd)));
+        let four = events
+            .iter()
+            .position(|e| *e == ParseEvent::Text("four".to_string()))
+            .unwrap();
+        assert_eq!(events[four - 1], ParseEvent::BlockquoteEnd);
     }
 
     #[test]
-    fn test_space_indented_code() {
+    fn test_parse_blocks_inside_blockquote() {
         let mut parser = Parser::new();
-        parser.set_code_spaces(true);
-        parser.parse_line(""); // Empty line first
-        let events = parser.parse_line("    let x = 1;");
-        assert!(
-            events
-                .iter()
-                .any(|e| matches!(e, ParseEvent::CodeBlockStart { .. }))
-        );
-        assert!(
-            events
-                .iter()
-                .any(|e| matches!(e, ParseEvent::CodeBlockLine(s) if s == "let x = 1;"))
+        let events =
+            parser.parse_document("> - item\n>   - nested\n>\n> ```rust\n> let x = 1;\n> ```\n");
+        assert_eq!(
+            events,
+            vec![
+                ParseEvent::BlockquoteStart { depth: 1 },
+                ParseEvent::ListItem {
+                    indent: 0,
+                    bullet: ListBullet::Dash,
+                    content: spans("item"),
+                    checked: None,
+                },
+                ParseEvent::ListItem {
+                    indent: 2,
+                    bullet: ListBullet::Dash,
+                    content: spans("nested"),
+                    checked: None,
+                },
+                ParseEvent::EmptyLine,
+                ParseEvent::ListEnd,
+                ParseEvent::CodeBlockStart {
+                    language: Some("rust".to_string()),
+                    indent: 0,
+                    info: CodeInfo::default(),
+                },
+                ParseEvent::CodeBlockLine("let x = 1;".to_string()),
+                ParseEvent::CodeBlockEnd,
+                ParseEvent::BlockquoteEnd,
+            ]
         );
     }
 
     #[test]
-    fn test_empty_line_collapsing() {
+    fn test_parse_table_inside_blockquote() {
         let mut parser = Parser::new();
-        let e1 = parser.parse_line("");
-        assert!(e1.iter().any(|e| matches!(e, ParseEvent::EmptyLine)));
-        let e2 = parser.parse_line("");
-        assert!(e2.is_empty()); // Collapsed
-        let e3 = parser.parse_line("text");
-        assert!(!e3.is_empty());
-        let e4 = parser.parse_line("");
-        assert!(e4.iter().any(|e| matches!(e, ParseEvent::EmptyLine)));
+        let events = parser.parse_document("> a | b\n> --|--\n> 1 | 2\n\ntext");
+        assert_eq!(events[0], ParseEvent::BlockquoteStart { depth: 1 });
+        assert_eq!(events[1], ParseEvent::TableHeader(row(&["a", "b"])));
+        assert_eq!(events[3], ParseEvent::TableRow(row(&["1", "2"])));
+        // The table ends before its quote does
+        assert_eq!(events[4], ParseEvent::TableEnd);
+        assert_eq!(events[5], ParseEvent::BlockquoteEnd);
     }
 
     #[test]
-    fn test_parse_think_block_unicode() {
+    fn test_leaving_blockquote_closes_code_block() {
         let mut parser = Parser::new();
-        let e1 = parser.parse_line("◁think▷");
-        assert!(e1.iter().any(|e| matches!(e, ParseEvent::ThinkBlockStart)));
+        parser.parse_line("> ```");
+        parser.parse_line("> code");
+        let events = parser.parse_line("outside");
+        assert_eq!(
+            &events[..2],
+            &[ParseEvent::CodeBlockEnd, ParseEvent::BlockquoteEnd]
+        );
+
+        // Quote markers inside a top-level code block are code
+        parser.parse_line("```");
+        let events = parser.parse_line("> not a quote");
+        assert_eq!(
+            events,
+            vec![ParseEvent::CodeBlockLine("> not a quote".to_string())]
+        );
     }
 
     #[test]
-    fn test_parse_list() {
+    fn test_split_quote_prefix() {
+        assert_eq!(split_quote_prefix("> text", usize::MAX), (1, "text"));
+        assert_eq!(split_quote_prefix(">> text", usize::MAX), (2, "text"));
+        assert_eq!(split_quote_prefix("> > text", usize::MAX), (2, "text"));
+        assert_eq!(
+            split_quote_prefix(">   - item", usize::MAX),
+            (1, "  - item")
+        );
+        assert_eq!(split_quote_prefix(">> > text", 1), (1, "> > text"));
+        assert_eq!(split_quote_prefix("text", usize::MAX), (0, "text"));
+    }
+
+    #[test]
+    fn test_parse_footnote_definition() {
+        let mut parser = streaming_parser();
+        let events = parser.parse_document("Text[^1].\n\n[^1]: The *note*.\n[^2]:");
+        assert!(events.contains(&ParseEvent::Footnote("¹".to_string())));
+        assert!(events.contains(&ParseEvent::FootnoteDefinition {
+            label: "1".to_string(),
+            content: spans("The *note*."),
+        }));
+        assert!(events.contains(&ParseEvent::FootnoteDefinition {
+            label: "2".to_string(),
+            content: Vec::new(),
+        }));
+        // A reference that merely starts a line is still inline
+        let events = parser.parse_line("[^3] is cited");
+        assert_eq!(events[0], ParseEvent::Footnote("³".to_string()));
+    }
+
+    #[test]
+    fn test_parse_named_footnote() {
+        let mut parser = streaming_parser();
+        let events = parser.parse_document("Text[^note].\n\n[^note]: A named note.");
+        assert!(events.contains(&ParseEvent::Footnote("note".to_string())));
+        assert!(events.contains(&ParseEvent::FootnoteDefinition {
+            label: "note".to_string(),
+            content: spans("A named note."),
+        }));
+    }
+
+    #[test]
+    fn test_parse_link_definition() {
+        let (label, def) = parse_link_definition(r#"[Docs]: https://docs.rs "Docs""#).unwrap();
+        assert_eq!(label, "Docs");
+        assert_eq!(def.url, "https://docs.rs");
+        assert_eq!(def.title.as_deref(), Some("Docs"));
+
+        let (_, def) = parse_link_definition("   [a b]: <./my file.md> (Title)").unwrap();
+        assert_eq!(def.url, "./my file.md");
+        assert_eq!(def.title.as_deref(), Some("Title"));
+
+        assert!(parse_link_definition("[^1]: footnote").is_none());
+        assert!(parse_link_definition("[a]: url trailing words").is_none());
+        assert!(parse_link_definition("[a]:").is_none());
+    }
+
+    #[test]
+    fn test_reference_link_deferred_until_definition() {
+        let mut parser = streaming_parser();
+        let events = parser.parse_line("Read [the guide][Guide] and [this][missing].");
+        assert!(events.contains(&ParseEvent::LinkReference {
+            text: "the guide".to_string(),
+            label: "Guide".to_string(),
+        }));
+
+        let events = parser.parse_line(r#"[guide]: https://example.com/guide "The Guide""#);
+        assert_eq!(
+            events,
+            vec![ParseEvent::LinkDefinition {
+                label: "guide".to_string(),
+                url: "https://example.com/guide".to_string(),
+                title: Some("The Guide".to_string()),
+            }]
+        );
+
+        // Now defined, later references resolve inline
+        let events = parser.parse_line("Again: [guide][]");
+        assert!(events.contains(&ParseEvent::Link {
+            text: "guide".to_string(),
+            url: "https://example.com/guide".to_string(),
+        }));
+
+        // Unused definitions are silent
+        assert!(parser.parse_line("[unused]: /nowhere").is_empty());
+
+        assert_eq!(
+            parser.finalize(),
+            vec![ParseEvent::UnresolvedLinkReference {
+                label: "missing".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_reference_links_deferred_in_blocks() {
+        fn has_reference(spans: &[InlineSpan], label: &str) -> bool {
+            spans.iter().any(|span| {
+                matches!(&span.element, InlineElement::LinkReference { label: l, .. } if l == label)
+            })
+        }
+
         let mut parser = Parser::new();
-        let events = parser.parse_line("- Item one");
+        let mut events = Vec::new();
+        for line in [
+            "[early]: /early",
+            "# [Heading][h] and [early][]",
+            "- [item][i]",
+            "",
+            "| [cell][c] |",
+            "|---|",
+            "| x |",
+            "",
+        ] {
+            events.extend(parser.parse_line(line));
+        }
+
+        let heading = events
+            .iter()
+            .find_map(|e| match e {
+                ParseEvent::Heading { content, .. } => Some(content),
+                _ => None,
+            })
+            .unwrap();
+        assert!(has_reference(heading, "h"));
+        // Already defined, so resolved in place
+        assert!(heading.iter().any(|span| matches!(
+            &span.element,
+            InlineElement::Link { url, .. } if url == "/early"
+        )));
         assert!(events.iter().any(|e| matches!(
-            e, ParseEvent::ListItem { bullet: ListBullet::Dash, content, .. } if content == "Item one"
+            e,
+            ParseEvent::ListItem { content, .. } if has_reference(content, "i")
+        )));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            ParseEvent::TableHeader(cells) if has_reference(&cells[0], "c")
         )));
+
+        for label in ["h", "i"] {
+            assert_eq!(
+                parser.parse_line(&format!("[{}]: /{}", label, label)),
+                vec![ParseEvent::LinkDefinition {
+                    label: label.to_string(),
+                    url: format!("/{}", label),
+                    title: None,
+                }]
+            );
+        }
+        assert_eq!(
+            parser.finalize(),
+            vec![ParseEvent::UnresolvedLinkReference {
+                label: "c".to_string(),
+            }]
+        );
     }
 
     #[test]
-    fn test_parse_nested_list() {
+    fn test_parse_document_resolves_forward_references() {
         let mut parser = Parser::new();
-        parser.parse_line("- Item 1");
-        let e2 = parser.parse_line("  - Nested");
-        // Nested item should have indent 2
-        assert!(
-            e2.iter()
-                .any(|e| matches!(e, ParseEvent::ListItem { indent: 2, .. }))
+        let events = parser.parse_document(
+            "See [the docs][docs].\n\n```\n[docs]: /from-code\n```\n\n[docs]: https://docs.rs",
         );
+        assert!(events.contains(&ParseEvent::Link {
+            text: "the docs".to_string(),
+            url: "https://docs.rs".to_string(),
+        }));
+        assert!(!events.iter().any(|e| matches!(
+            e,
+            ParseEvent::LinkReference { .. }
+                | ParseEvent::LinkDefinition { .. }
+                | ParseEvent::UnresolvedLinkReference { .. }
+        )));
     }
 
     #[test]
-    fn test_parse_ordered_list_numbering() {
+    fn test_parse_details_block() {
         let mut parser = Parser::new();
-        parser.parse_line("1. First");
-        let e2 = parser.parse_line("2. Second");
-        // Should auto-number
-        assert!(e2.iter().any(|e| matches!(
-            e,
-            ParseEvent::ListItem {
-                bullet: ListBullet::Ordered(2),
-                ..
-            }
-        )));
+        let events = parser.parse_document(
+            "<details>\n<summary>Show more</summary>\n\n- hidden\n\n</details>\n<details open><summary>Two</summary>",
+        );
+        let blocks: Vec<_> = events
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    ParseEvent::DetailsStart
+                        | ParseEvent::DetailsSummary(_)
+                        | ParseEvent::DetailsEnd
+                        | ParseEvent::ListItem { .. }
+                        | ParseEvent::ListEnd
+                )
+            })
+            .cloned()
+            .collect();
+        assert!(matches!(
+            blocks.as_slice(),
+            [
+                ParseEvent::DetailsStart,
+                ParseEvent::DetailsSummary(a),
+                ParseEvent::ListItem { .. },
+                ParseEvent::ListEnd,
+                ParseEvent::DetailsEnd,
+                ParseEvent::DetailsStart,
+                ParseEvent::DetailsSummary(b),
+                // Closed at the end of input
+                ParseEvent::DetailsEnd,
+            ] if plain_text(a) == "Show more" && plain_text(b) == "Two"
+        ));
+
+        // Without an open box, </details> is just an unknown tag
+        let events = streaming_parser().parse_line("</details>");
+        assert_eq!(events[0], ParseEvent::Text("</details>".to_string()));
     }
 
     #[test]
-    fn test_parse_blockquote() {
+    fn test_html_comments_hidden() {
+        let mut parser = streaming_parser();
+        assert!(parser.parse_line("<!-- one line -->").is_empty());
+        assert!(parser.parse_line("<!-- spans").is_empty());
+        assert!(parser.parse_line("# not a heading").is_empty());
+        let events = parser.parse_line("--> after");
+        assert_eq!(events[0], ParseEvent::Text("after".to_string()));
+
+        let events = parser.parse_line("Text <!-- inline --> more");
+        assert_eq!(events[0], ParseEvent::Text("Text  more".to_string()));
+    }
+
+    #[test]
+    fn test_parse_alert() {
         let mut parser = Parser::new();
-        let events = parser.parse_line("> Quote text");
+        let events = parser.parse_document(
+            "> [!WARNING]\n> Mind the gap.\n\n> [!tip] Pro tip\n> > [!NOTE]\n\n> [!NOTE]: not a marker",
+        );
+        assert_eq!(
+            events[0],
+            ParseEvent::AlertStart {
+                kind: AlertKind::Warning,
+                title: None,
+                depth: 1,
+            }
+        );
+        assert!(events.contains(&ParseEvent::Text("Mind the gap.".to_string())));
+        assert!(events.contains(&ParseEvent::AlertStart {
+            kind: AlertKind::Tip,
+            title: Some(spans("Pro tip")),
+            depth: 1,
+        }));
+        // Nested quotes can be alerts too
+        assert!(events.contains(&ParseEvent::AlertStart {
+            kind: AlertKind::Note,
+            title: None,
+            depth: 2,
+        }));
+        let starts = events
+            .iter()
+            .filter(|e| {
+                matches!(
+                    e,
+                    ParseEvent::AlertStart { .. } | ParseEvent::BlockquoteStart { .. }
+                )
+            })
+            .count();
+        let ends = events
+            .iter()
+            .filter(|e| matches!(e, ParseEvent::BlockquoteEnd))
+            .count();
+        assert_eq!(starts, ends);
+
+        // Only the first line of a quote can be a marker
+        let events = Parser::new().parse_document("> Quote\n> [!NOTE]");
         assert!(
-            events
+            !events
                 .iter()
-                .any(|e| matches!(e, ParseEvent::BlockquoteLine(s) if s == "Quote text"))
+                .any(|e| matches!(e, ParseEvent::AlertStart { .. }))
         );
     }
 
     #[test]
-    fn test_parse_nested_blockquote() {
+    fn test_parse_front_matter() {
         let mut parser = Parser::new();
-        let events = parser.parse_line(">> Nested quote");
+        let events = parser.parse_document("---\ntitle: Hi\ntags: [a]\n---\n# Body");
+        assert_eq!(
+            events[0],
+            ParseEvent::FrontMatter {
+                format: FrontMatterFormat::Yaml,
+                raw: "title: Hi\ntags: [a]".to_string(),
+            }
+        );
+        assert!(matches!(events[1], ParseEvent::Heading { level: 1, .. }));
+
+        let events = Parser::new().parse_document("+++\ntitle = \"Hi\"\n+++");
+        assert_eq!(
+            events,
+            vec![ParseEvent::FrontMatter {
+                format: FrontMatterFormat::Toml,
+                raw: "title = \"Hi\"".to_string(),
+            }]
+        );
+
+        // Only at the very start of the document
+        let events = Parser::new().parse_document("Intro\n\n---\ntitle: Hi\n---");
+        assert!(
+            !events
+                .iter()
+                .any(|e| matches!(e, ParseEvent::FrontMatter { .. }))
+        );
+    }
+
+    #[test]
+    fn test_leading_rule_is_not_front_matter() {
+        // A rule followed by prose is replayed as markdown
+        let events = Parser::new().parse_document("---\nJust text.");
+        assert_eq!(events[0], ParseEvent::HorizontalRule);
+        assert!(events.contains(&ParseEvent::Text("Just text.".to_string())));
+
+        // So is front matter that never closes
+        let events = Parser::new().parse_document("---\ntitle: Hi");
+        assert_eq!(events[0], ParseEvent::HorizontalRule);
+        assert!(events.contains(&ParseEvent::Text("title: Hi".to_string())));
+    }
+
+    #[test]
+    fn test_front_matter_streaming() {
+        let mut parser = streaming_parser();
+        assert!(parser.feed(b"---\ntitle: Hi\n").is_empty());
+        let events = parser.feed(b"---\nText\n");
+        assert!(matches!(events[0], ParseEvent::FrontMatter { .. }));
+        assert!(events.contains(&ParseEvent::Text("Text".to_string())));
+    }
+
+    #[test]
+    fn test_nested_inline_formatting_events() {
+        let mut parser = streaming_parser();
+        let events = parser.parse_line("Plain **bold `code`** [*em*](u) [x](v)");
+        assert_eq!(events[0], ParseEvent::Text("Plain ".to_string()));
+        assert_eq!(events[1], ParseEvent::Bold("bold ".to_string()));
+        // Spans no flat event can express are grouped
+        let ParseEvent::InlineElements(spans) = &events[2] else {
+            panic!("expected inline elements, got {:?}", events[2]);
+        };
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].style, InlineStyle::BOLD);
+        assert!(matches!(events[4], ParseEvent::InlineElements(_)));
+        assert_eq!(
+            events[6],
+            ParseEvent::Link {
+                text: "x".to_string(),
+                url: "v".to_string(),
+            }
+        );
         assert!(
             events
                 .iter()
-                .any(|e| matches!(e, ParseEvent::BlockquoteStart { depth: 2 }))
+                .all(|e| e.is_inline() || *e == ParseEvent::Newline)
         );
     }
 
     #[test]
     fn test_parse_hr() {
         let mut parser = Parser::new();
+        // A `---` first line could open front matter
+        parser.parse_line("");
         assert!(
             parser
                 .parse_line("---")
@@ -1010,11 +3197,124 @@ mod tests {
         let e1 = parser.parse_line("| A | B | C |");
         assert!(e1.iter().any(|e| matches!(e, ParseEvent::TableHeader(_))));
         let e2 = parser.parse_line("|---|---|---|");
-        assert!(e2.iter().any(|e| matches!(e, ParseEvent::TableSeparator)));
+        assert!(
+            e2.iter()
+                .any(|e| matches!(e, ParseEvent::TableSeparator(_)))
+        );
         let e3 = parser.parse_line("| 1 | 2 | 3 |");
         assert!(e3.iter().any(|e| matches!(e, ParseEvent::TableRow(_))));
     }
 
+    #[test]
+    fn test_parse_table_alignment() {
+        let mut parser = Parser::new();
+        parser.parse_line("| A | B | C | D |");
+        let events = parser.parse_line("|:---|:---:|---:|---|");
+        assert_eq!(
+            events,
+            vec![ParseEvent::TableSeparator(vec![
+                Alignment::Left,
+                Alignment::Center,
+                Alignment::Right,
+                Alignment::None,
+            ])]
+        );
+    }
+
+    fn cells(cells: &[&str]) -> Vec<String> {
+        cells.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_split_table_row() {
+        assert_eq!(split_table_row("| a | b |"), Some(cells(&["a", "b"])));
+        assert_eq!(split_table_row("a | b"), Some(cells(&["a", "b"])));
+        assert_eq!(split_table_row("| a | b"), Some(cells(&["a", "b"])));
+        assert_eq!(split_table_row("a | b |"), Some(cells(&["a", "b"])));
+        assert_eq!(split_table_row("| a | |"), Some(cells(&["a", ""])));
+        assert_eq!(split_table_row("plain text"), None);
+    }
+
+    #[test]
+    fn test_split_table_row_escapes_and_code() {
+        assert_eq!(
+            split_table_row(r"| a \| b | c |"),
+            Some(cells(&["a | b", "c"]))
+        );
+        assert_eq!(
+            split_table_row("| `a|b` | ``x`|`y`` |"),
+            Some(cells(&["`a|b`", "``x`|`y``"]))
+        );
+        // An unclosed backtick does not start a code span
+        assert_eq!(split_table_row("| `a | b |"), Some(cells(&["`a", "b"])));
+    }
+
+    #[test]
+    fn test_parse_table_without_outer_pipes() {
+        let mut parser = Parser::new();
+        assert!(parser.parse_line("Name | Age").is_empty());
+        let events = parser.parse_line(":--- | ---:");
+        assert_eq!(
+            events,
+            vec![
+                ParseEvent::TableHeader(row(&["Name", "Age"])),
+                ParseEvent::TableSeparator(vec![Alignment::Left, Alignment::Right]),
+            ]
+        );
+        let events = parser.parse_line("Alice | 30");
+        assert_eq!(events, vec![ParseEvent::TableRow(row(&["Alice", "30"]))]);
+        let events = parser.parse_line("Done.");
+        assert_eq!(events[0], ParseEvent::TableEnd);
+    }
+
+    #[test]
+    fn test_pipe_line_without_delimiter_is_text() {
+        let mut parser = Parser::new();
+        assert!(parser.parse_line("either a | b").is_empty());
+        let events = parser.parse_line("or c");
+        assert_eq!(
+            events,
+            vec![
+                ParseEvent::Text("either a | b".to_string()),
+                ParseEvent::Newline,
+                ParseEvent::Text("or c".to_string()),
+                ParseEvent::Newline,
+            ]
+        );
+
+        // A held line at the end of input is flushed by finalize
+        let mut parser = Parser::new();
+        parser.parse_line("a | b");
+        let events = parser.finalize();
+        assert_eq!(events[0], ParseEvent::Text("a | b".to_string()));
+    }
+
+    #[test]
+    fn test_parse_table_fits_header_columns() {
+        let mut parser = Parser::new();
+        parser.parse_line("| A | B | C |");
+        let events = parser.parse_line("|---|:-:|");
+        assert_eq!(
+            events,
+            vec![ParseEvent::TableSeparator(vec![
+                Alignment::None,
+                Alignment::Center,
+                Alignment::None,
+            ])]
+        );
+        let events = parser.parse_line("| 1 |");
+        assert_eq!(events, vec![ParseEvent::TableRow(row(&["1", "", ""]))]);
+        let events = parser.parse_line("| 1 | 2 | 3 | 4 |");
+        assert_eq!(events, vec![ParseEvent::TableRow(row(&["1", "2", "3"]))]);
+    }
+
+    #[test]
+    fn test_alignment_parse() {
+        assert_eq!(Alignment::parse(" :-: "), Alignment::Center);
+        assert_eq!(Alignment::parse(":"), Alignment::Left);
+        assert_eq!(Alignment::parse("--"), Alignment::None);
+    }
+
     #[test]
     fn test_parse_think_block() {
         let mut parser = Parser::new();
@@ -1029,6 +3329,48 @@ mod tests {
         assert!(e3.iter().any(|e| matches!(e, ParseEvent::ThinkBlockEnd)));
     }
 
+    #[test]
+    fn test_commonmark_dialect_disables_extensions() {
+        let mut parser = Parser::with_options(ParserOptions::commonmark());
+        let events = parser.parse_document("<think>\n| a | b |\n|---|---|\n- [x] done");
+        assert!(!events.iter().any(|e| matches!(
+            e,
+            ParseEvent::ThinkBlockStart | ParseEvent::TableHeader(_)
+        )));
+        assert!(events.iter().any(|e| matches!(
+            e,
+            ParseEvent::ListItem { content, checked: None, .. } if plain_text(content) == "[x] done"
+        )));
+        assert!(parser.options().code_spaces);
+    }
+
+    #[test]
+    fn test_extensions_apply_to_headings() {
+        let heading = |options: ParserOptions| {
+            let mut parser = Parser::with_options(options);
+            match parser.parse_line("# ==x==").as_slice() {
+                [ParseEvent::Heading { content, .. }] => content.clone(),
+                events => panic!("expected a heading, got {:?}", events),
+            }
+        };
+
+        let options = ParserOptions {
+            extensions: Extensions::commonmark(),
+            ..ParserOptions::classic()
+        };
+        assert_eq!(
+            heading(options),
+            vec![InlineSpan::text("==x==", InlineStyle::PLAIN)]
+        );
+        assert!(matches!(
+            heading(ParserOptions::classic()).as_slice(),
+            [InlineSpan {
+                element: InlineElement::Highlight(_),
+                ..
+            }]
+        ));
+    }
+
     #[test]
     fn test_first_indent_stripping() {
         let mut parser = Parser::new();
@@ -1037,7 +3379,7 @@ mod tests {
         // Should strip the 4 spaces and parse as heading
         assert!(
             e1.iter().any(
-                |e| matches!(e, ParseEvent::Heading { level: 1, content } if content == "Hello")
+                |e| matches!(e, ParseEvent::Heading { level: 1, content } if plain_text(content) == "Hello")
             )
         );
     }
@@ -1074,14 +3416,15 @@ mod tests {
         assert!(
             ParseEvent::Heading {
                 level: 1,
-                content: "x".to_string()
+                content: spans("x")
             }
             .is_block()
         );
         assert!(
             ParseEvent::CodeBlockStart {
                 language: None,
-                indent: 0
+                indent: 0,
+                info: CodeInfo::default(),
             }
             .is_block()
         );
@@ -1100,7 +3443,7 @@ mod tests {
         // Buggy code checks: current_indent (3 bytes) >= first_indent (2 bytes) ✓
         // Then tries: line[2..] which is INSIDE the fullwidth space!
         // Panic: "byte index 2 is not a char boundary; it is inside '　'"
-        let mut parser = Parser::new();
+        let mut parser = streaming_parser();
 
         // First line: 2 ASCII spaces = 2 bytes indent
         let line1 = "  # Hello";
@@ -1226,4 +3569,85 @@ mod tests {
             "Should have exited code block with only 2-char indent"
         );
     }
+
+    #[test]
+    fn test_feed_matches_parse_line() {
+        let input = "# Title\n\n- **bold** item\n```rust\nlet x = \"→\";\n```\n";
+
+        let mut by_lines = Parser::new();
+        let expected = by_lines.parse_document(input);
+
+        let mut by_bytes = Parser::new();
+        let mut events = Vec::new();
+        for byte in input.as_bytes() {
+            events.extend(by_bytes.feed(std::slice::from_ref(byte)));
+        }
+        events.extend(by_bytes.finalize());
+
+        assert_eq!(events, expected);
+    }
+
+    #[test]
+    fn test_feed_holds_partial_line() {
+        let mut parser = Parser::new();
+        assert!(parser.feed(b"# Hel").is_empty());
+        assert_eq!(parser.partial_line(), "# Hel");
+
+        let events = parser.feed(b"lo\n");
+        assert!(events.iter().any(|e| matches!(
+            e,
+            ParseEvent::Heading { level: 1, content } if plain_text(content) == "Hello"
+        )));
+        assert_eq!(parser.partial_line(), "");
+    }
+
+    #[test]
+    fn test_provisional_events_do_not_mutate() {
+        let mut parser = Parser::new();
+        parser.feed(b"```rust");
+
+        let provisional = parser.provisional_events();
+        assert!(
+            provisional
+                .iter()
+                .any(|e| matches!(e, ParseEvent::CodeBlockStart { .. }))
+        );
+        assert!(!parser.state().is_in_code());
+
+        // The real events still come through once the line completes
+        let events = parser.feed(b"\n");
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, ParseEvent::CodeBlockStart { .. }))
+        );
+    }
+
+    #[test]
+    fn test_preview_does_not_define_links() {
+        let mut parser = Parser::new();
+        parser.preview_line("[docs]: /docs");
+
+        let events = parser.parse_line("# See [docs][]");
+        let ParseEvent::Heading { content, .. } = &events[0] else {
+            panic!("expected heading, got {:?}", events);
+        };
+        assert!(content.iter().any(|span| matches!(
+            &span.element,
+            InlineElement::LinkReference { label, .. } if label == "docs"
+        )));
+    }
+
+    #[test]
+    fn test_finalize_flushes_partial_line() {
+        let mut parser = Parser::new();
+        parser.feed(b"- item");
+        let events = parser.finalize();
+        assert!(
+            events
+                .iter()
+                .any(|e| matches!(e, ParseEvent::ListItem { .. }))
+        );
+        assert!(events.iter().any(|e| matches!(e, ParseEvent::ListEnd)));
+    }
 }
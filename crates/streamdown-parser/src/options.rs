@@ -0,0 +1,236 @@
+//! Markdown dialects and parser options.
+//!
+//! The syntax the parser recognises beyond CommonMark is a set of
+//! [`Extensions`]. Three presets cover the common cases:
+//! - [`Dialect::CommonMark`]: no extensions; `__text__` is bold and
+//!   indented lines are code
+//! - [`Dialect::Gfm`]: GitHub Flavored Markdown, adding tables,
+//!   strikethrough, task lists, bare URL autolinks, footnotes, alerts,
+//!   front matter and emoji shortcodes
+//! - [`Dialect::Classic`]: streamdown's own behaviour and the default:
+//!   GFM plus think blocks, `+---` bullets, `<pre>` fences,
+//!   `__underline__`, `==highlight==`, `^superscript^` and `~subscript~`,
+//!   without indented code
+//!
+//! ```
+//! use streamdown_parser::{Parser, ParserOptions};
+//!
+//! let mut parser = Parser::with_options(ParserOptions::commonmark());
+//! assert!(!parser.options().extensions.tables);
+//! ```
+
+use crate::html::HtmlPolicy;
+
+/// A named set of parser options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    /// Strict CommonMark
+    CommonMark,
+    /// GitHub Flavored Markdown
+    Gfm,
+    /// Streamdown's original behaviour
+    #[default]
+    Classic,
+}
+
+impl Dialect {
+    /// The options of this dialect.
+    pub fn options(self) -> ParserOptions {
+        match self {
+            Dialect::CommonMark => ParserOptions::commonmark(),
+            Dialect::Gfm => ParserOptions::gfm(),
+            Dialect::Classic => ParserOptions::classic(),
+        }
+    }
+}
+
+impl std::str::FromStr for Dialect {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "commonmark" | "cm" => Ok(Self::CommonMark),
+            "gfm" | "github" => Ok(Self::Gfm),
+            "classic" | "streamdown" => Ok(Self::Classic),
+            other => Err(format!(
+                "unknown markdown dialect '{}' (expected commonmark, gfm or classic)",
+                other
+            )),
+        }
+    }
+}
+
+/// Syntax recognised on top of CommonMark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Extensions {
+    /// Pipe tables
+    pub tables: bool,
+    /// `~~text~~` strikes text through
+    pub strikethrough: bool,
+    /// `~text~` strikes text through as well
+    pub single_tilde_strikethrough: bool,
+    /// `- [ ]` and `- [x]` list items are checkboxes
+    pub task_lists: bool,
+    /// Bare `https://` and `www.` URLs and email addresses are links
+    pub autolinks: bool,
+    /// `[^1]` references and `[^1]: ...` definitions
+    pub footnotes: bool,
+    /// `> [!NOTE]` quotes are callouts
+    pub alerts: bool,
+    /// A `---` or `+++` block on the first line is front matter
+    pub front_matter: bool,
+    /// `<think>` blocks
+    pub think_blocks: bool,
+    /// `+---` bullets
+    pub expandable_bullets: bool,
+    /// `<pre>` ... `</pre>` fences code blocks
+    pub pre_fences: bool,
+    /// `__text__` underlines instead of bolding
+    pub underline: bool,
+    /// `==text==` is highlighted
+    pub highlight: bool,
+    /// `^text^` is superscript
+    pub superscript: bool,
+    /// `~text~` is subscript, unless it strikes text through
+    pub subscript: bool,
+    /// `:rocket:` shortcodes are emoji
+    pub emoji_shortcodes: bool,
+}
+
+impl Extensions {
+    /// No extensions.
+    pub fn commonmark() -> Self {
+        Self {
+            tables: false,
+            strikethrough: false,
+            single_tilde_strikethrough: false,
+            task_lists: false,
+            autolinks: false,
+            footnotes: false,
+            alerts: false,
+            front_matter: false,
+            think_blocks: false,
+            expandable_bullets: false,
+            pre_fences: false,
+            underline: false,
+            highlight: false,
+            superscript: false,
+            subscript: false,
+            emoji_shortcodes: false,
+        }
+    }
+
+    /// The GitHub Flavored Markdown extensions.
+    pub fn gfm() -> Self {
+        Self {
+            tables: true,
+            strikethrough: true,
+            single_tilde_strikethrough: true,
+            task_lists: true,
+            autolinks: true,
+            footnotes: true,
+            alerts: true,
+            front_matter: true,
+            emoji_shortcodes: true,
+            ..Self::commonmark()
+        }
+    }
+
+    /// Streamdown's extensions: GFM's with `~text~` as subscript, plus its own.
+    pub fn classic() -> Self {
+        Self {
+            single_tilde_strikethrough: false,
+            think_blocks: true,
+            expandable_bullets: true,
+            pre_fences: true,
+            underline: true,
+            highlight: true,
+            superscript: true,
+            subscript: true,
+            ..Self::gfm()
+        }
+    }
+}
+
+impl Default for Extensions {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+/// Options for [`Parser::with_options`](crate::Parser::with_options).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserOptions {
+    /// Syntax recognised on top of CommonMark
+    pub extensions: Extensions,
+    /// Lines indented by four spaces are code
+    pub code_spaces: bool,
+    /// What to do with HTML tags outside the supported subset
+    pub html_policy: HtmlPolicy,
+}
+
+impl ParserOptions {
+    /// Strict CommonMark.
+    pub fn commonmark() -> Self {
+        Self {
+            extensions: Extensions::commonmark(),
+            code_spaces: true,
+            html_policy: HtmlPolicy::default(),
+        }
+    }
+
+    /// GitHub Flavored Markdown.
+    pub fn gfm() -> Self {
+        Self {
+            extensions: Extensions::gfm(),
+            ..Self::commonmark()
+        }
+    }
+
+    /// Streamdown's original behaviour.
+    pub fn classic() -> Self {
+        Self {
+            extensions: Extensions::classic(),
+            code_spaces: false,
+            html_policy: HtmlPolicy::default(),
+        }
+    }
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dialect_from_str() {
+        assert_eq!("CommonMark".parse(), Ok(Dialect::CommonMark));
+        assert_eq!("gfm".parse(), Ok(Dialect::Gfm));
+        assert_eq!("classic".parse(), Ok(Dialect::Classic));
+        assert!("markdown".parse::<Dialect>().is_err());
+    }
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(ParserOptions::default(), Dialect::Classic.options());
+
+        let commonmark = Dialect::CommonMark.options();
+        assert_eq!(commonmark.extensions, Extensions::commonmark());
+        assert!(commonmark.code_spaces);
+
+        let gfm = Dialect::Gfm.options().extensions;
+        assert!(gfm.tables && gfm.single_tilde_strikethrough);
+        assert!(!gfm.underline && !gfm.think_blocks && !gfm.highlight);
+        assert!(gfm.emoji_shortcodes);
+
+        let classic = Dialect::Classic.options();
+        assert!(classic.extensions.underline && classic.extensions.pre_fences);
+        assert!(!classic.extensions.single_tilde_strikethrough);
+        assert!(!classic.code_spaces);
+    }
+}
@@ -3,37 +3,195 @@
 //! This module provides tokenization of markdown inline content,
 //! breaking text into tokens for formatting markers, text, and special elements.
 
+use crate::entities::decode_html_entities;
+use crate::options::Extensions;
 use regex::Regex;
 use std::sync::LazyLock;
 
 /// Regex for tokenizing inline markdown content.
-/// Matches formatting markers (**, *, _, ~~, `) and regular text.
+/// Matches formatting markers (**, *, _, ~~, ~, `) and regular text.
 static INLINE_TOKEN_RE: LazyLock<Regex> = LazyLock::new(|| {
     // Match formatting markers OR runs of non-marker text
     // Order matters: longer markers first
-    Regex::new(r"(```+|~~|\*\*\*|\*\*_|_\*\*|\*\*|\*|___|__|_|`+|[^~_*`]+)").unwrap()
+    Regex::new(r"(```+|~~|~|\*\*\*|\*\*_|_\*\*|\*\*|\*|___|__|_|`+|[^~_*`]+)").unwrap()
 });
 
 /// Regex for matching links: [text](url)
 static LINK_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"\[([^\]]+)\]\(([^\)]+)\)").unwrap());
 
+/// Regex for matching reference links: [text][label] or [text][]
+static REF_LINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]^][^\]]*)\]\[([^\]]*)\]").unwrap());
+
+/// Regex for matching autolinks: `<scheme:...>` or `<user@example.com>`
+static AUTOLINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"<(?:([a-zA-Z][a-zA-Z0-9+.-]{1,31}:[^<>\s\x00-\x1f]*)|([a-zA-Z0-9.!#$%&'*+/=?^_`{|}~-]+@[a-zA-Z0-9](?:[a-zA-Z0-9-]*[a-zA-Z0-9])?(?:\.[a-zA-Z0-9](?:[a-zA-Z0-9-]*[a-zA-Z0-9])?)*))>",
+    )
+    .unwrap()
+});
+
+/// Regex for bare URL and email candidates (GFM autolink extension),
+/// trimmed afterwards by [`trim_autolink`]
+static BARE_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i:https?://|www\.)[^\s<]+|[a-zA-Z0-9._+-]+@[a-zA-Z0-9_-]+(?:\.[a-zA-Z0-9_-]+)+")
+        .unwrap()
+});
+
 /// Regex for matching images: ![alt](url)
 static IMAGE_RE: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"!\[([^\]]*)\]\(([^\)]+)\)").unwrap());
 
-/// Regex for matching footnotes: `[^1]` or `[^1]:`
-static FOOTNOTE_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[\^(\d+)\]:?").unwrap());
+/// Regex for highlighted text: `==text==`
+static HIGHLIGHT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"==([^=\s](?:[^=]*[^=\s])?)==").unwrap());
+
+/// Regex for superscript text: `^text^`, without spaces
+static SUPERSCRIPT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\^([^\s^\[\]]+)\^").unwrap());
+
+/// Regex for subscript text: `~text~`, without spaces
+static SUBSCRIPT_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"~([^\s~]+)~").unwrap());
 
-/// Regex for matching inline code spans: `code` or ``code``
-static CODE_SPAN_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"``[^`]+``|`[^`]+`").unwrap());
+/// Regex for emoji shortcodes: `:rocket:`
+static EMOJI_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r":([a-z0-9_+-]+):").unwrap());
+
+/// Regex for matching footnotes: `[^1]`, `[^note]` or `[^note]:`
+static FOOTNOTE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\^([^\s\[\]]+)\]:?").unwrap());
+
+/// Split a hard line break off the end of a line: two or more spaces, or
+/// a backslash that isn't itself escaped.
+fn split_hard_break(line: &str) -> (&str, bool) {
+    let trimmed = line.trim_end_matches(' ');
+    if trimmed.is_empty() {
+        return (line, false);
+    }
+    if line.len() - trimmed.len() >= 2 {
+        return (trimmed, true);
+    }
+    let backslashes = line.len() - line.trim_end_matches('\\').len();
+    if backslashes % 2 == 1 && backslashes < line.len() {
+        return (&line[..line.len() - 1], true);
+    }
+    (line, false)
+}
 
 /// Find byte ranges of inline code spans in a line.
+///
+/// A run of backticks that isn't escaped opens a span, and the next run
+/// of the same length closes it. Backslashes inside a span are literal.
 fn find_code_regions(line: &str) -> Vec<(usize, usize)> {
-    CODE_SPAN_RE
-        .find_iter(line)
-        .map(|m| (m.start(), m.end()))
-        .collect()
+    let bytes = line.as_bytes();
+    let mut regions = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if bytes.get(i + 1).is_some_and(u8::is_ascii_punctuation) => i += 2,
+            b'`' => {
+                let run = backtick_run(&bytes[i..]);
+                let mut close = i + run;
+                while close < bytes.len() {
+                    let len = backtick_run(&bytes[close..]);
+                    if len == run {
+                        break;
+                    }
+                    close += len.max(1);
+                }
+                if close < bytes.len() {
+                    regions.push((i, close + run));
+                    i = close + run;
+                } else {
+                    i += run;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    regions
+}
+
+/// Length of the run of backticks at the start of `bytes`.
+fn backtick_run(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|&&b| b == b'`').count()
+}
+
+/// Whether the punctuation at `pos` is escaped by an odd number of
+/// backslashes before it.
+fn is_escaped(line: &str, pos: usize) -> bool {
+    let punctuation = line[pos..].starts_with(|c: char| c.is_ascii_punctuation());
+    let backslashes = line[..pos].len() - line[..pos].trim_end_matches('\\').len();
+    punctuation && backslashes % 2 == 1
+}
+
+/// The marker token for a match of [`INLINE_TOKEN_RE`], if it is one.
+fn marker_token(s: &str) -> Option<Token> {
+    let token = match s {
+        "***" => Token::TripleAsterisk,
+        "**" => Token::DoubleAsterisk,
+        "*" => Token::Asterisk,
+        "___" => Token::TripleUnderscore,
+        "__" => Token::DoubleUnderscore,
+        "_" => Token::Underscore,
+        "**_" => Token::DoubleAsteriskUnderscore,
+        "_**" => Token::UnderscoreDoubleAsterisk,
+        "~~" => Token::DoubleTilde,
+        "~" => Token::Tilde,
+        _ if s.chars().all(|c| c == '`') => Token::Backticks(s.len()),
+        _ => return None,
+    };
+    Some(token)
+}
+
+/// Tokenize code as written: markers are split off for the inline
+/// parser to match, but nothing is escaped or decoded.
+fn tokenize_code(text: &str, tokens: &mut Vec<Token>) {
+    for m in INLINE_TOKEN_RE.find_iter(text) {
+        let s = m.as_str();
+        tokens.push(marker_token(s).unwrap_or_else(|| Token::Text(s.to_string())));
+    }
+}
+
+/// Tokenize text outside code spans.
+///
+/// A backslash before ASCII punctuation makes it literal text, and HTML
+/// entities are decoded. Returns the offset of an unclosed run of
+/// backticks, which the inline parser treats as the start of code.
+fn tokenize_text(text: &str, tokens: &mut Vec<Token>) -> Option<usize> {
+    let mut piece_start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let Some(&(_, next)) = chars.peek() else {
+            break;
+        };
+        if c != '\\' || !next.is_ascii_punctuation() {
+            continue;
+        }
+        if let Some(open) = tokenize_unescaped(&text[piece_start..i], tokens) {
+            return Some(piece_start + open);
+        }
+        tokens.push(Token::Text(next.to_string()));
+        chars.next();
+        piece_start = i + 2;
+    }
+
+    tokenize_unescaped(&text[piece_start..], tokens).map(|open| piece_start + open)
+}
+
+/// Tokenize text without escapes, decoding entities in the text between
+/// markers. Stops at the first run of backticks and returns its offset.
+fn tokenize_unescaped(text: &str, tokens: &mut Vec<Token>) -> Option<usize> {
+    for m in INLINE_TOKEN_RE.find_iter(text) {
+        match marker_token(m.as_str()) {
+            Some(Token::Backticks(_)) => return Some(m.start()),
+            Some(token) => tokens.push(token),
+            None => tokens.push(Token::Text(decode_html_entities(m.as_str()))),
+        }
+    }
+    None
 }
 
 /// Token types for inline markdown content.
@@ -69,17 +227,53 @@ pub enum Token {
     /// Tilde pair: ~~
     DoubleTilde,
 
+    /// Single tilde: ~
+    Tilde,
+
     /// Backticks (variable count)
     Backticks(usize),
 
     /// A link: [text](url)
     Link { text: String, url: String },
 
+    /// An autolink or bare URL, whose text is the URL as written and is
+    /// not parsed for formatting
+    Autolink { text: String, url: String },
+
+    /// A reference link: `[text][label]`, or `[text][]` with the text as
+    /// the label
+    RefLink { text: String, label: String },
+
     /// An image: ![alt](url)
     Image { alt: String, url: String },
 
-    /// A footnote reference: `[^1]`
-    Footnote(u32),
+    /// A footnote reference: `[^1]` or `[^note]`, holding the label
+    Footnote(String),
+
+    /// A hard line break: `<br>`, or two spaces or a backslash at the
+    /// end of the line
+    HardBreak,
+
+    /// A key: `<kbd>Ctrl</kbd>`
+    Kbd(String),
+
+    /// Superscript text: `<sup>2</sup>` or `^2^`
+    Superscript(String),
+
+    /// Subscript text: `<sub>2</sub>` or `~2~`
+    Subscript(String),
+
+    /// Highlighted text, not yet parsed for formatting: `==text==`
+    Highlight(String),
+
+    /// An emoji from a shortcode: `:rocket:`
+    Emoji(String),
+
+    /// An HTML comment: `<!-- ... -->`
+    HtmlComment,
+
+    /// Any other HTML tag, as written
+    HtmlTag(String),
 }
 
 impl Token {
@@ -87,7 +281,20 @@ impl Token {
     pub fn is_marker(&self) -> bool {
         !matches!(
             self,
-            Token::Text(_) | Token::Link { .. } | Token::Image { .. } | Token::Footnote(_)
+            Token::Text(_)
+                | Token::Link { .. }
+                | Token::Autolink { .. }
+                | Token::RefLink { .. }
+                | Token::Image { .. }
+                | Token::Footnote(_)
+                | Token::HardBreak
+                | Token::Kbd(_)
+                | Token::Superscript(_)
+                | Token::Subscript(_)
+                | Token::Highlight(_)
+                | Token::Emoji(_)
+                | Token::HtmlComment
+                | Token::HtmlTag(_)
         )
     }
 
@@ -103,6 +310,7 @@ impl Token {
             Token::DoubleAsteriskUnderscore => Some("**_"),
             Token::UnderscoreDoubleAsterisk => Some("_**"),
             Token::DoubleTilde => Some("~~"),
+            Token::Tilde => Some("~"),
             Token::Backticks(_) => {
                 // Can't return dynamic string as static
                 None
@@ -113,12 +321,14 @@ impl Token {
 }
 
 /// Tokenizer for inline markdown content.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Tokenizer {
     /// Whether to process links
     pub process_links: bool,
     /// Whether to process images
     pub process_images: bool,
+    /// Syntax recognised on top of CommonMark
+    pub extensions: Extensions,
 }
 
 impl Tokenizer {
@@ -127,6 +337,7 @@ impl Tokenizer {
         Self {
             process_links: true,
             process_images: true,
+            extensions: Extensions::default(),
         }
     }
 
@@ -135,6 +346,7 @@ impl Tokenizer {
         Self {
             process_links,
             process_images,
+            extensions: Extensions::default(),
         }
     }
 
@@ -142,29 +354,32 @@ impl Tokenizer {
     ///
     /// This extracts links, images, footnotes, and inline formatting markers.
     pub fn tokenize(&self, line: &str) -> Vec<Token> {
+        let (line, hard_break) = split_hard_break(line);
         let mut tokens = Vec::new();
         self.tokenize_with_extractions(line, &mut tokens);
+        if hard_break {
+            tokens.push(Token::HardBreak);
+        }
         tokens
     }
 
     /// Tokenize inline content for formatting markers.
+    ///
+    /// Outside code spans, backslash escapes make ASCII punctuation literal
+    /// and HTML entities are decoded. Code spans are kept as written.
     pub fn tokenize_inline(&self, text: &str, tokens: &mut Vec<Token>) {
-        for cap in INLINE_TOKEN_RE.find_iter(text) {
-            let s = cap.as_str();
-            let token = match s {
-                "***" => Token::TripleAsterisk,
-                "**" => Token::DoubleAsterisk,
-                "*" => Token::Asterisk,
-                "___" => Token::TripleUnderscore,
-                "__" => Token::DoubleUnderscore,
-                "_" => Token::Underscore,
-                "**_" => Token::DoubleAsteriskUnderscore,
-                "_**" => Token::UnderscoreDoubleAsterisk,
-                "~~" => Token::DoubleTilde,
-                _ if s.chars().all(|c| c == '`') => Token::Backticks(s.len()),
-                _ => Token::Text(s.to_string()),
-            };
-            tokens.push(token);
+        let mut last_end = 0;
+        for (start, end) in find_code_regions(text) {
+            if let Some(open) = tokenize_text(&text[last_end..start], tokens) {
+                // An unclosed run of backticks: the rest is code
+                tokenize_code(&text[last_end + open..], tokens);
+                return;
+            }
+            tokenize_code(&text[start..end], tokens);
+            last_end = end;
+        }
+        if let Some(open) = tokenize_text(&text[last_end..], tokens) {
+            tokenize_code(&text[last_end + open..], tokens);
         }
     }
 
@@ -197,8 +412,11 @@ impl Tokenizer {
         if self.process_links {
             for cap in LINK_RE.captures_iter(line) {
                 let m = cap.get(0).unwrap();
-                // Check if this is part of an image (preceded by !)
-                if m.start() > 0 && line.as_bytes().get(m.start() - 1) == Some(&b'!') {
+                // Check if this is part of an image (preceded by an unescaped !)
+                if m.start() > 0
+                    && line.as_bytes().get(m.start() - 1) == Some(&b'!')
+                    && !is_escaped(line, m.start() - 1)
+                {
                     continue;
                 }
                 let text = cap.get(1).map(|m| m.as_str()).unwrap_or("");
@@ -214,22 +432,91 @@ impl Tokenizer {
             }
         }
 
+        // Find all reference links (that aren't part of images)
+        if self.process_links {
+            for cap in REF_LINK_RE.captures_iter(line) {
+                let m = cap.get(0).unwrap();
+                if m.start() > 0
+                    && line.as_bytes().get(m.start() - 1) == Some(&b'!')
+                    && !is_escaped(line, m.start() - 1)
+                {
+                    continue;
+                }
+                let text = cap.get(1).map(|m| m.as_str()).unwrap_or("");
+                let label = match cap.get(2).map(|m| m.as_str()) {
+                    Some(label) if !label.trim().is_empty() => label,
+                    _ => text,
+                };
+                extractions.push((
+                    m.start(),
+                    m.end(),
+                    Token::RefLink {
+                        text: text.to_string(),
+                        label: label.to_string(),
+                    },
+                ));
+            }
+        }
+
+        // Find autolinks and bare URLs
+        if self.process_links {
+            for cap in AUTOLINK_RE.captures_iter(line) {
+                let m = cap.get(0).unwrap();
+                let token = match (cap.get(1), cap.get(2)) {
+                    (Some(uri), _) => Token::Autolink {
+                        text: uri.as_str().to_string(),
+                        url: uri.as_str().to_string(),
+                    },
+                    (None, Some(email)) => Token::Autolink {
+                        text: email.as_str().to_string(),
+                        url: format!("mailto:{}", email.as_str()),
+                    },
+                    (None, None) => continue,
+                };
+                extractions.push((m.start(), m.end(), token));
+            }
+
+            let bare_urls = BARE_URL_RE
+                .find_iter(line)
+                .filter(|_| self.extensions.autolinks);
+            for m in bare_urls {
+                // Autolinks only start at a word boundary
+                let boundary = line[..m.start()]
+                    .chars()
+                    .next_back()
+                    .is_none_or(|c| c.is_whitespace() || "*_~(".contains(c));
+                if !boundary {
+                    continue;
+                }
+                if let Some((len, token)) = bare_autolink(m.as_str()) {
+                    extractions.push((m.start(), m.start() + len, token));
+                }
+            }
+        }
+
+        // Find inline HTML
+        extractions.extend(crate::html::find_inline_html(line));
+
+        self.find_extended_inline(line, &mut extractions);
+
         // Find all footnotes
-        for cap in FOOTNOTE_RE.captures_iter(line) {
+        let footnotes = FOOTNOTE_RE
+            .captures_iter(line)
+            .filter(|_| self.extensions.footnotes);
+        for cap in footnotes {
             let m = cap.get(0).unwrap();
-            if let Some(num_match) = cap.get(1)
-                && let Ok(num) = num_match.as_str().parse::<u32>()
-            {
-                extractions.push((m.start(), m.end(), Token::Footnote(num)));
-            }
+            let label = cap[1].to_string();
+            extractions.push((m.start(), m.end(), Token::Footnote(label)));
         }
 
-        // Filter out extractions inside code spans (backtick-delimited regions)
+        // Filter out escaped extractions, and those inside or crossing
+        // code spans (backtick-delimited regions)
         let code_regions = find_code_regions(line);
         extractions.retain(|(start, end, _)| {
-            !code_regions
-                .iter()
-                .any(|(cs, ce)| *start >= *cs && *end <= *ce)
+            !is_escaped(line, *start)
+                && !code_regions
+                    .iter()
+                    .any(|(cs, ce)| start < ce && end > cs && (cs < start || ce > end))
         });
 
         // Sort extractions by start position
@@ -259,6 +546,51 @@ impl Tokenizer {
         }
     }
 
+    /// Find highlights, super/subscripts and emoji shortcodes, as far as
+    /// the extensions enable them.
+    fn find_extended_inline(&self, line: &str, extractions: &mut Vec<(usize, usize, Token)>) {
+        if self.extensions.highlight {
+            for cap in HIGHLIGHT_RE.captures_iter(line) {
+                let m = cap.get(0).unwrap();
+                extractions.push((m.start(), m.end(), Token::Highlight(cap[1].to_string())));
+            }
+        }
+
+        if self.extensions.superscript {
+            for cap in SUPERSCRIPT_RE.captures_iter(line) {
+                let m = cap.get(0).unwrap();
+                extractions.push((m.start(), m.end(), Token::Superscript(cap[1].to_string())));
+            }
+        }
+
+        // `~text~` strikes text through if that is enabled
+        if self.extensions.subscript && !self.extensions.single_tilde_strikethrough {
+            for cap in SUBSCRIPT_RE.captures_iter(line) {
+                let m = cap.get(0).unwrap();
+                // Not part of a `~~` pair
+                if line[..m.start()].ends_with('~') || line[m.end()..].starts_with('~') {
+                    continue;
+                }
+                extractions.push((m.start(), m.end(), Token::Subscript(cap[1].to_string())));
+            }
+        }
+
+        if self.extensions.emoji_shortcodes {
+            let mut pos = 0;
+            while let Some(cap) = EMOJI_RE.captures_at(line, pos) {
+                let m = cap.get(0).unwrap();
+                match crate::emoji::emoji(&cap[1]) {
+                    Some(emoji) => {
+                        extractions.push((m.start(), m.end(), Token::Emoji(emoji.to_string())));
+                        pos = m.end();
+                    }
+                    // The closing colon may open the next shortcode
+                    None => pos = m.end() - 1,
+                }
+            }
+        }
+    }
+
     // Note: These extraction methods are kept for potential future use
     // when we need to process links/images/footnotes separately.
 
@@ -278,6 +610,86 @@ impl Tokenizer {
     }
 }
 
+/// Turn a bare URL or email candidate into a link token.
+///
+/// Returns the length of the candidate that belongs to the link, or
+/// `None` if nothing valid is left once trailing punctuation is trimmed.
+fn bare_autolink(candidate: &str) -> Option<(usize, Token)> {
+    let text = trim_autolink(candidate);
+    let lower = text.to_ascii_lowercase();
+
+    let url = if lower.starts_with("http://") || lower.starts_with("https://") {
+        let host = &text[text.find("://")? + 3..];
+        if !host.starts_with(|c: char| c.is_alphanumeric()) {
+            return None;
+        }
+        text.to_string()
+    } else if lower.starts_with("www.") {
+        if !text[4..].contains(|c: char| c.is_alphanumeric()) {
+            return None;
+        }
+        format!("http://{}", text)
+    } else {
+        // Email: the domain must not end in '-' or '_'
+        let text = text.trim_end_matches('.');
+        if text.ends_with(['-', '_']) || !text.contains('@') {
+            return None;
+        }
+        return Some((
+            text.len(),
+            Token::Autolink {
+                text: text.to_string(),
+                url: format!("mailto:{}", text),
+            },
+        ));
+    };
+
+    Some((
+        text.len(),
+        Token::Autolink {
+            text: text.to_string(),
+            url,
+        },
+    ))
+}
+
+/// Trim trailing characters that GFM excludes from extended autolinks.
+///
+/// Trailing punctuation (`?!.,:*_~`) is dropped, a closing `)` only when
+/// the parentheses are unbalanced, and a trailing entity reference
+/// (`&amp;`) is dropped as a whole.
+fn trim_autolink(candidate: &str) -> &str {
+    let mut text = candidate;
+    loop {
+        let trimmed =
+            if let Some(rest) = text.strip_suffix(['?', '!', '.', ',', ':', '*', '_', '~']) {
+                rest
+            } else if let Some(rest) = text.strip_suffix(')') {
+                if text.matches(')').count() > text.matches('(').count() {
+                    rest
+                } else {
+                    text
+                }
+            } else if let Some(rest) = text.strip_suffix(';') {
+                match rest.rfind('&') {
+                    Some(amp)
+                        if rest.len() > amp + 1
+                            && rest[amp + 1..].chars().all(|c| c.is_ascii_alphanumeric()) =>
+                    {
+                        &rest[..amp]
+                    }
+                    _ => text,
+                }
+            } else {
+                text
+            };
+        if trimmed.len() == text.len() {
+            return text;
+        }
+        text = trimmed;
+    }
+}
+
 /// Check if a character is CJK (Chinese, Japanese, Korean).
 ///
 /// CJK characters don't use spaces as word separators, so we need
@@ -434,6 +846,119 @@ mod tests {
         assert!(tokens.iter().any(|t| matches!(t, Token::Link { .. })));
     }
 
+    #[test]
+    fn test_tokenize_reference_link() {
+        let tokenizer = Tokenizer::new();
+        let tokens = tokenizer.tokenize("See [the docs][docs] and [Rust][].");
+        assert_eq!(
+            tokens,
+            vec![
+                Token::Text("See ".to_string()),
+                Token::RefLink {
+                    text: "the docs".to_string(),
+                    label: "docs".to_string(),
+                },
+                Token::Text(" and ".to_string()),
+                Token::RefLink {
+                    text: "Rust".to_string(),
+                    label: "Rust".to_string(),
+                },
+                Token::Text(".".to_string()),
+            ]
+        );
+
+        // Inline links and footnotes are not reference links
+        let tokens = tokenizer.tokenize("[a](b) [^1][x]");
+        assert!(!tokens.iter().any(|t| matches!(t, Token::RefLink { .. })));
+    }
+
+    /// Links extracted from a line, as (text, url) pairs.
+    fn links(line: &str) -> Vec<(String, String)> {
+        Tokenizer::new()
+            .tokenize(line)
+            .into_iter()
+            .filter_map(|t| match t {
+                Token::Link { text, url } | Token::Autolink { text, url } => Some((text, url)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_angle_autolinks() {
+        assert_eq!(
+            links("Go to <https://example.com/a?b=1> or mail <user@example.com>."),
+            vec![
+                (
+                    "https://example.com/a?b=1".to_string(),
+                    "https://example.com/a?b=1".to_string()
+                ),
+                (
+                    "user@example.com".to_string(),
+                    "mailto:user@example.com".to_string()
+                ),
+            ]
+        );
+        // Not autolinks
+        assert!(links("<br> <a b> <x.com> <@user>").is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_bare_urls() {
+        assert_eq!(
+            links("See https://example.com/path, then www.rust-lang.org."),
+            vec![
+                (
+                    "https://example.com/path".to_string(),
+                    "https://example.com/path".to_string()
+                ),
+                (
+                    "www.rust-lang.org".to_string(),
+                    "http://www.rust-lang.org".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            links("Write to foo.bar@example.co.uk."),
+            vec![(
+                "foo.bar@example.co.uk".to_string(),
+                "mailto:foo.bar@example.co.uk".to_string()
+            )]
+        );
+        // Bare URLs must start a word
+        assert!(links("xhttps://example.com").is_empty());
+    }
+
+    #[test]
+    fn test_trim_autolink() {
+        assert_eq!(trim_autolink("https://a.com/x?!"), "https://a.com/x");
+        assert_eq!(
+            trim_autolink("https://en.wikipedia.org/wiki/Rust_(language)"),
+            "https://en.wikipedia.org/wiki/Rust_(language)"
+        );
+        assert_eq!(trim_autolink("https://a.com/x)."), "https://a.com/x");
+        assert_eq!(
+            trim_autolink("https://a.com/?q=1&amp;"),
+            "https://a.com/?q=1"
+        );
+        assert_eq!(trim_autolink("https://a.com/;"), "https://a.com/;");
+    }
+
+    #[test]
+    fn test_autolinks_not_detected_in_code_or_links() {
+        assert!(links("Run `curl https://example.com` now").is_empty());
+        assert_eq!(
+            links("[docs](https://docs.rs) and (https://a.com)"),
+            vec![
+                ("docs".to_string(), "https://docs.rs".to_string()),
+                ("https://a.com".to_string(), "https://a.com".to_string()),
+            ]
+        );
+        // The URL keeps its underscores instead of starting italics
+        let tokens = Tokenizer::new().tokenize("https://a.com/my_file_name ok");
+        assert!(!tokens.contains(&Token::Underscore));
+    }
+
     #[test]
     fn test_tokenize_image() {
         let tokenizer = Tokenizer::new();
@@ -449,7 +974,166 @@ mod tests {
         let tokens = tokenizer.tokenize("Some text[^1] here");
 
         // Should contain a Footnote token
-        assert!(tokens.iter().any(|t| matches!(t, Token::Footnote(1))));
+        assert!(
+            tokens
+                .iter()
+                .any(|t| matches!(t, Token::Footnote(l) if l == "1"))
+        );
+
+        let tokens = tokenizer.tokenize("Named[^note-a] here");
+        assert!(
+            tokens
+                .iter()
+                .any(|t| matches!(t, Token::Footnote(l) if l == "note-a"))
+        );
+    }
+
+    #[test]
+    fn test_tokenize_hard_break() {
+        let tokenizer = Tokenizer::new();
+        for line in ["line  ", "line   ", "line\\"] {
+            assert_eq!(
+                tokenizer.tokenize(line),
+                vec![Token::Text("line".to_string()), Token::HardBreak],
+                "{:?}",
+                line
+            );
+        }
+        // One space, an escaped backslash, or nothing before the break
+        assert!(!tokenizer.tokenize("line ").contains(&Token::HardBreak));
+        assert!(!tokenizer.tokenize("a\\\\").contains(&Token::HardBreak));
+        assert!(!tokenizer.tokenize("\\").contains(&Token::HardBreak));
+    }
+
+    #[test]
+    fn test_tokenize_backslash_escapes() {
+        let tokenizer = Tokenizer::new();
+        let text = |s: &str| Token::Text(s.to_string());
+        assert_eq!(
+            tokenizer.tokenize(r"\*not italic\*"),
+            vec![text("*"), text("not italic"), text("*")]
+        );
+        assert_eq!(
+            tokenizer.tokenize(r"\_a\_ \`b\` \# \q"),
+            vec![
+                text("_"),
+                text("a"),
+                text("_"),
+                text(" "),
+                text("`"),
+                text("b")
+            ]
+            .into_iter()
+            .chain([text("`"), text(" "), text("#"), text(" \\q")])
+            .collect::<Vec<_>>()
+        );
+        // An escaped backslash leaves the marker after it
+        assert_eq!(
+            tokenizer.tokenize(r"\\*a*"),
+            vec![text("\\"), Token::Asterisk, text("a"), Token::Asterisk]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_escaped_extractions() {
+        let tokenizer = Tokenizer::new();
+        for line in [r"\[a](b)", r"\<b>", r"\<https://x.com>", r"\[^1]"] {
+            assert!(
+                tokenizer
+                    .tokenize(line)
+                    .iter()
+                    .all(|t| matches!(t, Token::Text(_))),
+                "{:?}",
+                line
+            );
+        }
+        // An escaped ! leaves a plain link
+        assert_eq!(
+            tokenizer.tokenize(r"\![a](b)"),
+            vec![
+                Token::Text("!".to_string()),
+                Token::Link {
+                    text: "a".to_string(),
+                    url: "b".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_extended_inline() {
+        let text = |s: &str| Token::Text(s.to_string());
+        let tokenizer = Tokenizer::new();
+        assert_eq!(
+            tokenizer.tokenize("==a *b*== x^2^ H~2~O :rocket: :nope:"),
+            vec![
+                Token::Highlight("a *b*".to_string()),
+                text(" x"),
+                Token::Superscript("2".to_string()),
+                text(" H"),
+                Token::Subscript("2".to_string()),
+                text("O "),
+                Token::Emoji("🚀".to_string()),
+                text(" :nope:"),
+            ]
+        );
+        // Spaces, `~~` pairs and code spans are left alone
+        assert_eq!(
+            tokenizer.tokenize("a == b == c ^x y^"),
+            vec![text("a == b == c ^x y^")]
+        );
+        assert!(
+            !tokenizer
+                .tokenize("~~a~~")
+                .contains(&Token::Subscript("a".to_string()))
+        );
+        assert_eq!(
+            tokenizer.tokenize("`:rocket:`"),
+            vec![Token::Backticks(1), text(":rocket:"), Token::Backticks(1)]
+        );
+        // A colon closing an unknown shortcode can open the next
+        assert_eq!(
+            tokenizer.tokenize("a:tada:"),
+            vec![text("a"), Token::Emoji("🎉".to_string())]
+        );
+
+        let tokenizer = Tokenizer {
+            extensions: Extensions::gfm(),
+            ..Tokenizer::new()
+        };
+        assert_eq!(
+            tokenizer.tokenize("==a== ~b~ :+1:"),
+            vec![
+                text("==a== "),
+                Token::Tilde,
+                text("b"),
+                Token::Tilde,
+                text(" "),
+                Token::Emoji("👍".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_entities_outside_code() {
+        let tokenizer = Tokenizer::new();
+        assert_eq!(
+            tokenizer.tokenize(r"&copy; `\* &amp;` \&amp;"),
+            vec![
+                Token::Text("© ".to_string()),
+                Token::Backticks(1),
+                Token::Text(r"\".to_string()),
+                Token::Asterisk,
+                Token::Text(" &amp;".to_string()),
+                Token::Backticks(1),
+                Token::Text(" ".to_string()),
+                Token::Text("&".to_string()),
+                Token::Text("amp;".to_string()),
+            ]
+        );
+        // Escaped backticks don't open code spans
+        assert_eq!(find_code_regions(r"\`a\` `b`"), vec![(6, 9)]);
+        assert_eq!(find_code_regions("``a`b`` `c"), vec![(0, 7)]);
     }
 
     #[test]
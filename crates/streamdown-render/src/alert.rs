@@ -0,0 +1,101 @@
+//! GitHub-style alert rendering.
+//!
+//! An alert (`> [!WARNING]`) is a blockquote with a kind. It is drawn like
+//! a quote, but its border takes the kind's colour and it opens with a
+//! title bar: the kind's icon and the title, or the kind's name if the
+//! alert gives no title.
+
+use crate::RenderStyle;
+use crate::fg_color;
+use crate::inline::render_spans;
+use streamdown_ansi::codes::{BOLD_OFF, BOLD_ON, RESET};
+use streamdown_parser::{AlertKind, InlineSpan};
+
+/// Icon shown in an alert's title bar.
+pub fn alert_icon(kind: AlertKind) -> &'static str {
+    match kind {
+        AlertKind::Note => "ℹ",
+        AlertKind::Tip => "💡",
+        AlertKind::Important => "❗",
+        AlertKind::Warning => "⚠",
+        AlertKind::Caution => "⛔",
+    }
+}
+
+/// Border and title colour for an alert kind.
+pub fn alert_color(kind: AlertKind, style: &RenderStyle) -> &str {
+    match kind {
+        AlertKind::Note => &style.alert_note,
+        AlertKind::Tip => &style.alert_tip,
+        AlertKind::Important => &style.alert_important,
+        AlertKind::Warning => &style.alert_warning,
+        AlertKind::Caution => &style.alert_caution,
+    }
+}
+
+/// Render an alert's title bar.
+///
+/// `left_margin` already includes the alert's own border.
+pub fn render_alert_title(
+    kind: AlertKind,
+    title: Option<&[InlineSpan]>,
+    left_margin: &str,
+    style: &RenderStyle,
+) -> String {
+    let fg = fg_color(alert_color(kind, style));
+    let title = match title {
+        Some(title) => render_spans(title, style, &format!("{}{}", fg, BOLD_ON)),
+        None => kind.default_title().to_string(),
+    };
+    format!(
+        "{}{}{} {}{}{}{}",
+        left_margin,
+        fg,
+        alert_icon(kind),
+        BOLD_ON,
+        title,
+        BOLD_OFF,
+        RESET
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use streamdown_ansi::utils::visible;
+    use streamdown_parser::InlineParser;
+
+    #[test]
+    fn test_render_alert_title() {
+        let style = RenderStyle::default();
+        let line = render_alert_title(AlertKind::Warning, None, "│ ", &style);
+        assert_eq!(visible(&line), "│ ⚠ Warning");
+        assert!(line.contains(&fg_color(&style.alert_warning)));
+
+        let line = render_alert_title(
+            AlertKind::Tip,
+            Some(InlineParser::new().parse("Use *this*").as_slice()),
+            "",
+            &style,
+        );
+        assert_eq!(visible(&line), "💡 Use this");
+    }
+
+    #[test]
+    fn test_alert_colors_differ() {
+        let style = RenderStyle::default();
+        let kinds = [
+            AlertKind::Note,
+            AlertKind::Tip,
+            AlertKind::Important,
+            AlertKind::Warning,
+            AlertKind::Caution,
+        ];
+        for (i, a) in kinds.iter().enumerate() {
+            for b in &kinds[i + 1..] {
+                assert_ne!(alert_color(*a, &style), alert_color(*b, &style));
+                assert_ne!(alert_icon(*a), alert_icon(*b));
+            }
+        }
+    }
+}
@@ -4,22 +4,30 @@
 //! - Syntax highlighting
 //! - Pretty padding (▄▄▄ / ▀▀▀ borders) or space-based borders
 //! - Line wrapping for long lines (optional)
-//! - Language labels
+//! - Language labels and titles
+//! - Line numbers and highlighted lines from the info string
 
 use crate::{RenderStyle, bg_color, fg_color};
 use streamdown_ansi::codes::RESET;
+use streamdown_ansi::utils::{is_ansi_code, parse_sgr_params, split_up};
+use streamdown_parser::CodeInfo;
 use streamdown_syntax::{HighlightState, Highlighter};
 
 /// Characters for pretty code block borders.
 pub const CODEPAD_TOP: char = '▄'; // Lower half block
 pub const CODEPAD_BOTTOM: char = '▀'; // Upper half block
 
+/// Width of the line number gutter, including the space after the number.
+const LINE_NUMBER_WIDTH: usize = 4;
+
 /// Code block rendering state.
-pub struct CodeBlockState<'a> {
-    /// The syntax highlighter
-    pub highlighter: &'a Highlighter,
+///
+/// Carries the syntax highlighting state from one line of a block to the
+/// next, so multi-line strings and comments are colored correctly.
+#[derive(Debug, Clone)]
+pub struct CodeBlockState {
     /// Highlight state for streaming
-    pub highlight_state: Option<HighlightState<'a>>,
+    pub highlight_state: Option<HighlightState>,
     /// Current language
     pub language: Option<String>,
     /// Background color for the block
@@ -30,34 +38,53 @@ pub struct CodeBlockState<'a> {
     pub pretty_broken: bool,
     /// Code block indent level
     pub indent: usize,
+    /// Title, line highlights and line numbers from the info string
+    pub info: CodeInfo,
+    /// Number of the last rendered line, 1-based
+    pub line_number: usize,
     /// Accumulated raw code (for clipboard/savebrace)
     pub raw_buffer: String,
 }
 
-impl<'a> CodeBlockState<'a> {
+impl Default for CodeBlockState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CodeBlockState {
     /// Create a new code block state.
-    pub fn new(highlighter: &'a Highlighter) -> Self {
+    pub fn new() -> Self {
         Self {
-            highlighter,
             highlight_state: None,
             language: None,
             background: String::new(),
             pretty_pad: true,
             pretty_broken: false,
             indent: 0,
+            info: CodeInfo::default(),
+            line_number: 0,
             raw_buffer: String::new(),
         }
     }
 
     /// Start a new code block.
-    pub fn start(&mut self, language: Option<String>, style: &RenderStyle) {
+    pub fn start(
+        &mut self,
+        highlighter: &Highlighter,
+        language: Option<String>,
+        info: CodeInfo,
+        style: &RenderStyle,
+    ) {
         self.language = language.clone();
+        self.info = info;
+        self.line_number = 0;
         self.background = bg_color(&style.code_bg);
         self.raw_buffer.clear();
 
         // Create highlight state for the language
         let lang = language.as_deref().unwrap_or("text");
-        self.highlight_state = Some(self.highlighter.new_highlight_state(lang));
+        self.highlight_state = Some(highlighter.new_highlight_state(lang));
     }
 
     /// Add a line to the raw buffer.
@@ -84,6 +111,7 @@ impl<'a> CodeBlockState<'a> {
 ///
 /// # Arguments
 /// * `language` - Optional language for the code block
+/// * `title` - Optional title from the info string, shown before the language
 /// * `width` - Available width
 /// * `left_margin` - Left margin string
 /// * `style` - Render style
@@ -93,6 +121,7 @@ impl<'a> CodeBlockState<'a> {
 /// Vector of lines for the code block header
 pub fn render_code_start(
     language: Option<&str>,
+    title: Option<&str>,
     width: usize,
     left_margin: &str,
     style: &RenderStyle,
@@ -103,9 +132,12 @@ pub fn render_code_start(
     let fg = fg_color(&style.table_border);
 
     // Check if we have a language label to embed
-    let lang_label = language
-        .filter(|l| !l.is_empty() && *l != "text")
-        .map(|lang| format!("[{}]", lang));
+    let language = language.filter(|l| !l.is_empty() && *l != "text");
+    let lang_label = match (title, language) {
+        (Some(title), Some(lang)) => Some(format!("[{} · {}]", title, lang)),
+        (Some(label), None) | (None, Some(label)) => Some(format!("[{}]", label)),
+        (None, None) => None,
+    };
 
     if pretty_pad {
         // Pretty top border: ▄▄▄▄▄ with optional language label embedded
@@ -162,9 +194,13 @@ pub fn render_code_start(
 
 /// Render a line of code with syntax highlighting.
 ///
+/// Lines marked in the block's info string get the highlight background,
+/// and a line number gutter is drawn when it asks for line numbers.
+///
 /// # Arguments
 /// * `line` - The code line
 /// * `state` - Code block state (for highlighting)
+/// * `highlighter` - The syntax highlighter
 /// * `width` - Available width
 /// * `left_margin` - Left margin string
 /// * `style` - Render style
@@ -174,41 +210,72 @@ pub fn render_code_start(
 /// Vector of rendered lines (may be multiple if wrapped)
 pub fn render_code_line(
     line: &str,
-    state: &mut CodeBlockState<'_>,
+    state: &mut CodeBlockState,
+    highlighter: &Highlighter,
     width: usize,
     left_margin: &str,
     style: &RenderStyle,
     pretty_broken: bool,
 ) -> Vec<String> {
-    let bg = bg_color(&style.code_bg);
+    state.line_number += 1;
+    let number = state.line_number;
+    let bg = if state.info.is_highlighted(number) {
+        bg_color(&style.code_highlight_bg)
+    } else {
+        bg_color(&style.code_bg)
+    };
+
+    let line_numbers = state.info.line_numbers;
+    let width = if line_numbers {
+        width.saturating_sub(LINE_NUMBER_WIDTH)
+    } else {
+        width
+    };
 
     // Wrap long lines if pretty_broken is enabled
     let (indent, wrapped_lines) = code_wrap(line, width, pretty_broken);
 
-    let mut result = Vec::new();
+    // Highlight the whole line, so the carried state sees it as written,
+    // then split the colored line into the wrapped rows
+    let rows = match state.highlight_state {
+        Some(ref mut hl_state) => {
+            let highlighted = highlighter.highlight_line_with_state(line, hl_state);
+            let lengths: Vec<usize> = wrapped_lines.iter().map(|l| l.chars().count()).collect();
+            split_highlighted(&highlighted, &lengths)
+        }
+        None => wrapped_lines,
+    };
 
-    for (i, code_line) in wrapped_lines.iter().enumerate() {
-        // Highlight the line
-        let highlighted = if let Some(ref mut hl_state) = state.highlight_state {
-            state
-                .highlighter
-                .highlight_line_with_state(code_line, hl_state)
-        } else {
-            code_line.to_string()
-        };
+    let mut result = Vec::new();
 
+    for (i, highlighted) in rows.iter().enumerate() {
         // Calculate padding
         let line_indent = if i == 0 { 0 } else { indent };
         let indent_str = " ".repeat(line_indent);
 
+        // Number the first row of the line; wrapped rows get a blank gutter
+        let gutter = match (line_numbers, i) {
+            (false, _) => String::new(),
+            (true, 0) => format!(
+                "{}{:>w$}{}{} ",
+                fg_color(&style.table_border),
+                number,
+                RESET,
+                bg,
+                w = LINE_NUMBER_WIDTH - 1
+            ),
+            (true, _) => " ".repeat(LINE_NUMBER_WIDTH),
+        };
+
         // Build the line with background
-        let visible_len = streamdown_ansi::utils::visible_length(&highlighted) + line_indent;
+        let visible_len = streamdown_ansi::utils::visible_length(highlighted) + line_indent;
         let padding = width.saturating_sub(visible_len);
 
         result.push(format!(
-            "{}{}{}{}{}{}{}",
+            "{}{}{}{}{}{}{}{}",
             left_margin,
             bg,
+            gutter,
             indent_str,
             highlighted,
             bg,
@@ -231,6 +298,45 @@ pub fn render_code_line(
     result
 }
 
+/// Split a highlighted line into rows of `lengths` characters.
+///
+/// Colors active at the end of a row are reopened at the start of the
+/// next, so wrapped rows keep the highlighting of the line.
+fn split_highlighted(highlighted: &str, lengths: &[usize]) -> Vec<String> {
+    let mut rows = Vec::with_capacity(lengths.len());
+    let mut lengths = lengths.iter();
+    let mut remaining = lengths.next().copied().unwrap_or(0);
+    let mut row = String::new();
+    let mut active: Vec<String> = Vec::new();
+
+    for segment in split_up(highlighted) {
+        if is_ansi_code(&segment) {
+            if parse_sgr_params(&segment).contains(&0) {
+                active.clear();
+            } else {
+                active.push(segment.clone());
+            }
+            row.push_str(&segment);
+            continue;
+        }
+        for ch in segment.chars() {
+            if remaining == 0 {
+                // Whitespace past the last row was dropped by the wrap
+                let Some(&length) = lengths.next() else {
+                    break;
+                };
+                rows.push(std::mem::replace(&mut row, active.concat()));
+                remaining = length;
+            }
+            row.push(ch);
+            remaining -= 1;
+        }
+    }
+    rows.push(row);
+
+    rows
+}
+
 /// Render the closing of a code block.
 ///
 /// # Arguments
@@ -385,7 +491,7 @@ mod tests {
 
     #[test]
     fn test_render_code_start_pretty() {
-        let lines = render_code_start(Some("rust"), 80, "", &default_style(), true);
+        let lines = render_code_start(Some("rust"), None, 80, "", &default_style(), true);
         assert!(!lines.is_empty());
         // Should have ▄ border
         assert!(lines[0].contains(CODEPAD_TOP));
@@ -393,12 +499,81 @@ mod tests {
 
     #[test]
     fn test_render_code_start_not_pretty() {
-        let lines = render_code_start(Some("rust"), 80, "", &default_style(), false);
+        let lines = render_code_start(Some("rust"), None, 80, "", &default_style(), false);
         assert!(!lines.is_empty());
         // Should NOT have ▄ border (space-based instead)
         assert!(!lines[0].contains(CODEPAD_TOP));
     }
 
+    #[test]
+    fn test_render_code_start_with_title() {
+        let lines = render_code_start(
+            Some("rust"),
+            Some("src/main.rs"),
+            40,
+            "",
+            &default_style(),
+            false,
+        );
+        let visible = streamdown_ansi::utils::visible(&lines[0]);
+        assert!(visible.contains("[src/main.rs · rust]"));
+        assert_eq!(unicode_width::UnicodeWidthStr::width(visible.as_str()), 40);
+
+        let lines = render_code_start(None, Some("notes.txt"), 40, "", &default_style(), true);
+        assert!(lines[0].contains("[notes.txt]"));
+    }
+
+    #[test]
+    fn test_render_code_line_numbers_and_highlight() {
+        let highlighter = Highlighter::new();
+        let style = default_style();
+        let mut state = CodeBlockState::new();
+        let (language, info) = CodeInfo::parse("text {2} linenos");
+        state.start(&highlighter, language, info, &style);
+
+        let plain_bg = bg_color(&style.code_bg);
+        let highlight_bg = bg_color(&style.code_highlight_bg);
+        let first = render_code_line("one", &mut state, &highlighter, 40, "", &style, false);
+        let second = render_code_line("two", &mut state, &highlighter, 40, "", &style, false);
+
+        assert!(streamdown_ansi::utils::visible(&first[0]).starts_with("  1 one"));
+        assert!(streamdown_ansi::utils::visible(&second[0]).starts_with("  2 two"));
+        assert!(first[0].contains(&plain_bg) && !first[0].contains(&highlight_bg));
+        assert!(second[0].contains(&highlight_bg));
+        // The gutter comes out of the line width
+        assert_eq!(streamdown_ansi::utils::visible_length(&second[0]), 40);
+    }
+
+    #[test]
+    fn test_wrapped_code_line_keeps_highlighting() {
+        let highlighter = Highlighter::new();
+        let style = default_style();
+        let mut state = CodeBlockState::new();
+        let (language, info) = CodeInfo::parse("rust");
+        state.start(&highlighter, language, info, &style);
+
+        let line = "let x = 1; // a comment long enough to be wrapped";
+        let rows = render_code_line(line, &mut state, &highlighter, 30, "", &style, true);
+        let (_, chunks) = code_wrap(line, 30, true);
+        assert!(chunks.len() > 1);
+        assert_eq!(rows.len(), chunks.len());
+        for (row, chunk) in rows.iter().zip(&chunks) {
+            assert_eq!(
+                streamdown_ansi::utils::visible(row).trim_end(),
+                chunk.trim_end()
+            );
+        }
+
+        // The comment's color carries over to the wrapped row
+        let comment = highlighter
+            .highlight_line_with_state("// x", &mut highlighter.new_highlight_state("rust"));
+        let color = streamdown_ansi::utils::extract_ansi_codes(&comment)
+            .into_iter()
+            .find(|code| code.contains("38;"))
+            .unwrap();
+        assert!(rows[1].contains(&color));
+    }
+
     #[test]
     fn test_render_code_end_pretty() {
         let lines = render_code_end(80, "", &default_style(), true);
@@ -416,10 +591,15 @@ mod tests {
     #[test]
     fn test_code_block_state_raw_buffer() {
         let highlighter = Highlighter::new();
-        let mut state = CodeBlockState::new(&highlighter);
+        let mut state = CodeBlockState::new();
         let style = default_style();
 
-        state.start(Some("rust".to_string()), &style);
+        state.start(
+            &highlighter,
+            Some("rust".to_string()),
+            CodeInfo::default(),
+            &style,
+        );
         state.add_raw_line("fn main() {");
         state.add_raw_line("    println!(\"Hello\");");
         state.add_raw_line("}");
@@ -530,7 +710,7 @@ mod tests {
         // With display-based: padding = 40 - (6 + 2) = 32
         // The difference is 3 extra spaces with byte-based
 
-        let lines = render_code_start(Some(lang), width, "", &style, false);
+        let lines = render_code_start(Some(lang), None, width, "", &style, false);
 
         // Find the language label line (contains "[日本語]")
         let label_line = lines
@@ -151,6 +151,62 @@ pub fn savebrace_last() -> io::Result<Option<String>> {
     Ok(blocks.into_iter().last())
 }
 
+/// Default number of table rows buffered before laying out a table.
+pub const DEFAULT_TABLE_BUFFER_ROWS: usize = 50;
+
+/// Where collected footnotes are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FootnoteBoundary {
+    /// Once, at the end of the document
+    #[default]
+    End,
+    /// Before each heading, closing the previous section
+    Heading,
+}
+
+impl std::str::FromStr for FootnoteBoundary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "end" => Ok(Self::End),
+            "heading" | "section" => Ok(Self::Heading),
+            other => Err(format!(
+                "unknown footnote boundary '{}' (expected end or heading)",
+                other
+            )),
+        }
+    }
+}
+
+/// How front matter at the start of a document is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrontMatterMode {
+    /// Not shown
+    Hide,
+    /// As a code block in its own language
+    Raw,
+    /// The title as a heading, the other fields as a key/value table
+    #[default]
+    Table,
+}
+
+impl std::str::FromStr for FrontMatterMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "hide" | "none" => Ok(Self::Hide),
+            "raw" => Ok(Self::Raw),
+            "table" => Ok(Self::Table),
+            other => Err(format!(
+                "unknown front matter mode '{}' (expected hide, raw or table)",
+                other
+            )),
+        }
+    }
+}
+
 /// Feature flags for rendering.
 #[derive(Debug, Clone)]
 pub struct RenderFeatures {
@@ -174,6 +230,19 @@ pub struct RenderFeatures {
 
     /// Margin on each side
     pub margin: usize,
+
+    /// Join soft-broken lines of a paragraph and reflow them together
+    pub reflow: bool,
+
+    /// Table rows to buffer for content-aware column widths
+    /// (0 = stream rows with evenly split columns)
+    pub table_buffer_rows: usize,
+
+    /// Where the notes section for collected footnotes is printed
+    pub footnote_boundary: FootnoteBoundary,
+
+    /// How front matter is shown
+    pub front_matter: FrontMatterMode,
 }
 
 impl Default for RenderFeatures {
@@ -186,6 +255,10 @@ impl Default for RenderFeatures {
             width_wrap: true,
             fixed_width: None,
             margin: 1,
+            reflow: false,
+            table_buffer_rows: DEFAULT_TABLE_BUFFER_ROWS,
+            footnote_boundary: FootnoteBoundary::End,
+            front_matter: FrontMatterMode::Table,
         }
     }
 }
@@ -252,6 +325,23 @@ mod tests {
         assert!(!features.pretty_broken);
         assert!(!features.clipboard);
         assert!(!features.savebrace);
+        assert_eq!(features.footnote_boundary, FootnoteBoundary::End);
+        assert_eq!(features.front_matter, FrontMatterMode::Table);
+    }
+
+    #[test]
+    fn test_footnote_boundary_from_str() {
+        assert_eq!("end".parse(), Ok(FootnoteBoundary::End));
+        assert_eq!("Heading".parse(), Ok(FootnoteBoundary::Heading));
+        assert!("middle".parse::<FootnoteBoundary>().is_err());
+    }
+
+    #[test]
+    fn test_front_matter_mode_from_str() {
+        assert_eq!("hide".parse(), Ok(FrontMatterMode::Hide));
+        assert_eq!("RAW".parse(), Ok(FrontMatterMode::Raw));
+        assert_eq!("table".parse(), Ok(FrontMatterMode::Table));
+        assert!("yaml".parse::<FrontMatterMode>().is_err());
     }
 
     #[test]
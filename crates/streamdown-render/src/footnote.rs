@@ -0,0 +1,255 @@
+//! Footnote rendering.
+//!
+//! Footnote definitions are collected while the document streams and
+//! printed together as a "Notes" section:
+//! - References link to their note with an OSC 8 hyperlink
+//! - Notes are listed in order of first reference
+//! - References without a definition are flagged
+
+use crate::RenderStyle;
+use crate::fg_color;
+use crate::inline::render_spans;
+use crate::text::text_wrap;
+use streamdown_ansi::codes::{BOLD_OFF, BOLD_ON, ITALIC_OFF, ITALIC_ON, RESET, SUPER};
+use streamdown_parser::InlineSpan;
+
+/// Glyph flagging a reference whose footnote was never defined.
+pub const FOOTNOTE_MISSING: &str = "⚠";
+
+/// Footnotes collected since the last notes section.
+#[derive(Debug, Clone, Default)]
+pub struct FootnoteState {
+    /// Definitions as (label, content), in the order they were seen
+    pub definitions: Vec<(String, Vec<InlineSpan>)>,
+    /// Referenced labels, in order of first reference
+    pub references: Vec<String>,
+}
+
+impl FootnoteState {
+    /// Create a new footnote state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a definition. The first definition of a label wins.
+    pub fn define(&mut self, label: &str, content: &[InlineSpan]) {
+        if !self.definitions.iter().any(|(l, _)| l == label) {
+            self.definitions.push((label.to_string(), content.to_vec()));
+        }
+    }
+
+    /// Record a reference to `label`.
+    pub fn reference(&mut self, label: &str) {
+        if !self.references.iter().any(|l| l == label) {
+            self.references.push(label.to_string());
+        }
+    }
+
+    /// Whether nothing has been collected.
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty() && self.references.is_empty()
+    }
+
+    /// Get the content defined for `label`.
+    pub fn definition(&self, label: &str) -> Option<&[InlineSpan]> {
+        self.definitions
+            .iter()
+            .find(|(l, _)| l == label)
+            .map(|(_, content)| content.as_slice())
+    }
+
+    /// Labels in notes order: referenced ones first, then the rest of the
+    /// definitions.
+    pub fn labels(&self) -> Vec<&str> {
+        let mut labels: Vec<&str> = self.references.iter().map(String::as_str).collect();
+        for (label, _) in &self.definitions {
+            if !labels.contains(&label.as_str()) {
+                labels.push(label);
+            }
+        }
+        labels
+    }
+
+    /// Forget everything collected.
+    pub fn clear(&mut self) {
+        self.definitions.clear();
+        self.references.clear();
+    }
+}
+
+/// Recover a footnote label from its superscript marker (`¹²` -> `12`).
+pub fn footnote_label(marker: &str) -> String {
+    marker
+        .chars()
+        .map(|c| {
+            SUPER
+                .iter()
+                .position(|&code| code == c as u32)
+                .and_then(|digit| char::from_digit(digit as u32, 10))
+                .unwrap_or(c)
+        })
+        .collect()
+}
+
+/// Superscript marker for a footnote label.
+fn footnote_marker(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| {
+            c.to_digit(10)
+                .and_then(|digit| char::from_u32(SUPER[digit as usize]))
+                .unwrap_or(c)
+        })
+        .collect()
+}
+
+/// Wrap `text` in an OSC 8 hyperlink.
+fn osc8_link(params: &str, uri: &str, text: &str) -> String {
+    format!("\x1b]8;{};{}\x1b\\{}\x1b]8;;\x1b\\", params, uri, text)
+}
+
+/// Render a footnote reference as a superscript linking to its note.
+pub fn render_footnote_ref(label: &str, marker: &str, style: &RenderStyle) -> String {
+    let fg = fg_color(&style.footnote);
+    let marker = format!("{}{}{}", fg, marker, RESET);
+    osc8_link("", &format!("#fn-{}", label), &marker)
+}
+
+/// Render the notes section for the collected footnotes, then clear them.
+///
+/// # Arguments
+/// * `state` - Collected footnotes
+/// * `width` - Available width
+/// * `left_margin` - Left margin string
+/// * `style` - Render style
+///
+/// # Returns
+/// The section's lines, or nothing if no footnotes were collected
+pub fn render_notes(
+    state: &mut FootnoteState,
+    width: usize,
+    left_margin: &str,
+    style: &RenderStyle,
+) -> Vec<String> {
+    if state.is_empty() {
+        return Vec::new();
+    }
+
+    let fg = fg_color(&style.footnote);
+    let rule_fg = fg_color(&style.hr);
+    let title = "Notes";
+    let rule = "─".repeat(width.saturating_sub(title.len() + 4));
+    let mut lines = vec![
+        String::new(),
+        format!(
+            "{}{}──{} {}{}{}{}{} {}{}{}",
+            left_margin, rule_fg, RESET, BOLD_ON, fg, title, BOLD_OFF, RESET, rule_fg, rule, RESET
+        ),
+    ];
+
+    for label in state.labels() {
+        let marker = footnote_marker(label);
+        let marker_width = unicode_width::UnicodeWidthStr::width(marker.as_str());
+        // The note's marker is the anchor its references point at
+        let anchor = osc8_link(
+            &format!("id=fn-{}", label),
+            &format!("#fn-{}", label),
+            &format!("{}{}{}", fg, marker, RESET),
+        );
+        let first_prefix = format!("{}{} ", left_margin, anchor);
+        let next_prefix = format!("{}{}", left_margin, " ".repeat(marker_width + 1));
+
+        let content = match state.definition(label) {
+            Some(content) => render_spans(content, style, ""),
+            None => format!(
+                "{}{} {}undefined footnote{}{}",
+                fg_color(&style.footnote),
+                FOOTNOTE_MISSING,
+                ITALIC_ON,
+                ITALIC_OFF,
+                RESET
+            ),
+        };
+        let wrapped = text_wrap(
+            &content,
+            width.saturating_sub(marker_width + 1),
+            0,
+            &first_prefix,
+            &next_prefix,
+            false,
+            true,
+        );
+        if wrapped.is_empty() {
+            lines.push(first_prefix);
+        } else {
+            lines.extend(wrapped.lines);
+        }
+    }
+
+    state.clear();
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use streamdown_parser::InlineParser;
+
+    fn spans(text: &str) -> Vec<InlineSpan> {
+        InlineParser::new().parse(text)
+    }
+
+    #[test]
+    fn test_footnote_label_roundtrip() {
+        assert_eq!(footnote_label("¹"), "1");
+        assert_eq!(footnote_label("⁴²"), "42");
+        assert_eq!(footnote_marker("42"), "⁴²");
+        assert_eq!(footnote_marker(&footnote_label("¹⁰³")), "¹⁰³");
+    }
+
+    #[test]
+    fn test_footnote_state_order() {
+        let mut state = FootnoteState::new();
+        state.define("3", &spans("unreferenced"));
+        state.reference("2");
+        state.reference("1");
+        state.reference("2");
+        state.define("1", &spans("first"));
+        state.define("1", &spans("duplicate"));
+
+        assert_eq!(state.labels(), vec!["2", "1", "3"]);
+        assert_eq!(state.definition("1"), Some(spans("first").as_slice()));
+        assert_eq!(state.definition("2"), None);
+    }
+
+    #[test]
+    fn test_render_notes() {
+        let style = RenderStyle::default();
+        let mut state = FootnoteState::new();
+        state.reference("1");
+        state.reference("2");
+        state.define("1", &spans("A **bold** note"));
+
+        let lines = render_notes(&mut state, 40, "", &style);
+        let text: Vec<String> = lines
+            .iter()
+            .map(|l| streamdown_ansi::utils::visible(l))
+            .collect();
+        assert!(text[1].contains("Notes"));
+        assert!(text[2].starts_with("¹ A bold note"));
+        assert!(text[3].contains(FOOTNOTE_MISSING));
+        assert!(lines[2].contains("\x1b]8;id=fn-1;#fn-1"));
+        // Collected notes are printed only once
+        assert!(state.is_empty());
+        assert!(render_notes(&mut state, 40, "", &style).is_empty());
+    }
+
+    #[test]
+    fn test_render_footnote_ref_links_to_note() {
+        let style = RenderStyle::default();
+        let rendered = render_footnote_ref("7", "⁷", &style);
+        assert!(rendered.starts_with("\x1b]8;;#fn-7\x1b\\"));
+        assert!(rendered.ends_with("\x1b]8;;\x1b\\"));
+        assert_eq!(streamdown_ansi::utils::visible(&rendered), "⁷");
+    }
+}
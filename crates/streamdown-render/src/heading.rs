@@ -7,18 +7,23 @@
 //! - h4: Bold, h4 color
 //! - h5: h5 color (no bold)
 //! - h6: h6 color (muted)
+//!
+//! Heading text may contain inline formatting, drawn on top of the
+//! heading's own style.
 
 use crate::RenderStyle;
 use crate::fg_color;
-use crate::text::simple_wrap;
+use crate::inline::render_spans;
+use crate::text::text_wrap;
 use streamdown_ansi::codes::{BOLD_OFF, BOLD_ON, RESET};
 use streamdown_ansi::utils::visible_length;
+use streamdown_parser::InlineSpan;
 
 /// Render a heading with appropriate styling.
 ///
 /// # Arguments
 /// * `level` - Heading level (1-6)
-/// * `text` - The heading text (already inline-formatted)
+/// * `content` - The heading text, parsed into spans
 /// * `width` - Available width for rendering
 /// * `left_margin` - Left margin/padding
 /// * `style` - Render style configuration
@@ -27,13 +32,39 @@ use streamdown_ansi::utils::visible_length;
 /// A vector of rendered lines
 pub fn render_heading(
     level: u8,
-    text: &str,
+    content: &[InlineSpan],
     width: usize,
     left_margin: &str,
     style: &RenderStyle,
 ) -> Vec<String> {
+    // Format inline content, restoring the heading's style after each span
+    let heading_fg = fg_color(match level {
+        1 => &style.h1,
+        2 => &style.h2,
+        3 => &style.h3,
+        4 => &style.h4,
+        5 => &style.h5,
+        _ => &style.h6,
+    });
+    let ambient = if level <= 4 {
+        format!("{}{}", BOLD_ON, heading_fg)
+    } else {
+        heading_fg
+    };
+    let text = render_spans(content, style, &ambient);
+
     // Wrap text if needed
-    let lines = simple_wrap(text, width);
+    let wrapped = text_wrap(&text, width, 0, "", "", false, true);
+    let lines: Vec<String> = if wrapped.is_empty() {
+        vec![String::new()]
+    } else {
+        // Only the line's content counts when centering
+        wrapped
+            .lines
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect()
+    };
     let mut result = Vec::new();
 
     for line in lines {
@@ -120,6 +151,11 @@ pub fn render_heading(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use streamdown_parser::InlineParser;
+
+    fn spans(text: &str) -> Vec<InlineSpan> {
+        InlineParser::new().parse(text)
+    }
 
     fn default_style() -> RenderStyle {
         RenderStyle::default()
@@ -127,7 +163,7 @@ mod tests {
 
     #[test]
     fn test_h1_centered() {
-        let lines = render_heading(1, "Title", 80, "", &default_style());
+        let lines = render_heading(1, &spans("Title"), 80, "", &default_style());
         assert!(!lines.is_empty());
         // Should contain bold codes
         assert!(lines[0].contains(BOLD_ON));
@@ -135,7 +171,7 @@ mod tests {
 
     #[test]
     fn test_h2_colored() {
-        let lines = render_heading(2, "Subtitle", 80, "", &default_style());
+        let lines = render_heading(2, &spans("Subtitle"), 80, "", &default_style());
         assert!(!lines.is_empty());
         // Should contain color codes
         assert!(lines[0].contains("\x1b[38;2;"));
@@ -143,14 +179,14 @@ mod tests {
 
     #[test]
     fn test_h3_head_color() {
-        let lines = render_heading(3, "Section", 80, "", &default_style());
+        let lines = render_heading(3, &spans("Section"), 80, "", &default_style());
         assert!(!lines.is_empty());
         assert!(lines[0].contains(BOLD_ON));
     }
 
     #[test]
     fn test_h6_grey() {
-        let lines = render_heading(6, "Minor", 80, "", &default_style());
+        let lines = render_heading(6, &spans("Minor"), 80, "", &default_style());
         assert!(!lines.is_empty());
         // Should contain grey color
         assert!(lines[0].contains("\x1b[38;2;"));
@@ -158,7 +194,7 @@ mod tests {
 
     #[test]
     fn test_heading_with_margin() {
-        let lines = render_heading(1, "Title", 80, "  ", &default_style());
+        let lines = render_heading(1, &spans("Title"), 80, "  ", &default_style());
         assert!(!lines.is_empty());
         assert!(lines[0].starts_with("  "));
     }
@@ -166,7 +202,7 @@ mod tests {
     #[test]
     fn test_long_heading_wraps() {
         let long_text = "This is a very long heading that should wrap to multiple lines";
-        let lines = render_heading(1, long_text, 20, "", &default_style());
+        let lines = render_heading(1, &spans(long_text), 20, "", &default_style());
         assert!(!lines.is_empty());
     }
 
@@ -174,7 +210,7 @@ mod tests {
     fn test_h1_left_aligned() {
         let mut style = default_style();
         style.heading_centered = false;
-        let lines = render_heading(1, "Title", 80, "", &style);
+        let lines = render_heading(1, &spans("Title"), 80, "", &style);
         assert!(!lines.is_empty());
         // Should not have center padding before "Title"
         assert!(lines[0].contains(BOLD_ON));
@@ -0,0 +1,92 @@
+//! Rendering for the supported HTML subset.
+//!
+//! - `<kbd>` keys are drawn as key caps
+//! - `<details>` boxes are drawn with a rounded border, the summary marked
+//!   with a disclosure triangle; content between the rules picks up a `│`
+//!   border through the renderer's left margin
+
+use crate::RenderStyle;
+use crate::inline::render_spans;
+use crate::{bg_color, fg_color};
+use streamdown_ansi::codes::{BOLD_OFF, BOLD_ON, RESET};
+use streamdown_parser::InlineSpan;
+
+/// Render a keyboard key as a key cap.
+pub fn render_kbd(key: &str, style: &RenderStyle) -> String {
+    let bg = bg_color(&style.code_bg);
+    format!("{}{} {} {}{}", bg, BOLD_ON, key, BOLD_OFF, RESET)
+}
+
+/// Render the top rule of a `<details>` box.
+pub fn render_details_start(width: usize, left_margin: &str, style: &RenderStyle) -> String {
+    let fg = fg_color(&style.blockquote_border);
+    format!(
+        "{}{}╭{}{}",
+        left_margin,
+        fg,
+        "─".repeat(width.saturating_sub(1)),
+        RESET
+    )
+}
+
+/// Render the summary line of a `<details>` box.
+///
+/// `left_margin` already includes the box's own border.
+pub fn render_details_summary(
+    summary: &[InlineSpan],
+    left_margin: &str,
+    style: &RenderStyle,
+) -> String {
+    let fg = fg_color(&style.blockquote_border);
+    format!(
+        "{}{}▼{} {}{}{}",
+        left_margin,
+        fg,
+        RESET,
+        BOLD_ON,
+        render_spans(summary, style, BOLD_ON),
+        BOLD_OFF
+    )
+}
+
+/// Render the bottom rule of a `<details>` box.
+pub fn render_details_end(width: usize, left_margin: &str, style: &RenderStyle) -> String {
+    let fg = fg_color(&style.blockquote_border);
+    format!(
+        "{}{}╰{}{}",
+        left_margin,
+        fg,
+        "─".repeat(width.saturating_sub(1)),
+        RESET
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use streamdown_ansi::utils::visible;
+    use streamdown_parser::InlineParser;
+
+    #[test]
+    fn test_render_kbd() {
+        let style = RenderStyle::default();
+        let key = render_kbd("Ctrl", &style);
+        assert_eq!(visible(&key), " Ctrl ");
+        assert!(key.contains(BOLD_ON));
+    }
+
+    #[test]
+    fn test_render_details_box() {
+        let style = RenderStyle::default();
+        assert_eq!(visible(&render_details_start(5, " ", &style)), " ╭────");
+        assert_eq!(
+            visible(&render_details_summary(
+                &InlineParser::new().parse("Show *more*"),
+                " │ ",
+                &style
+            )),
+            " │ ▼ Show more"
+        );
+        assert_eq!(visible(&render_details_end(3, "", &style)), "╰──");
+    }
+}
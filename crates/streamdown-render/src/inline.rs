@@ -0,0 +1,187 @@
+//! Inline content rendering.
+//!
+//! Renders the [`InlineSpan`]s produced by the parser. Each span
+//! turns its emphasis on and off again, so spans wrap cleanly with
+//! `text_wrap` and nest inside links.
+//!
+//! Code, links and other elements end with a full reset. Content drawn on
+//! top of its own attributes (a heading's colour, a table cell's
+//! background) passes them as `ambient` codes, restored after each span
+//! that may have cleared them.
+
+use crate::RenderStyle;
+use crate::html::render_kbd;
+use crate::links::{render_link, render_link_reference};
+use crate::{bg_color, fg_color};
+use streamdown_ansi::codes::{
+    BOLD_OFF, BOLD_ON, DIM_ON, ITALIC_OFF, ITALIC_ON, RESET, STRIKEOUT_OFF, STRIKEOUT_ON,
+    UNDERLINE_OFF, UNDERLINE_ON,
+};
+use streamdown_parser::{InlineElement, InlineSpan, InlineStyle};
+
+/// Codes turning on the emphasis of `style`.
+fn emphasis_on(style: InlineStyle) -> String {
+    [
+        (style.bold, BOLD_ON),
+        (style.italic, ITALIC_ON),
+        (style.underline, UNDERLINE_ON),
+        (style.strikeout, STRIKEOUT_ON),
+    ]
+    .iter()
+    .filter(|(active, _)| *active)
+    .map(|(_, code)| *code)
+    .collect()
+}
+
+/// Codes turning off the emphasis of `style`.
+fn emphasis_off(style: InlineStyle) -> String {
+    [
+        (style.strikeout, STRIKEOUT_OFF),
+        (style.underline, UNDERLINE_OFF),
+        (style.italic, ITALIC_OFF),
+        (style.bold, BOLD_OFF),
+    ]
+    .iter()
+    .filter(|(active, _)| *active)
+    .map(|(_, code)| *code)
+    .collect()
+}
+
+/// Render inline spans to a string with ANSI codes.
+pub fn render_spans(spans: &[InlineSpan], style: &RenderStyle, ambient: &str) -> String {
+    render_spans_within(spans, InlineStyle::PLAIN, style, ambient)
+}
+
+/// Render a single inline span to a string with ANSI codes.
+pub fn render_span(span: &InlineSpan, style: &RenderStyle, ambient: &str) -> String {
+    render_span_within(span, InlineStyle::PLAIN, style, ambient)
+}
+
+/// Render spans nested in content with the `outer` emphasis.
+fn render_spans_within(
+    spans: &[InlineSpan],
+    outer: InlineStyle,
+    style: &RenderStyle,
+    ambient: &str,
+) -> String {
+    spans
+        .iter()
+        .map(|span| render_span_within(span, outer, style, ambient))
+        .collect()
+}
+
+fn render_span_within(
+    span: &InlineSpan,
+    outer: InlineStyle,
+    style: &RenderStyle,
+    ambient: &str,
+) -> String {
+    let emphasis = outer.union(span.style);
+    let on = emphasis_on(emphasis);
+    let rendered = match &span.element {
+        InlineElement::Text(text)
+        | InlineElement::Superscript(text)
+        | InlineElement::Subscript(text)
+        | InlineElement::Emoji(text) => {
+            if emphasis.is_plain() {
+                return text.clone();
+            }
+            format!("{}{}{}", on, text, emphasis_off(emphasis))
+        }
+        InlineElement::Code(code) => {
+            // Dim would cancel out bold
+            let dim = if emphasis.bold { "" } else { DIM_ON };
+            let bg = bg_color(&style.code_bg);
+            format!("{}{}{} {} {}", on, bg, dim, code, RESET)
+        }
+        InlineElement::Link { content, url } => {
+            // The link text keeps its underline around nested spans
+            let text = render_spans_within(content, emphasis, style, UNDERLINE_ON);
+            render_link(&text, url, style)
+        }
+        InlineElement::LinkReference { content, label } => {
+            let text = render_spans_within(content, emphasis, style, UNDERLINE_ON);
+            render_link_reference(&text, label, style)
+        }
+        InlineElement::Highlight(content) => {
+            // The background is restored after nested spans that reset
+            let bg = bg_color(&style.highlight_bg);
+            let text = render_spans_within(content, emphasis, style, &format!("{}{}", ambient, bg));
+            format!("{}{}{}{}", bg, on, text, RESET)
+        }
+        InlineElement::Image { alt, .. } => {
+            let fg = fg_color(&style.image_marker);
+            format!("{}[\u{1F5BC} {}]{}", fg, alt, RESET)
+        }
+        InlineElement::Footnote(marker) => {
+            let fg = fg_color(&style.footnote);
+            format!("{}{}{}", fg, marker, RESET)
+        }
+        InlineElement::HardBreak => return " ".to_string(),
+        InlineElement::Kbd(key) => render_kbd(key, style),
+    };
+    format!("{}{}", rendered, ambient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use streamdown_ansi::utils::visible;
+    use streamdown_parser::InlineParser;
+
+    fn render_inline_content(content: &str, style: &RenderStyle) -> String {
+        render_inline_content_within(content, style, "")
+    }
+
+    fn render_inline_content_within(content: &str, style: &RenderStyle, ambient: &str) -> String {
+        render_spans(&InlineParser::new().parse(content), style, ambient)
+    }
+
+    #[test]
+    fn test_render_nested_emphasis() {
+        let style = RenderStyle::default();
+        let rendered = render_inline_content("**bold *both* `code`** plain", &style);
+        assert_eq!(visible(&rendered), "bold both  code  plain");
+        assert!(rendered.starts_with(&format!("{}bold ", BOLD_ON)));
+        assert!(rendered.contains(&format!(
+            "{}{}both{}{}",
+            BOLD_ON, ITALIC_ON, ITALIC_OFF, BOLD_OFF
+        )));
+        // Bold code stays bold
+        assert!(rendered.contains(&format!("{}{}", BOLD_ON, bg_color(&style.code_bg))));
+    }
+
+    #[test]
+    fn test_render_formatted_link() {
+        let style = RenderStyle::default();
+        let rendered = render_inline_content("*[the **docs**](https://docs.rs)*", &style);
+        assert_eq!(visible(&rendered), "the docs (https://docs.rs)");
+        // Underlined throughout, with the nested span's emphasis on top
+        assert!(rendered.contains(&format!("{}{}the ", UNDERLINE_ON, ITALIC_ON)));
+        assert!(rendered.contains(&format!("{}{}docs", BOLD_ON, ITALIC_ON)));
+        assert!(rendered.contains("\x1b]8;;https://docs.rs\x1b\\"));
+    }
+
+    #[test]
+    fn test_render_highlight() {
+        let style = RenderStyle::default();
+        let bg = bg_color(&style.highlight_bg);
+        let rendered = render_inline_content("==a `b` **c**== x^2^ :tada:", &style);
+        assert_eq!(visible(&rendered), "a  b  c x² 🎉");
+        assert!(rendered.starts_with(&format!("{}a ", bg)));
+        // Code inside the highlight gives the background back
+        assert!(rendered.contains(&format!(" b {}{}", RESET, bg)));
+        assert!(rendered.contains(&format!("c{}{}{}", BOLD_OFF, bg, RESET)));
+    }
+
+    #[test]
+    fn test_ambient_restored_after_spans() {
+        let style = RenderStyle::default();
+        let ambient = "\x1b[44m";
+        let rendered = render_inline_content_within("a `b` *c* d", &style, ambient);
+        assert!(rendered.contains(&format!(" b {}{}", RESET, ambient)));
+        assert!(rendered.contains(&format!("c{}{}", ITALIC_OFF, ambient)));
+        // Plain text needs no restoring
+        assert!(rendered.starts_with("a "));
+    }
+}
@@ -11,38 +11,52 @@
 //! - **ANSI-aware text wrapping** - Preserves formatting across line breaks
 //! - **Nested lists** - With cycling bullet styles
 //! - **Blockquotes** - With visual borders
+//! - **Alerts** - GitHub-style callouts with an icon and title
 //!
 //! # Example
 //!
 //! ```
 //! use streamdown_render::Renderer;
-//! use streamdown_parser::ParseEvent;
+//! use streamdown_parser::{InlineParser, ParseEvent};
 //!
 //! let mut output = Vec::new();
 //! let mut renderer = Renderer::new(&mut output, 80);
 //!
 //! renderer.render_event(&ParseEvent::Heading {
 //!     level: 1,
-//!     content: "Hello World".to_string(),
+//!     content: InlineParser::new().parse("Hello World"),
 //! }).unwrap();
 //! ```
 
+pub mod alert;
 pub mod code;
 pub mod colors;
 pub mod features;
+pub mod footnote;
 pub mod heading;
+pub mod html;
+pub mod inline;
+pub mod links;
 pub mod list;
 pub mod table;
 pub mod text;
 
+pub use alert::{alert_color, alert_icon, render_alert_title};
 pub use code::{CODEPAD_BOTTOM, CODEPAD_TOP, CodeBlockState, code_wrap};
 pub use colors::{COLODORE, resolve_color};
 pub use features::{
-    RenderFeatures, copy_to_clipboard, is_tty, savebrace, savebrace_clear, savebrace_last,
+    DEFAULT_TABLE_BUFFER_ROWS, FootnoteBoundary, FrontMatterMode, RenderFeatures, copy_to_clipboard, is_tty, savebrace, savebrace_clear, savebrace_last,
     savebrace_path, savebrace_read, terminal_size, terminal_width,
 };
+pub use footnote::{FootnoteState, footnote_label, render_footnote_ref, render_notes};
 pub use heading::render_heading;
-pub use list::{BULLETS, ListState, render_list_item};
+pub use html::{render_details_end, render_details_start, render_details_summary, render_kbd};
+pub use inline::{render_span, render_spans};
+pub use links::{
+    hyperlink, render_link, render_link_definition, render_link_reference,
+    render_unresolved_reference,
+};
+pub use list::{ItemLayout, ListState, NumberingStyle, layout_list_item, render_list_item};
 pub use table::{TableState, render_table_row, render_table_separator};
 pub use text::{WrappedText, simple_wrap, split_text, text_wrap};
 
@@ -51,8 +65,8 @@ use std::io::Write;
 use serde::{Deserialize, Serialize};
 use streamdown_config::ComputedStyle;
 use streamdown_ansi::codes::{
-    BOLD_OFF, BOLD_ON, DIM_ON, ITALIC_OFF, ITALIC_ON, RESET, STRIKEOUT_OFF, STRIKEOUT_ON,
-    UNDERLINE_OFF, UNDERLINE_ON,
+    BOLD_OFF, BOLD_ON, CLEAR_LINE, CURSOR_UP, DIM_ON, ITALIC_OFF, ITALIC_ON, RESET, STRIKEOUT_OFF,
+    STRIKEOUT_ON, UNDERLINE_OFF, UNDERLINE_ON,
 };
 use streamdown_ansi::color::hex2rgb;
 
@@ -79,7 +93,10 @@ pub fn bg_color(color: &str) -> String {
         String::new()
     }
 }
-use streamdown_parser::{InlineElement, ParseEvent};
+use streamdown_parser::{
+    AlertKind, Alignment, FrontMatterFormat, InlineElement, InlineSpan, ParseEvent,
+    front_matter_fields,
+};
 use streamdown_syntax::Highlighter;
 
 /// Render style configuration.
@@ -108,10 +125,16 @@ pub struct RenderStyle {
     pub code_bg: String,
     /// Color for code block language labels
     pub code_label: String,
+    /// Background color for code lines highlighted by the info string
+    pub code_highlight_bg: String,
 
     // Lists
     /// Color for list bullet markers
     pub bullet: String,
+    /// Color for unchecked task list boxes
+    pub checkbox: String,
+    /// Color for checked task list boxes
+    pub checkbox_checked: String,
 
     // Tables
     /// Background color for table headers
@@ -122,6 +145,21 @@ pub struct RenderStyle {
     // Borders and decorations
     /// Color for blockquote borders
     pub blockquote_border: String,
+    /// Color for note alert borders and titles
+    #[serde(default = "default_alert_note")]
+    pub alert_note: String,
+    /// Color for tip alert borders and titles
+    #[serde(default = "default_alert_tip")]
+    pub alert_tip: String,
+    /// Color for important alert borders and titles
+    #[serde(default = "default_alert_important")]
+    pub alert_important: String,
+    /// Color for warning alert borders and titles
+    #[serde(default = "default_alert_warning")]
+    pub alert_warning: String,
+    /// Color for caution alert borders and titles
+    #[serde(default = "default_alert_caution")]
+    pub alert_caution: String,
     /// Color for think block borders
     pub think_border: String,
     /// Color for horizontal rules
@@ -134,10 +172,25 @@ pub struct RenderStyle {
     pub image_marker: String,
     /// Color for footnote markers
     pub footnote: String,
+    /// Background color for `==highlighted==` text
+    #[serde(default = "default_highlight_bg")]
+    pub highlight_bg: String,
 
     // Layout
     /// Whether h1/h2 headings should be centered (default: true)
     pub heading_centered: bool,
+    /// Left margin in characters (default: 0)
+    #[serde(default)]
+    pub margin: usize,
+
+    // Lists
+    /// Bullet glyph for each list nesting level, repeating for deeper levels
+    #[serde(default = "default_bullets")]
+    pub bullets: Vec<String>,
+    /// Numbering style for each list nesting level, repeating for deeper
+    /// levels
+    #[serde(default = "default_numbering")]
+    pub numbering: Vec<NumberingStyle>,
 }
 
 impl Default for RenderStyle {
@@ -152,20 +205,78 @@ impl Default for RenderStyle {
             h6: "grey".to_string(),
             code_bg: "black".to_string(),
             code_label: "cyan".to_string(),
+            code_highlight_bg: "dark_grey".to_string(),
             bullet: "cyan".to_string(),
+            checkbox: "cyan".to_string(),
+            checkbox_checked: "light_green".to_string(),
             table_header_bg: "blue".to_string(),
             table_border: "grey".to_string(),
             blockquote_border: "grey".to_string(),
+            alert_note: default_alert_note(),
+            alert_tip: default_alert_tip(),
+            alert_important: default_alert_important(),
+            alert_warning: default_alert_warning(),
+            alert_caution: default_alert_caution(),
             think_border: "grey".to_string(),
             hr: "dark_grey".to_string(),
             link_url: "grey".to_string(),
             image_marker: "cyan".to_string(),
             footnote: "cyan".to_string(),
+            highlight_bg: default_highlight_bg(),
             heading_centered: true,
+            margin: 0,
+            bullets: default_bullets(),
+            numbering: default_numbering(),
         }
     }
 }
 
+fn default_bullets() -> Vec<String> {
+    ["•", "◦", "▪", "‣"].map(String::from).to_vec()
+}
+
+fn default_numbering() -> Vec<NumberingStyle> {
+    vec![NumberingStyle::Decimal]
+}
+
+fn default_highlight_bg() -> String {
+    "dark_grey".to_string()
+}
+
+fn default_alert_note() -> String {
+    "light_blue".to_string()
+}
+
+fn default_alert_tip() -> String {
+    "light_green".to_string()
+}
+
+fn default_alert_important() -> String {
+    "purple".to_string()
+}
+
+fn default_alert_warning() -> String {
+    "yellow".to_string()
+}
+
+fn default_alert_caution() -> String {
+    "red".to_string()
+}
+
+/// Turn a ComputedStyle color fragment (`"r;g;bm"`) into a hex color
+/// that [`fg_color`] and [`bg_color`] can resolve.
+fn fragment_hex(fragment: &str) -> String {
+    let rgb: Vec<u8> = fragment
+        .trim_end_matches('m')
+        .split(';')
+        .filter_map(|part| part.parse().ok())
+        .collect();
+    match rgb[..] {
+        [r, g, b] => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        _ => fragment.to_string(),
+    }
+}
+
 impl RenderStyle {
     /// Create a RenderStyle from a ComputedStyle.
     ///
@@ -181,16 +292,41 @@ impl RenderStyle {
             h6: computed.grey.clone(),
             code_bg: computed.dark.clone(),
             code_label: computed.bright.clone(),
+            code_highlight_bg: fragment_hex(&computed.mid),
             bullet: computed.symbol.clone(),
+            checkbox: fragment_hex(&computed.symbol),
+            checkbox_checked: fragment_hex(&computed.bright),
             table_header_bg: computed.mid.clone(),
             table_border: computed.grey.clone(),
             blockquote_border: computed.grey.clone(),
+            alert_note: computed.alert_note.clone(),
+            alert_tip: computed.alert_tip.clone(),
+            alert_important: computed.alert_important.clone(),
+            alert_warning: computed.alert_warning.clone(),
+            alert_caution: computed.alert_caution.clone(),
             think_border: computed.grey.clone(),
             hr: computed.grey.clone(),
             link_url: computed.bright.clone(),
             image_marker: computed.symbol.clone(),
             footnote: computed.bright.clone(),
+            highlight_bg: fragment_hex(&computed.mid),
             heading_centered: true,
+            margin: computed.margin_spaces.len(),
+            bullets: if computed.bullets.is_empty() {
+                default_bullets()
+            } else {
+                computed.bullets.clone()
+            },
+            // Unknown style names are skipped
+            numbering: match computed
+                .numbering
+                .iter()
+                .filter_map(|name| name.parse().ok())
+                .collect::<Vec<_>>()
+            {
+                numbering if numbering.is_empty() => default_numbering(),
+                numbering => numbering,
+            },
         }
     }
 }
@@ -209,10 +345,8 @@ pub struct Renderer<W: Write> {
     features: RenderFeatures,
     /// Current column position
     column: usize,
-    /// Current code language
-    code_language: Option<String>,
-    /// Raw code buffer (for clipboard/savebrace)
-    code_buffer: String,
+    /// Current code block (highlight state and raw code)
+    code_state: CodeBlockState,
     /// Table state
     table_state: TableState,
     /// List state
@@ -221,6 +355,47 @@ pub struct Renderer<W: Write> {
     in_blockquote: bool,
     /// Blockquote depth
     blockquote_depth: usize,
+    /// Alert kind of each open blockquote level, `None` for plain quotes
+    quote_alerts: Vec<Option<AlertKind>>,
+    /// List indentation outside the open quote, when it is nested in an item
+    quote_indent: usize,
+    /// Indentation of the list item content that blocks are nested in
+    list_indent: usize,
+    /// Number of open `<details>` boxes
+    details_depth: usize,
+    /// Styled inline text of the current paragraph, wrapped when flushed
+    paragraph: String,
+    /// Layout of the list item whose text the paragraph holds
+    paragraph_item: Option<ItemLayout>,
+    /// Whether a hard break already ended the current line
+    hard_break: bool,
+    /// Rows written by the last paragraph flush, if nothing followed it
+    retractable_rows: usize,
+    /// Footnote references and definitions awaiting the notes section
+    footnotes: FootnoteState,
+    /// Capture buffer used while rendering a preview
+    preview_buffer: Option<Vec<u8>>,
+    /// Screen rows occupied by the current preview
+    preview_rows: usize,
+}
+
+/// Renderer block state saved while drawing a preview.
+struct PreviewSnapshot {
+    column: usize,
+    code_state: CodeBlockState,
+    table_state: TableState,
+    list_state: ListState,
+    in_blockquote: bool,
+    blockquote_depth: usize,
+    quote_alerts: Vec<Option<AlertKind>>,
+    quote_indent: usize,
+    list_indent: usize,
+    details_depth: usize,
+    paragraph: String,
+    paragraph_item: Option<ItemLayout>,
+    hard_break: bool,
+    retractable_rows: usize,
+    footnotes: FootnoteState,
 }
 
 impl<W: Write> Renderer<W> {
@@ -233,12 +408,22 @@ impl<W: Write> Renderer<W> {
             style: RenderStyle::default(),
             features: RenderFeatures::default(),
             column: 0,
-            code_language: None,
-            code_buffer: String::new(),
+            code_state: CodeBlockState::new(),
             table_state: TableState::new(),
             list_state: ListState::new(),
             in_blockquote: false,
             blockquote_depth: 0,
+            quote_alerts: Vec::new(),
+            quote_indent: 0,
+            list_indent: 0,
+            details_depth: 0,
+            paragraph: String::new(),
+            paragraph_item: None,
+            hard_break: false,
+            retractable_rows: 0,
+            footnotes: FootnoteState::new(),
+            preview_buffer: None,
+            preview_rows: 0,
         }
     }
 
@@ -301,14 +486,100 @@ impl<W: Write> Renderer<W> {
         &self.features
     }
 
+    /// Get a mutable reference to the underlying writer.
+    pub fn writer_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consume the renderer and return the underlying writer.
+    pub fn into_writer(self) -> W {
+        self.writer
+    }
+
+    /// Render front matter per [`RenderFeatures::front_matter`], through the
+    /// events for the equivalent code block, or heading and table.
+    fn render_front_matter(&mut self, format: FrontMatterFormat, raw: &str) -> std::io::Result<()> {
+        let mut events = Vec::new();
+        match self.features.front_matter {
+            FrontMatterMode::Hide => {}
+            FrontMatterMode::Raw => {
+                events.push(ParseEvent::CodeBlockStart {
+                    language: Some(format.language().to_string()),
+                    indent: 0,
+                    info: Default::default(),
+                });
+                events.extend(
+                    raw.lines()
+                        .map(|line| ParseEvent::CodeBlockLine(line.to_string())),
+                );
+                events.push(ParseEvent::CodeBlockEnd);
+            }
+            FrontMatterMode::Table => {
+                // Metadata values are shown as written, not as markdown
+                let plain = |text: String| vec![InlineSpan::plain(InlineElement::Text(text))];
+                let mut fields = front_matter_fields(format, raw);
+                if let Some(i) = fields.iter().position(|(key, _)| key == "title") {
+                    let (_, title) = fields.remove(i);
+                    events.push(ParseEvent::Heading {
+                        level: 1,
+                        content: plain(title),
+                    });
+                }
+                if !fields.is_empty() {
+                    events.push(ParseEvent::TableHeader(vec![
+                        plain("Key".to_string()),
+                        plain("Value".to_string()),
+                    ]));
+                    events.push(ParseEvent::TableSeparator(vec![Alignment::None; 2]));
+                    events.extend(
+                        fields.into_iter().map(|(key, value)| {
+                            ParseEvent::TableRow(vec![plain(key), plain(value)])
+                        }),
+                    );
+                    events.push(ParseEvent::TableEnd);
+                }
+            }
+        }
+        for event in &events {
+            self.render_event(event)?;
+        }
+        Ok(())
+    }
+
+    /// Open a blockquote level, recording its alert kind if it is an alert.
+    fn open_quote(&mut self, depth: usize, alert: Option<AlertKind>) {
+        // A quote opened inside a list item sits at the item's content column
+        if depth == 1 {
+            self.quote_indent = self.list_indent;
+        }
+        self.in_blockquote = true;
+        self.blockquote_depth = depth;
+        self.quote_alerts.resize(depth.saturating_sub(1), None);
+        self.quote_alerts.push(alert);
+    }
+
     /// Calculate the left margin based on current state.
     fn left_margin(&self) -> String {
+        let mut margin = " ".repeat(self.style.margin);
+        let mut list_indent = self.list_indent;
         if self.in_blockquote {
+            margin.push_str(&" ".repeat(self.quote_indent));
+            list_indent = list_indent.saturating_sub(self.quote_indent);
+            // Alert levels take their kind's colour
+            for level in 0..self.blockquote_depth {
+                let color = match self.quote_alerts.get(level) {
+                    Some(Some(kind)) => alert_color(*kind, &self.style),
+                    _ => &self.style.blockquote_border,
+                };
+                margin.push_str(&format!("{}│{} ", fg_color(color), RESET));
+            }
+        }
+        if self.details_depth > 0 {
             let border = format!("{}│{} ", fg_color(&self.style.blockquote_border), RESET);
-            border.repeat(self.blockquote_depth)
-        } else {
-            String::new()
+            margin.push_str(&border.repeat(self.details_depth));
         }
+        margin.push_str(&" ".repeat(list_indent));
+        margin
     }
 
     /// Calculate the current available width.
@@ -318,92 +589,381 @@ impl<W: Write> Renderer<W> {
         } else {
             0
         };
-        self.width.saturating_sub(margin_width)
+        self.width
+            .saturating_sub(self.style.margin)
+            .saturating_sub(margin_width)
+            .saturating_sub(self.details_depth * 2)
+            .saturating_sub(self.list_indent)
     }
 
     /// Write a string to the output.
     fn write(&mut self, s: &str) -> std::io::Result<()> {
-        write!(self.writer, "{}", s)
+        match self.preview_buffer.as_mut() {
+            Some(buffer) => {
+                buffer.extend_from_slice(s.as_bytes());
+                Ok(())
+            }
+            None => write!(self.writer, "{}", s),
+        }
     }
 
     /// Write a line to the output.
     fn writeln(&mut self, s: &str) -> std::io::Result<()> {
-        writeln!(self.writer, "{}", s)?;
+        self.write(s)?;
+        self.write("\n")?;
         self.column = 0;
+        self.retractable_rows = 0;
         Ok(())
     }
 
+    /// Append styled inline text to the current paragraph.
+    fn push_inline(&mut self, s: &str) {
+        self.paragraph.push_str(s);
+    }
+
+    /// Wrap and write the buffered paragraph text.
+    ///
+    /// A list item's text is wrapped under its marker.
+    fn flush_paragraph(&mut self) -> std::io::Result<()> {
+        if self.paragraph.is_empty() && self.paragraph_item.is_none() {
+            return Ok(());
+        }
+        let text = std::mem::take(&mut self.paragraph);
+        let wrapped = match self.paragraph_item.take() {
+            Some(item) => {
+                let wrapped = text_wrap(
+                    &text,
+                    item.content_width,
+                    0,
+                    &item.first_prefix,
+                    &item.next_prefix,
+                    false,
+                    true,
+                );
+                if wrapped.is_empty() {
+                    self.writeln(&item.first_prefix)?;
+                    return Ok(());
+                }
+                wrapped
+            }
+            None => {
+                let margin = self.left_margin();
+                text_wrap(
+                    &text,
+                    self.current_width(),
+                    0,
+                    &margin,
+                    &margin,
+                    false,
+                    false,
+                )
+            }
+        };
+        if wrapped.is_empty() {
+            self.writeln("")?;
+            self.retractable_rows = 1;
+            return Ok(());
+        }
+        let rows = wrapped.lines.len();
+        for line in wrapped.lines {
+            self.writeln(line.trim_end_matches(' '))?;
+        }
+        self.retractable_rows = rows;
+        Ok(())
+    }
+
+    /// Take back the paragraph line a setext underline turned into a heading.
+    fn retract_paragraph(&mut self) -> std::io::Result<()> {
+        if !self.paragraph.is_empty() {
+            // Still buffered (reflow), nothing on screen yet
+            self.paragraph.clear();
+            return Ok(());
+        }
+        // A preview is drawn below the committed output, which stays put
+        if self.preview_buffer.is_some() {
+            return Ok(());
+        }
+        for _ in 0..std::mem::take(&mut self.retractable_rows) {
+            write!(self.writer, "{}\r{}", CURSOR_UP, CLEAR_LINE)?;
+        }
+        Ok(())
+    }
+
+    /// Lay out the buffered table rows from their content and render them.
+    fn flush_table(&mut self) -> std::io::Result<()> {
+        if !self.table_state.buffering {
+            return Ok(());
+        }
+        self.table_state.buffering = false;
+        let pending = std::mem::take(&mut self.table_state.pending);
+
+        let rows: Vec<&[Vec<InlineSpan>]> = pending
+            .iter()
+            .filter_map(|event| match event {
+                ParseEvent::TableHeader(cells) | ParseEvent::TableRow(cells) => {
+                    Some(cells.as_slice())
+                }
+                _ => None,
+            })
+            .collect();
+        let width = self.current_width();
+        self.table_state.calculate_content_widths(&rows, width);
+
+        // Alignment is known up front, so header cells are aligned too
+        if let Some(alignments) = pending.iter().find_map(|event| match event {
+            ParseEvent::TableSeparator(alignments) => Some(alignments),
+            _ => None,
+        }) {
+            self.table_state.alignments = alignments.clone();
+        }
+
+        for event in &pending {
+            self.render_table_event(event)?;
+        }
+        Ok(())
+    }
+
+    /// Render a single table row or separator with the current layout.
+    fn render_table_event(&mut self, event: &ParseEvent) -> std::io::Result<()> {
+        let width = self.current_width();
+        let margin = self.left_margin();
+        match event {
+            ParseEvent::TableHeader(cells) | ParseEvent::TableRow(cells) => {
+                self.table_state.is_header = matches!(event, ParseEvent::TableHeader(_));
+                let style = self.style.clone();
+                let lines =
+                    render_table_row(cells, &mut self.table_state, width, &margin, &style, false);
+                for line in lines {
+                    self.writeln(&line)?;
+                }
+            }
+            ParseEvent::TableSeparator(alignments) => {
+                self.table_state.alignments = alignments.clone();
+                let sep = render_table_separator(&self.table_state, width, &margin, &self.style);
+                self.writeln(&sep)?;
+                self.table_state.end_header();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Flush any buffered paragraph text and table rows. Call once at the
+    /// end of input.
+    pub fn finish(&mut self) -> std::io::Result<()> {
+        self.flush_paragraph()?;
+        self.flush_table()?;
+        self.flush_notes()?;
+        self.writer.flush()
+    }
+
+    /// Write the notes section for the footnotes collected so far.
+    fn flush_notes(&mut self) -> std::io::Result<()> {
+        let width = self.current_width();
+        let margin = self.left_margin();
+        let lines = render_notes(&mut self.footnotes, width, &margin, &self.style);
+        for line in lines {
+            self.writeln(&line)?;
+        }
+        Ok(())
+    }
+
+    /// Record a footnote reference and push its marker.
+    fn push_footnote_ref(&mut self, marker: &str) {
+        let label = footnote_label(marker);
+        self.footnotes.reference(&label);
+        let rendered = render_footnote_ref(&label, marker, &self.style);
+        self.push_inline(&rendered);
+    }
+
+    /// Whether a preview is currently drawn on screen.
+    pub fn has_preview(&self) -> bool {
+        self.preview_rows > 0
+    }
+
+    /// Draw provisional events for an in-progress line.
+    ///
+    /// The output replaces any previous preview in place (carriage return
+    /// plus `CLEAR_LINE`), and renderer state is left untouched so the line
+    /// can be redrawn as more input arrives. The next committed event clears
+    /// the preview first. Synchronized output is left to the caller, since
+    /// the redraw usually shares a block with freshly committed lines.
+    pub fn render_preview(&mut self, events: &[ParseEvent]) -> std::io::Result<()> {
+        let snapshot = self.snapshot();
+        self.preview_buffer = Some(Vec::new());
+        let result = self.render(events).and_then(|_| self.flush_paragraph());
+        let captured = self.preview_buffer.take().unwrap_or_default();
+        self.restore(snapshot);
+        result?;
+
+        // Keep the cursor on the last preview row so it can be erased
+        let text = String::from_utf8_lossy(&captured);
+        let text = text.trim_end_matches('\n');
+
+        self.clear_preview()?;
+        write!(self.writer, "{}", text)?;
+        self.preview_rows = screen_rows(text, terminal_width());
+        Ok(())
+    }
+
+    /// Erase the preview drawn by [`render_preview`](Renderer::render_preview).
+    pub fn clear_preview(&mut self) -> std::io::Result<()> {
+        if self.preview_rows == 0 {
+            return Ok(());
+        }
+        write!(self.writer, "\r{}", CLEAR_LINE)?;
+        for _ in 1..self.preview_rows {
+            write!(self.writer, "{}\r{}", CURSOR_UP, CLEAR_LINE)?;
+        }
+        self.preview_rows = 0;
+        Ok(())
+    }
+
+    /// Save the block state that rendering may change.
+    fn snapshot(&self) -> PreviewSnapshot {
+        PreviewSnapshot {
+            column: self.column,
+            code_state: self.code_state.clone(),
+            table_state: self.table_state.clone(),
+            list_state: self.list_state.clone(),
+            in_blockquote: self.in_blockquote,
+            blockquote_depth: self.blockquote_depth,
+            quote_alerts: self.quote_alerts.clone(),
+            quote_indent: self.quote_indent,
+            list_indent: self.list_indent,
+            details_depth: self.details_depth,
+            paragraph: self.paragraph.clone(),
+            paragraph_item: self.paragraph_item.clone(),
+            hard_break: self.hard_break,
+            retractable_rows: self.retractable_rows,
+            footnotes: self.footnotes.clone(),
+        }
+    }
+
+    /// Restore block state saved by [`snapshot`](Renderer::snapshot).
+    fn restore(&mut self, snapshot: PreviewSnapshot) {
+        self.column = snapshot.column;
+        self.code_state = snapshot.code_state;
+        self.table_state = snapshot.table_state;
+        self.list_state = snapshot.list_state;
+        self.in_blockquote = snapshot.in_blockquote;
+        self.blockquote_depth = snapshot.blockquote_depth;
+        self.quote_alerts = snapshot.quote_alerts;
+        self.quote_indent = snapshot.quote_indent;
+        self.list_indent = snapshot.list_indent;
+        self.details_depth = snapshot.details_depth;
+        self.paragraph = snapshot.paragraph;
+        self.paragraph_item = snapshot.paragraph_item;
+        self.hard_break = snapshot.hard_break;
+        self.retractable_rows = snapshot.retractable_rows;
+        self.footnotes = snapshot.footnotes;
+    }
+
     /// Render a single parse event.
     pub fn render_event(&mut self, event: &ParseEvent) -> std::io::Result<()> {
+        let previewing = self.preview_buffer.is_some();
+        if !previewing {
+            self.clear_preview()?;
+        }
+
+        if let ParseEvent::SetextHeading { .. } = event {
+            self.retract_paragraph()?;
+        }
+
+        // Any block-level event ends the current paragraph, except more
+        // lines of a list item's text
+        let is_inline = event.is_inline() || self.continues_item_text(event);
+        if !is_inline && !matches!(event, ParseEvent::Newline) {
+            self.flush_paragraph()?;
+            // Buffered rows go out before the block that follows the table
+            if !matches!(
+                event,
+                ParseEvent::TableHeader(_)
+                    | ParseEvent::TableSeparator(_)
+                    | ParseEvent::TableRow(_)
+                    | ParseEvent::TableEnd
+            ) {
+                self.flush_table()?;
+            }
+        }
+
         match event {
             // === Inline elements ===
+            // Buffered into the paragraph and wrapped when it is flushed
             ParseEvent::Text(text) => {
-                // Decode HTML entities like &copy; -> ©
-                let decoded = streamdown_parser::decode_html_entities(text);
-                self.write(&decoded)?;
-                self.column += streamdown_ansi::utils::visible_length(&decoded);
+                self.push_inline(text);
+                self.column += streamdown_ansi::utils::visible_length(text);
             }
 
             ParseEvent::InlineCode(code) => {
                 let bg = bg_color(&self.style.code_bg);
-                self.write(&format!("{}{} {} {}", bg, DIM_ON, code, RESET))?;
+                self.push_inline(&format!("{}{} {} {}", bg, DIM_ON, code, RESET));
             }
 
             ParseEvent::Bold(text) => {
-                self.write(&format!("{}{}{}", BOLD_ON, text, BOLD_OFF))?;
+                self.push_inline(&format!("{}{}{}", BOLD_ON, text, BOLD_OFF));
             }
 
             ParseEvent::Italic(text) => {
-                self.write(&format!("{}{}{}", ITALIC_ON, text, ITALIC_OFF))?;
+                self.push_inline(&format!("{}{}{}", ITALIC_ON, text, ITALIC_OFF));
             }
 
             ParseEvent::BoldItalic(text) => {
-                self.write(&format!(
+                self.push_inline(&format!(
                     "{}{}{}{}{}",
                     BOLD_ON, ITALIC_ON, text, ITALIC_OFF, BOLD_OFF
-                ))?;
+                ));
             }
 
             ParseEvent::Underline(text) => {
-                self.write(&format!("{}{}{}", UNDERLINE_ON, text, UNDERLINE_OFF))?;
+                self.push_inline(&format!("{}{}{}", UNDERLINE_ON, text, UNDERLINE_OFF));
             }
 
             ParseEvent::Strikeout(text) => {
-                self.write(&format!("{}{}{}", STRIKEOUT_ON, text, STRIKEOUT_OFF))?;
+                self.push_inline(&format!("{}{}{}", STRIKEOUT_ON, text, STRIKEOUT_OFF));
             }
 
             ParseEvent::Link { text, url } => {
-                // Render as: underlined text (url)
-                // Also include OSC 8 hyperlink for terminals that support it
-                let fg = fg_color(&self.style.link_url);
-
-                // OSC 8 start
-                self.write("\x1b]8;;")?;
-                self.write(url)?;
-                self.write("\x1b\\")?;
+                // Underlined text (url), hyperlinked with OSC 8
+                let rendered = render_link(text, url, &self.style);
+                self.push_inline(&rendered);
+            }
 
-                // Underlined text
-                self.write(&format!("{}{}{}", UNDERLINE_ON, text, UNDERLINE_OFF))?;
+            ParseEvent::LinkReference { text, label } => {
+                let rendered = render_link_reference(text, label, &self.style);
+                self.push_inline(&rendered);
+            }
 
-                // OSC 8 end
-                self.write("\x1b]8;;\x1b\\")?;
+            // Ends the current line; the paragraph goes on
+            ParseEvent::HardBreak => {
+                self.flush_paragraph()?;
+                self.hard_break = true;
+            }
 
-                // Show URL in parentheses (dimmed)
-                self.write(&format!(" {}({}){}", fg, url, RESET))?;
+            ParseEvent::Kbd(key) => {
+                let rendered = render_kbd(key, &self.style);
+                self.push_inline(&rendered);
             }
 
             ParseEvent::Image { alt, url: _ } => {
                 let fg = fg_color(&self.style.image_marker);
-                self.write(&format!("{}[\u{1F5BC} {}]{}", fg, alt, RESET))?;
+                self.push_inline(&format!("{}[\u{1F5BC} {}]{}", fg, alt, RESET));
             }
 
             ParseEvent::Footnote(superscript) => {
-                let fg = fg_color(&self.style.footnote);
-                self.write(&format!("{}{}{}", fg, superscript, RESET))?;
+                self.push_footnote_ref(superscript);
             }
 
             // === Block elements ===
-            ParseEvent::Heading { level, content } => {
+            ParseEvent::FrontMatter { format, raw } => {
+                self.render_front_matter(*format, raw)?;
+            }
+
+            ParseEvent::Heading { level, content }
+            | ParseEvent::SetextHeading { level, content } => {
+                if self.features.footnote_boundary == FootnoteBoundary::Heading {
+                    self.flush_notes()?;
+                }
                 let lines = render_heading(
                     *level,
                     content,
@@ -416,12 +976,17 @@ impl<W: Write> Renderer<W> {
                 }
             }
 
-            ParseEvent::CodeBlockStart { language, .. } => {
-                self.code_language = language.clone();
-                self.code_buffer.clear();
+            ParseEvent::CodeBlockStart { language, info, .. } => {
+                self.code_state.start(
+                    &self.highlighter,
+                    language.clone(),
+                    info.clone(),
+                    &self.style,
+                );
 
                 let lines = code::render_code_start(
                     language.as_deref(),
+                    info.title.as_deref(),
                     self.current_width(),
                     &self.left_margin(),
                     &self.style,
@@ -434,31 +999,23 @@ impl<W: Write> Renderer<W> {
 
             ParseEvent::CodeBlockLine(line) => {
                 // Buffer raw code for clipboard/savebrace
-                if !self.code_buffer.is_empty() {
-                    self.code_buffer.push('\n');
-                }
-                self.code_buffer.push_str(line);
-
-                let lang = self.code_language.as_deref().unwrap_or("text");
-                let highlighted = self.highlighter.highlight(line, Some(lang));
+                self.code_state.add_raw_line(line);
 
-                // Render with background
-                let bg = bg_color(&self.style.code_bg);
+                // Highlight with the state carried over from previous lines
+                let width = self.current_width();
                 let margin = self.left_margin();
-                let trimmed = highlighted.trim_end();
-                let padding_needed = self
-                    .current_width()
-                    .saturating_sub(streamdown_ansi::utils::visible_length(trimmed));
-
-                self.writeln(&format!(
-                    "{}{}{}{}{}{}",
-                    margin,
-                    bg,
-                    trimmed,
-                    bg,
-                    " ".repeat(padding_needed),
-                    RESET
-                ))?;
+                let lines = code::render_code_line(
+                    line,
+                    &mut self.code_state,
+                    &self.highlighter,
+                    width,
+                    &margin,
+                    &self.style,
+                    self.features.pretty_broken,
+                );
+                for line in lines {
+                    self.writeln(&line)?;
+                }
             }
 
             ParseEvent::CodeBlockEnd => {
@@ -473,107 +1030,104 @@ impl<W: Write> Renderer<W> {
                 }
 
                 // Handle clipboard integration (OSC 52)
-                if self.features.clipboard && !self.code_buffer.is_empty() {
-                    let _ = copy_to_clipboard(&self.code_buffer, &mut self.writer);
+                let code = self.code_state.raw_code();
+                if self.features.clipboard && !previewing && !code.is_empty() {
+                    let _ = copy_to_clipboard(code, &mut self.writer);
                 }
 
                 // Handle savebrace
-                if self.features.savebrace && !self.code_buffer.is_empty() {
-                    let _ = savebrace(&self.code_buffer);
+                if self.features.savebrace && !previewing && !code.is_empty() {
+                    let _ = savebrace(code);
                 }
 
-                self.code_language = None;
-                self.code_buffer.clear();
+                self.code_state.end();
             }
 
             ParseEvent::ListItem {
                 indent,
                 bullet,
                 content,
+                checked,
             } => {
-                let lines = render_list_item(
+                // The item's own line is laid out from the list's margin
+                self.list_indent = 0;
+                let layout = layout_list_item(
                     *indent,
                     bullet,
-                    content,
+                    *checked,
                     self.current_width(),
                     &self.left_margin(),
                     &self.style,
                     &mut self.list_state,
                 );
-                for line in lines {
-                    self.writeln(&line)?;
+                // The item's text is a paragraph wrapped under its marker
+                self.paragraph_item = Some(layout);
+                for span in content {
+                    self.render_inline_span(span)?;
+                }
+                let broken = std::mem::take(&mut self.hard_break);
+                if !self.features.reflow {
+                    self.flush_paragraph()?;
+                } else if !broken {
+                    // Soft break: lines continuing the item join its text
+                    self.push_inline(" ");
                 }
             }
 
+            ParseEvent::ListContinuation { indent } => {
+                self.list_indent = self.list_state.continue_item(*indent);
+            }
+
             ParseEvent::ListEnd => {
+                self.list_indent = 0;
                 list::render_list_end(&mut self.list_state);
             }
 
-            ParseEvent::TableHeader(cells) => {
-                self.table_state.reset();
-                self.table_state.is_header = true;
-
-                let width = self.current_width();
-                let margin = self.left_margin();
-                let style = self.style.clone();
-                let lines =
-                    render_table_row(cells, &mut self.table_state, width, &margin, &style, false);
-                for line in lines {
-                    self.writeln(&line)?;
+            ParseEvent::TableHeader(_)
+            | ParseEvent::TableRow(_)
+            | ParseEvent::TableSeparator(_) => {
+                if !self.table_state.active {
+                    self.table_state.reset();
+                    self.table_state.active = true;
+                    self.table_state.buffering = self.features.table_buffer_rows > 0;
                 }
-            }
 
-            ParseEvent::TableRow(cells) => {
-                let width = self.current_width();
-                let margin = self.left_margin();
-                let style = self.style.clone();
-                let lines =
-                    render_table_row(cells, &mut self.table_state, width, &margin, &style, false);
-                for line in lines {
-                    self.writeln(&line)?;
+                if self.table_state.buffering {
+                    self.table_state.pending.push(event.clone());
+                    if self.table_state.pending_rows() >= self.features.table_buffer_rows {
+                        // Buffer full: lay out from what we have and stream the rest
+                        self.flush_table()?;
+                    }
+                } else {
+                    self.render_table_event(event)?;
                 }
             }
 
-            ParseEvent::TableSeparator => {
-                let sep = render_table_separator(
-                    &self.table_state,
-                    self.current_width(),
-                    &self.left_margin(),
-                    &self.style,
-                );
-                self.writeln(&sep)?;
-                self.table_state.end_header();
-            }
-
             ParseEvent::TableEnd => {
+                self.flush_table()?;
                 self.table_state.reset();
             }
 
+            // Quote content arrives as ordinary block events, which pick up
+            // the │ border through left_margin()
             ParseEvent::BlockquoteStart { depth } => {
-                self.in_blockquote = true;
-                self.blockquote_depth = *depth;
+                self.open_quote(*depth, None);
             }
 
-            ParseEvent::BlockquoteLine(text) => {
-                let margin = self.left_margin();
-                // Wrap text to fit
-                let wrapped = text_wrap(
-                    text,
-                    self.current_width(),
-                    0,
-                    &margin,
-                    &margin,
-                    false,
-                    false,
-                );
-                for line in wrapped.lines {
-                    self.writeln(&line)?;
-                }
+            ParseEvent::AlertStart { kind, title, depth } => {
+                self.open_quote(*depth, Some(*kind));
+                let line =
+                    render_alert_title(*kind, title.as_deref(), &self.left_margin(), &self.style);
+                self.writeln(&line)?;
             }
 
             ParseEvent::BlockquoteEnd => {
-                self.in_blockquote = false;
-                self.blockquote_depth = 0;
+                self.blockquote_depth = self.blockquote_depth.saturating_sub(1);
+                self.quote_alerts.truncate(self.blockquote_depth);
+                self.in_blockquote = self.blockquote_depth > 0;
+                if !self.in_blockquote {
+                    self.quote_indent = 0;
+                }
             }
 
             ParseEvent::ThinkBlockStart => {
@@ -595,6 +1149,41 @@ impl<W: Write> Renderer<W> {
                 self.blockquote_depth = 0;
             }
 
+            // Definitions are collected silently and printed as notes
+            ParseEvent::FootnoteDefinition { label, content } => {
+                self.footnotes.define(label, content);
+            }
+
+            ParseEvent::DetailsStart => {
+                let line =
+                    render_details_start(self.current_width(), &self.left_margin(), &self.style);
+                self.writeln(&line)?;
+                self.details_depth += 1;
+            }
+
+            ParseEvent::DetailsSummary(summary) => {
+                let line = render_details_summary(summary, &self.left_margin(), &self.style);
+                self.writeln(&line)?;
+            }
+
+            ParseEvent::DetailsEnd => {
+                self.details_depth = self.details_depth.saturating_sub(1);
+                let line =
+                    render_details_end(self.current_width(), &self.left_margin(), &self.style);
+                self.writeln(&line)?;
+            }
+
+            // A deferred reference's target, on a line of its own
+            ParseEvent::LinkDefinition { label, url, .. } => {
+                let line = render_link_definition(label, url, &self.left_margin(), &self.style);
+                self.writeln(&line)?;
+            }
+
+            ParseEvent::UnresolvedLinkReference { label } => {
+                let line = render_unresolved_reference(label, &self.left_margin(), &self.style);
+                self.writeln(&line)?;
+            }
+
             ParseEvent::HorizontalRule => {
                 let fg = fg_color(&self.style.hr);
                 let rule = "─".repeat(self.current_width());
@@ -602,20 +1191,31 @@ impl<W: Write> Renderer<W> {
             }
 
             ParseEvent::EmptyLine => {
-                self.writeln("")?;
+                // Keep the quote border going across blank lines
+                let margin = self.left_margin();
+                self.writeln(margin.trim_end())?;
             }
 
             ParseEvent::Newline => {
-                self.writeln("")?;
+                if std::mem::take(&mut self.hard_break) && self.paragraph.is_empty() {
+                    // The break already ended the line
+                } else if self.paragraph.is_empty() {
+                    self.writeln("")?;
+                } else if self.features.reflow {
+                    // Soft break: keep collecting the paragraph
+                    self.push_inline(" ");
+                } else {
+                    self.flush_paragraph()?;
+                }
             }
 
             ParseEvent::Prompt(prompt) => {
                 self.write(prompt)?;
             }
 
-            ParseEvent::InlineElements(elements) => {
-                for element in elements {
-                    self.render_inline_element(element)?;
+            ParseEvent::InlineElements(spans) => {
+                for span in spans {
+                    self.render_inline_span(span)?;
                 }
             }
         }
@@ -623,51 +1223,33 @@ impl<W: Write> Renderer<W> {
         self.writer.flush()
     }
 
-    /// Render an inline element.
-    fn render_inline_element(&mut self, element: &InlineElement) -> std::io::Result<()> {
-        match element {
-            InlineElement::Text(s) => self.write(s)?,
-            InlineElement::Bold(s) => self.write(&format!("{}{}{}", BOLD_ON, s, BOLD_OFF))?,
-            InlineElement::Italic(s) => self.write(&format!("{}{}{}", ITALIC_ON, s, ITALIC_OFF))?,
-            InlineElement::BoldItalic(s) => self.write(&format!(
-                "{}{}{}{}{}",
-                BOLD_ON, ITALIC_ON, s, ITALIC_OFF, BOLD_OFF
-            ))?,
-            InlineElement::Underline(s) => {
-                self.write(&format!("{}{}{}", UNDERLINE_ON, s, UNDERLINE_OFF))?
-            }
-            InlineElement::Strikeout(s) => {
-                self.write(&format!("{}{}{}", STRIKEOUT_ON, s, STRIKEOUT_OFF))?
+    /// Render an inline span into the current paragraph.
+    fn render_inline_span(&mut self, span: &InlineSpan) -> std::io::Result<()> {
+        match &span.element {
+            InlineElement::HardBreak => {
+                self.flush_paragraph()?;
+                self.hard_break = true;
             }
-            InlineElement::Code(s) => {
-                let bg = bg_color(&self.style.code_bg);
-                self.write(&format!("{} {} {}", bg, s, RESET))?
-            }
-            InlineElement::Link { text, url } => {
-                let fg = fg_color(&self.style.link_url);
-                // OSC 8 start
-                self.write("\x1b]8;;")?;
-                self.write(url)?;
-                self.write("\x1b\\")?;
-                // Underlined text
-                self.write(&format!("{}{}{}", UNDERLINE_ON, text, UNDERLINE_OFF))?;
-                // OSC 8 end
-                self.write("\x1b]8;;\x1b\\")?;
-                // Show URL in parentheses (dimmed)
-                self.write(&format!(" {}({}){}", fg, url, RESET))?;
-            }
-            InlineElement::Image { alt, .. } => {
-                let fg = fg_color(&self.style.image_marker);
-                self.write(&format!("{}[\u{1F5BC} {}]{}", fg, alt, RESET))?
-            }
-            InlineElement::Footnote(s) => {
-                let fg = fg_color(&self.style.footnote);
-                self.write(&format!("{}{}{}", fg, s, RESET))?
+            InlineElement::Footnote(s) if span.style.is_plain() => self.push_footnote_ref(s),
+            _ => {
+                let rendered = inline::render_span(span, &self.style, "");
+                self.push_inline(&rendered)
             }
         }
         Ok(())
     }
 
+    /// Whether `event` continues the list item whose text is buffered.
+    fn continues_item_text(&self, event: &ParseEvent) -> bool {
+        match event {
+            ParseEvent::ListContinuation { indent } => {
+                self.paragraph_item.is_some()
+                    && self.list_state.stack.last().map(|(i, _)| i) == Some(indent)
+            }
+            _ => false,
+        }
+    }
+
     /// Render multiple events.
     pub fn render(&mut self, events: &[ParseEvent]) -> std::io::Result<()> {
         for event in events {
@@ -677,10 +1259,28 @@ impl<W: Write> Renderer<W> {
     }
 }
 
+/// Number of terminal rows `text` occupies at the given column count.
+fn screen_rows(text: &str, columns: usize) -> usize {
+    if text.is_empty() {
+        return 0;
+    }
+    text.split('\n')
+        .map(|line| {
+            streamdown_ansi::utils::visible_length(line)
+                .div_ceil(columns.max(1))
+                .max(1)
+        })
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use streamdown_parser::ListBullet;
+    use streamdown_parser::{CodeInfo, InlineParser, InlineStyle, ListBullet};
+
+    fn spans(text: &str) -> Vec<InlineSpan> {
+        InlineParser::new().parse(text)
+    }
 
     #[test]
     fn test_render_heading() {
@@ -690,7 +1290,7 @@ mod tests {
         renderer
             .render_event(&ParseEvent::Heading {
                 level: 1,
-                content: "Title".to_string(),
+                content: spans("Title"),
             })
             .unwrap();
 
@@ -707,7 +1307,7 @@ mod tests {
         renderer
             .render_event(&ParseEvent::Heading {
                 level: 2,
-                content: "Subtitle".to_string(),
+                content: spans("Subtitle"),
             })
             .unwrap();
 
@@ -725,6 +1325,7 @@ mod tests {
             .render_event(&ParseEvent::CodeBlockStart {
                 language: Some("rust".to_string()),
                 indent: 0,
+                info: CodeInfo::default(),
             })
             .unwrap();
         renderer
@@ -748,6 +1349,7 @@ mod tests {
             .render_event(&ParseEvent::CodeBlockStart {
                 language: Some("rust".to_string()),
                 indent: 0,
+                info: CodeInfo::default(),
             })
             .unwrap();
         renderer.render_event(&ParseEvent::CodeBlockEnd).unwrap();
@@ -766,7 +1368,8 @@ mod tests {
             .render_event(&ParseEvent::ListItem {
                 indent: 0,
                 bullet: ListBullet::Dash,
-                content: "Item".to_string(),
+                content: spans("Item"),
+                checked: None,
             })
             .unwrap();
 
@@ -784,7 +1387,8 @@ mod tests {
             .render_event(&ParseEvent::ListItem {
                 indent: 0,
                 bullet: ListBullet::Ordered(1),
-                content: "First".to_string(),
+                content: spans("First"),
+                checked: None,
             })
             .unwrap();
 
@@ -799,17 +1403,13 @@ mod tests {
         let mut renderer = Renderer::new(&mut output, 80);
 
         renderer
-            .render_event(&ParseEvent::TableHeader(vec![
-                "A".to_string(),
-                "B".to_string(),
-            ]))
+            .render_event(&ParseEvent::TableHeader(vec![spans("A"), spans("B")]))
             .unwrap();
-        renderer.render_event(&ParseEvent::TableSeparator).unwrap();
         renderer
-            .render_event(&ParseEvent::TableRow(vec![
-                "1".to_string(),
-                "2".to_string(),
-            ]))
+            .render_event(&ParseEvent::TableSeparator(vec![Alignment::None; 2]))
+            .unwrap();
+        renderer
+            .render_event(&ParseEvent::TableRow(vec![spans("1"), spans("2")]))
             .unwrap();
         renderer.render_event(&ParseEvent::TableEnd).unwrap();
 
@@ -818,6 +1418,72 @@ mod tests {
         assert!(result.contains("1"));
     }
 
+    fn table_events(rows: usize) -> Vec<ParseEvent> {
+        let mut events = vec![
+            ParseEvent::TableHeader(vec![spans("ID"), spans("Description")]),
+            ParseEvent::TableSeparator(vec![Alignment::Left, Alignment::Right]),
+        ];
+        for i in 0..rows {
+            events.push(ParseEvent::TableRow(vec![
+                spans(&i.to_string()),
+                spans("a fairly long description of the row"),
+            ]));
+        }
+        events.push(ParseEvent::TableEnd);
+        events
+    }
+
+    #[test]
+    fn test_render_table_buffers_until_end() {
+        let mut output = Vec::new();
+        let mut renderer = Renderer::new(&mut output, 80);
+        let events = table_events(2);
+
+        renderer.render(&events[..events.len() - 1]).unwrap();
+        assert!(renderer.writer_mut().is_empty());
+        renderer.render(&events[events.len() - 1..]).unwrap();
+
+        let result = String::from_utf8(output).unwrap();
+        let plain = streamdown_ansi::utils::visible(&result);
+        // The short ID column is laid out narrower than the description
+        let header = plain.lines().next().unwrap();
+        let id_width = header.find('│').unwrap();
+        assert!(id_width < 20, "ID column too wide: {}", header);
+        assert!(plain.contains("a fairly long description of the row"));
+    }
+
+    #[test]
+    fn test_render_table_streams_after_buffer_limit() {
+        let mut output = Vec::new();
+        let features = RenderFeatures {
+            table_buffer_rows: 2,
+            ..Default::default()
+        };
+        let mut renderer = Renderer::with_features(&mut output, 80, features);
+        let events = table_events(3);
+
+        // Header and one body row fill the buffer
+        renderer.render(&events[..3]).unwrap();
+        let flushed = renderer.writer_mut().len();
+        assert!(flushed > 0);
+        renderer.render(&events[3..4]).unwrap();
+        assert!(renderer.writer_mut().len() > flushed);
+    }
+
+    #[test]
+    fn test_render_table_without_buffering() {
+        let mut output = Vec::new();
+        let features = RenderFeatures {
+            table_buffer_rows: 0,
+            ..Default::default()
+        };
+        let mut renderer = Renderer::with_features(&mut output, 80, features);
+        let events = table_events(1);
+
+        renderer.render(&events[..1]).unwrap();
+        assert!(!renderer.writer_mut().is_empty());
+    }
+
     #[test]
     fn test_render_blockquote() {
         let mut output = Vec::new();
@@ -827,14 +1493,39 @@ mod tests {
             .render_event(&ParseEvent::BlockquoteStart { depth: 1 })
             .unwrap();
         renderer
-            .render_event(&ParseEvent::BlockquoteLine("Quote text".to_string()))
+            .render_event(&ParseEvent::Text("Quote text".to_string()))
             .unwrap();
+        renderer.render_event(&ParseEvent::Newline).unwrap();
         renderer.render_event(&ParseEvent::BlockquoteEnd).unwrap();
 
         let result = String::from_utf8(output).unwrap();
+        assert!(result.contains("│"));
         assert!(result.contains("Quote text"));
     }
 
+    #[test]
+    fn test_render_blocks_inside_blockquote() {
+        let mut output = Vec::new();
+        let mut renderer = Renderer::new(&mut output, 80);
+        let mut parser = streamdown_parser::Parser::new();
+        let input =
+            "> - item\n>\n> ```\n> code\n> ```\n>\n> | a | b |\n> |---|---|\n> | 1 | 2 |\n\nafter";
+        renderer.render(&parser.parse_document(input)).unwrap();
+        renderer.finish().unwrap();
+
+        let result = String::from_utf8(output).unwrap();
+        let plain = streamdown_ansi::utils::visible(&result);
+        let lines: Vec<&str> = plain.lines().collect();
+        let after = lines.iter().position(|l| l.contains("after")).unwrap();
+        // Every line of the quote, blank ones included, carries the border
+        for line in &lines[..after - 1] {
+            assert!(line.trim_start().starts_with('│'), "{:?}", line);
+        }
+        assert!(!lines[after].contains('│'));
+        assert!(plain.contains("item") && plain.contains("code"));
+        assert!(!plain.contains("```") && !plain.contains("|---"));
+    }
+
     #[test]
     fn test_render_think_block() {
         let mut output = Vec::new();
@@ -873,6 +1564,7 @@ mod tests {
                 url: "https://example.com".to_string(),
             })
             .unwrap();
+        renderer.finish().unwrap();
 
         let result = String::from_utf8(output).unwrap();
         assert!(result.contains("Click here"));
@@ -899,11 +1591,468 @@ mod tests {
         renderer
             .render_event(&ParseEvent::Heading {
                 level: 2,
-                content: "Red".to_string(),
+                content: spans("Red"),
             })
             .unwrap();
 
         let result = String::from_utf8(output).unwrap();
         assert!(result.contains("Red"));
     }
+
+    #[test]
+    fn test_render_preview_redraws_in_place() {
+        let mut output = Vec::new();
+        let mut renderer = Renderer::new(&mut output, 80);
+
+        let partial = |s: &str| {
+            vec![
+                ParseEvent::InlineElements(vec![InlineSpan::text(s, InlineStyle::PLAIN)]),
+                ParseEvent::Newline,
+            ]
+        };
+        renderer.render_preview(&partial("Hel")).unwrap();
+        assert!(renderer.has_preview());
+        renderer.render_preview(&partial("Hello")).unwrap();
+        renderer.render(&partial("Hello world")).unwrap();
+        assert!(!renderer.has_preview());
+
+        let result = String::from_utf8(output).unwrap();
+        assert!(result.starts_with(&format!("Hel{}\r{}Hello{}", RESET, CLEAR_LINE, RESET)));
+        assert!(!result.contains(streamdown_ansi::codes::SYNC_START));
+        assert!(result.ends_with(&format!("\r{}Hello world{}\n", CLEAR_LINE, RESET)));
+    }
+
+    #[test]
+    fn test_render_alert() {
+        let mut output = Vec::new();
+        let mut renderer = Renderer::new(&mut output, 80);
+        let warning = fg_color(&renderer.style.alert_warning);
+        let plain = fg_color(&renderer.style.blockquote_border);
+
+        renderer
+            .render_event(&ParseEvent::AlertStart {
+                kind: AlertKind::Warning,
+                title: Some(spans("Careful")),
+                depth: 1,
+            })
+            .unwrap();
+        renderer
+            .render_event(&ParseEvent::BlockquoteStart { depth: 2 })
+            .unwrap();
+        renderer
+            .render_event(&ParseEvent::Text("Nested".to_string()))
+            .unwrap();
+        renderer.render_event(&ParseEvent::Newline).unwrap();
+        renderer.render_event(&ParseEvent::BlockquoteEnd).unwrap();
+        renderer.render_event(&ParseEvent::BlockquoteEnd).unwrap();
+        assert!(renderer.quote_alerts.is_empty());
+
+        let result = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(streamdown_ansi::utils::visible(lines[0]), "│ ⚠ Careful");
+        assert_eq!(streamdown_ansi::utils::visible(lines[1]), "│ │ Nested");
+        // The alert's border takes its colour; the nested quote's doesn't
+        assert!(lines[1].starts_with(&format!("{}│{} {}│", warning, RESET, plain)));
+    }
+
+    #[test]
+    fn test_render_front_matter_modes() {
+        let event = ParseEvent::FrontMatter {
+            format: FrontMatterFormat::Yaml,
+            raw: "title: Guide\nauthor: Ada".to_string(),
+        };
+        let render = |mode| {
+            let mut output = Vec::new();
+            let features = RenderFeatures {
+                front_matter: mode,
+                ..Default::default()
+            };
+            let mut renderer = Renderer::with_features(&mut output, 40, features);
+            renderer.render_event(&event).unwrap();
+            streamdown_ansi::utils::visible(&String::from_utf8(output).unwrap())
+        };
+
+        assert!(render(FrontMatterMode::Hide).is_empty());
+
+        let raw = render(FrontMatterMode::Raw);
+        assert!(raw.contains("title: Guide"));
+        assert!(raw.contains("author: Ada"));
+
+        // The title becomes a heading and leaves the table
+        let table = render(FrontMatterMode::Table);
+        let heading = table.lines().find(|l| l.contains("Guide")).unwrap();
+        assert!(!heading.contains("title"));
+        assert!(
+            table
+                .lines()
+                .any(|l| l.contains("author") && l.contains("Ada"))
+        );
+    }
+
+    #[test]
+    fn test_render_preview_keeps_state() {
+        let mut output = Vec::new();
+        let mut renderer = Renderer::new(&mut output, 80);
+
+        renderer
+            .render_preview(&[ParseEvent::BlockquoteStart { depth: 2 }])
+            .unwrap();
+        assert!(!renderer.in_blockquote);
+
+        renderer
+            .render_preview(&[
+                ParseEvent::CodeBlockStart {
+                    language: Some("rust".to_string()),
+                    indent: 0,
+                    info: CodeInfo::default(),
+                },
+                ParseEvent::CodeBlockLine("let x = 1;".to_string()),
+            ])
+            .unwrap();
+        assert!(renderer.code_state.language.is_none());
+        assert!(renderer.code_state.raw_code().is_empty());
+    }
+
+    #[test]
+    fn test_code_highlight_state_spans_lines() {
+        let render = |lines: &[&str]| {
+            let mut output = Vec::new();
+            let mut renderer = Renderer::new(&mut output, 80);
+            renderer
+                .render_event(&ParseEvent::CodeBlockStart {
+                    language: Some("rust".to_string()),
+                    indent: 0,
+                    info: CodeInfo::default(),
+                })
+                .unwrap();
+            for line in lines {
+                renderer
+                    .render_event(&ParseEvent::CodeBlockLine(line.to_string()))
+                    .unwrap();
+            }
+            String::from_utf8(output).unwrap()
+        };
+
+        let fresh = render(&["let x = 1;"]);
+        let fresh_line = fresh.lines().last().unwrap();
+
+        // Inside a block comment the same line must be colored differently
+        let in_comment = render(&["/* start", "let x = 1;"]);
+        assert_ne!(in_comment.lines().last().unwrap(), fresh_line);
+
+        // After a line comment, state must not leak into the next line
+        let after_comment = render(&["// note", "let x = 1;"]);
+        assert_eq!(after_comment.lines().last().unwrap(), fresh_line);
+    }
+
+    fn render_paragraph_lines(renderer: &mut Renderer<&mut Vec<u8>>, lines: &[&str]) {
+        render_lines_with(streamdown_parser::Parser::new(), renderer, lines);
+    }
+
+    /// Render lines through a parser that retracts setext headings.
+    fn render_retracting_lines(renderer: &mut Renderer<&mut Vec<u8>>, lines: &[&str]) {
+        let mut parser = streamdown_parser::Parser::new();
+        parser.set_setext_lookahead(false);
+        render_lines_with(parser, renderer, lines);
+    }
+
+    #[test]
+    fn test_computed_style_highlight() {
+        let computed = streamdown_config::Config::default().computed_style();
+        let style = RenderStyle::from_computed(&computed);
+        let bg = bg_color(&style.highlight_bg);
+        assert_eq!(bg, format!("\x1b[48;2;{}", computed.mid));
+
+        let mut output = Vec::new();
+        let mut renderer = Renderer::with_style(&mut output, 80, style);
+        render_lines_with(streamdown_parser::Parser::new(), &mut renderer, &["==hi=="]);
+        let result = String::from_utf8(output).unwrap();
+        assert!(result.contains(&format!("{}hi", bg)));
+    }
+
+    #[test]
+    fn test_computed_style_code_highlight() {
+        let computed = streamdown_config::Config::default().computed_style();
+        let style = RenderStyle::from_computed(&computed);
+        let bg = bg_color(&style.code_highlight_bg);
+        assert!(!bg.is_empty());
+
+        let mut output = Vec::new();
+        let mut renderer = Renderer::with_style(&mut output, 40, style);
+        let lines = ["```text {2}", "one", "two", "```"];
+        render_lines_with(streamdown_parser::Parser::new(), &mut renderer, &lines);
+        let result = String::from_utf8(output).unwrap();
+        let one = result.lines().find(|l| l.contains("one")).unwrap();
+        let two = result.lines().find(|l| l.contains("two")).unwrap();
+        assert!(!one.contains(&bg) && two.contains(&bg));
+    }
+
+    #[test]
+    fn test_computed_style_checkboxes() {
+        let computed = streamdown_config::Config::default().computed_style();
+        let style = RenderStyle::from_computed(&computed);
+        let unchecked = fg_color(&style.checkbox);
+        let checked = fg_color(&style.checkbox_checked);
+        assert_eq!(unchecked, format!("\x1b[38;2;{}", computed.symbol));
+        assert_eq!(checked, format!("\x1b[38;2;{}", computed.bright));
+
+        let mut output = Vec::new();
+        let mut renderer = Renderer::with_style(&mut output, 40, style);
+        let lines = ["- [ ] todo", "- [x] done"];
+        render_lines_with(streamdown_parser::Parser::new(), &mut renderer, &lines);
+        let result = String::from_utf8(output).unwrap();
+        assert!(result.contains(&format!("{}☐", unchecked)));
+        assert!(result.contains(&format!("{}☑", checked)));
+    }
+
+    #[test]
+    fn test_computed_style_alert_colours() {
+        let mut config = streamdown_config::Config::default();
+        config.style.alert_warning = "orange".to_string();
+        let style = RenderStyle::from_computed(&config.computed_style());
+        assert_eq!(style.alert_warning, "orange");
+        assert_eq!(style.alert_note, default_alert_note());
+    }
+
+    fn render_lines_with(
+        mut parser: streamdown_parser::Parser,
+        renderer: &mut Renderer<&mut Vec<u8>>,
+        lines: &[&str],
+    ) {
+        for line in lines {
+            renderer.render(&parser.parse_line(line)).unwrap();
+        }
+        renderer.render(&parser.finalize()).unwrap();
+        renderer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_paragraph_wraps_with_margin() {
+        let mut output = Vec::new();
+        let style = RenderStyle {
+            margin: 2,
+            ..Default::default()
+        };
+        let mut renderer = Renderer::with_style(&mut output, 24, style);
+        render_paragraph_lines(
+            &mut renderer,
+            &["A long line of **bold words** that must wrap around"],
+        );
+
+        let result = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert!(lines.len() > 1);
+        for line in &lines {
+            let visible = streamdown_ansi::utils::visible(line);
+            assert!(visible.starts_with("  "));
+            assert!(streamdown_ansi::utils::visible_length(&visible) <= 24);
+        }
+        // Bold is re-applied after the wrap point
+        let continued = lines.iter().skip(1).any(|l| l.contains(BOLD_ON));
+        assert!(continued);
+    }
+
+    #[test]
+    fn test_reflow_joins_soft_breaks() {
+        let text = ["first part of a", "paragraph split", "", "next"];
+
+        let mut output = Vec::new();
+        let mut renderer = Renderer::new(&mut output, 80);
+        render_paragraph_lines(&mut renderer, &text);
+        let plain = streamdown_ansi::utils::visible(&String::from_utf8(output).unwrap());
+        assert!(plain.contains("first part of a\nparagraph split\n"));
+
+        let mut output = Vec::new();
+        let features = RenderFeatures {
+            reflow: true,
+            ..Default::default()
+        };
+        let mut renderer = Renderer::with_features(&mut output, 80, features);
+        render_paragraph_lines(&mut renderer, &text);
+        let plain = streamdown_ansi::utils::visible(&String::from_utf8(output).unwrap());
+        assert!(plain.contains("first part of a paragraph split\n"));
+        assert!(plain.contains("next"));
+    }
+
+    #[test]
+    fn test_reflow_joins_list_item_lines() {
+        let text = [
+            "- an item whose text",
+            "  goes on, then lazily",
+            "continues  ",
+            "after a break",
+            "- next",
+        ];
+        let features = RenderFeatures {
+            reflow: true,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        let mut renderer = Renderer::with_features(&mut output, 30, features);
+        render_paragraph_lines(&mut renderer, &text);
+        let plain = streamdown_ansi::utils::visible(&String::from_utf8(output).unwrap());
+        let lines: Vec<&str> = plain.lines().map(str::trim_end).collect();
+        // Wrapped under the item's text, broken after "continues"
+        assert_eq!(
+            lines,
+            vec![
+                "• an item whose text goes on,",
+                "  then lazily continues",
+                "  after a break",
+                "• next",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hard_break_ends_line_once() {
+        let mut output = Vec::new();
+        let mut renderer = Renderer::new(&mut output, 80);
+        render_paragraph_lines(&mut renderer, &["one\\", "two<br>", "three"]);
+        let plain = streamdown_ansi::utils::visible(&String::from_utf8(output).unwrap());
+        assert_eq!(
+            plain.lines().map(str::trim).collect::<Vec<_>>(),
+            vec!["one", "two", "three"]
+        );
+    }
+
+    #[test]
+    fn test_setext_heading_retracts_paragraph_line() {
+        let mut output = Vec::new();
+        let mut renderer = Renderer::new(&mut output, 80);
+        render_retracting_lines(&mut renderer, &["Title", "====="]);
+
+        let result = String::from_utf8(output).unwrap();
+        let retract = format!("{}\r{}", CURSOR_UP, CLEAR_LINE);
+        let (before, after) = result.split_once(&retract).unwrap();
+        assert!(before.contains("Title"));
+        assert!(after.contains("Title"));
+        assert!(!after.contains(&retract));
+    }
+
+    #[test]
+    fn test_setext_heading_drops_reflow_buffer() {
+        let mut output = Vec::new();
+        let features = RenderFeatures {
+            reflow: true,
+            ..Default::default()
+        };
+        let mut renderer = Renderer::with_features(&mut output, 80, features);
+        render_retracting_lines(&mut renderer, &["Title", "---"]);
+
+        let result = String::from_utf8(output).unwrap();
+        assert!(!result.contains(CURSOR_UP));
+        assert_eq!(result.matches("Title").count(), 1);
+    }
+
+    #[test]
+    fn test_footnotes_printed_at_end() {
+        let mut output = Vec::new();
+        let mut renderer = Renderer::new(&mut output, 80);
+        render_paragraph_lines(
+            &mut renderer,
+            &["Claim[^1] and another[^2].", "", "[^1]: The source."],
+        );
+
+        let plain = streamdown_ansi::utils::visible(&String::from_utf8(output).unwrap());
+        let (body, notes) = plain.split_once("Notes").unwrap();
+        assert!(body.contains("Claim¹ and another²."));
+        assert!(!body.contains("The source."));
+        assert!(notes.contains("¹ The source."));
+        assert!(notes.contains("² ⚠ undefined footnote"));
+    }
+
+    #[test]
+    fn test_footnotes_printed_before_heading() {
+        let mut output = Vec::new();
+        let features = RenderFeatures {
+            footnote_boundary: FootnoteBoundary::Heading,
+            ..Default::default()
+        };
+        let mut renderer = Renderer::with_features(&mut output, 80, features);
+        render_paragraph_lines(
+            &mut renderer,
+            &["Claim[^1].", "[^1]: The source.", "# Next"],
+        );
+
+        let plain = streamdown_ansi::utils::visible(&String::from_utf8(output).unwrap());
+        let notes = plain.find("¹ The source.").unwrap();
+        assert!(notes < plain.find("Next").unwrap());
+        assert_eq!(plain.matches("Notes").count(), 1);
+    }
+
+    #[test]
+    fn test_deferred_reference_link_filled_in() {
+        let mut output = Vec::new();
+        let mut renderer = Renderer::new(&mut output, 80);
+        render_paragraph_lines(
+            &mut renderer,
+            &[
+                "See [the guide][guide] and [nowhere][].",
+                "",
+                "[guide]: https://example.com",
+            ],
+        );
+
+        let result = String::from_utf8(output).unwrap();
+        let plain = streamdown_ansi::utils::visible(&result);
+        assert!(plain.contains("See the guide[guide] and nowhere[nowhere]."));
+        let filled = plain.find("↳ [guide]: https://example.com").unwrap();
+        let flagged = plain
+            .find("↳ [nowhere]: ⚠ undefined link reference")
+            .unwrap();
+        assert!(filled < flagged);
+        assert!(result.contains("\x1b]8;;https://example.com\x1b\\"));
+    }
+
+    #[test]
+    fn test_hard_break_splits_paragraph_line() {
+        let mut output = Vec::new();
+        let features = RenderFeatures {
+            reflow: true,
+            ..Default::default()
+        };
+        let mut renderer = Renderer::with_features(&mut output, 80, features);
+        render_paragraph_lines(&mut renderer, &["first<br>second", "third"]);
+
+        let plain = streamdown_ansi::utils::visible(&String::from_utf8(output).unwrap());
+        assert!(plain.contains("first\n"));
+        assert!(plain.contains("second third"));
+    }
+
+    #[test]
+    fn test_details_box_borders_content() {
+        let mut output = Vec::new();
+        let mut renderer = Renderer::new(&mut output, 20);
+        render_paragraph_lines(
+            &mut renderer,
+            &[
+                "<details>",
+                "<summary>More</summary>",
+                "Hidden text",
+                "</details>",
+            ],
+        );
+
+        let plain = streamdown_ansi::utils::visible(&String::from_utf8(output).unwrap());
+        let lines: Vec<&str> = plain.lines().map(str::trim_end).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "╭───────────────────",
+                "│ ▼ More",
+                "│ Hidden text",
+                "╰───────────────────",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_screen_rows() {
+        assert_eq!(screen_rows("", 80), 0);
+        assert_eq!(screen_rows("short", 80), 1);
+        assert_eq!(screen_rows(&"x".repeat(80), 80), 1);
+        assert_eq!(screen_rows(&"x".repeat(81), 80), 2);
+        assert_eq!(screen_rows("a\n\nb", 80), 3);
+    }
 }
@@ -0,0 +1,134 @@
+//! Link rendering.
+//!
+//! Links are underlined and hyperlinked with OSC 8 when their URL passes
+//! [`is_safe_url`]; the URL follows in parentheses unless the link text
+//! already shows it, as with autolinks.
+//!
+//! While streaming, a reference link can arrive before its
+//! `[label]: url` definition. It is drawn right away as a deferred
+//! reference (the link text plus a dim `[label]` tag), and the target is
+//! filled in on a line of its own once the definition arrives, or flagged
+//! if the document ends without one.
+
+use crate::RenderStyle;
+use crate::fg_color;
+use streamdown_ansi::codes::{ITALIC_OFF, ITALIC_ON, RESET, UNDERLINE_OFF, UNDERLINE_ON};
+use streamdown_ansi::sanitize::{is_safe_url, sanitize_for_terminal};
+
+/// Glyph flagging a reference whose label was never defined.
+pub const LINK_UNRESOLVED: &str = "⚠";
+
+/// Wrap `text` in an OSC 8 hyperlink to `url`, or leave it plain if the
+/// URL is not safe to hand to the terminal.
+pub fn hyperlink(url: &str, text: &str) -> String {
+    if is_safe_url(url) {
+        format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Whether the link text already spells out its URL.
+fn shows_url(text: &str, url: &str) -> bool {
+    text == url
+        || ["mailto:", "http://"]
+            .iter()
+            .any(|scheme| url.strip_prefix(scheme) == Some(text))
+}
+
+/// Render a link: underlined, hyperlinked text followed by the URL.
+pub fn render_link(text: &str, url: &str, style: &RenderStyle) -> String {
+    let underlined = format!("{}{}{}", UNDERLINE_ON, text, UNDERLINE_OFF);
+    let mut result = hyperlink(url, &underlined);
+    if !shows_url(text, url) {
+        let fg = fg_color(&style.link_url);
+        result.push_str(&format!(" {}({}){}", fg, sanitize_for_terminal(url), RESET));
+    }
+    result
+}
+
+/// Render a reference link whose definition has not arrived yet.
+pub fn render_link_reference(text: &str, label: &str, style: &RenderStyle) -> String {
+    let fg = fg_color(&style.link_url);
+    format!(
+        "{}{}{}{}[{}]{}",
+        UNDERLINE_ON, text, UNDERLINE_OFF, fg, label, RESET
+    )
+}
+
+/// Render the line filling in a deferred reference's target.
+pub fn render_link_definition(
+    label: &str,
+    url: &str,
+    left_margin: &str,
+    style: &RenderStyle,
+) -> String {
+    let fg = fg_color(&style.link_url);
+    format!(
+        "{}{}↳ [{}]: {}{}",
+        left_margin,
+        fg,
+        label,
+        hyperlink(url, &sanitize_for_terminal(url)),
+        RESET
+    )
+}
+
+/// Render the line flagging a deferred reference that was never defined.
+pub fn render_unresolved_reference(label: &str, left_margin: &str, style: &RenderStyle) -> String {
+    let fg = fg_color(&style.link_url);
+    format!(
+        "{}{}↳ [{}]: {} {}undefined link reference{}{}",
+        left_margin, fg, label, LINK_UNRESOLVED, ITALIC_ON, ITALIC_OFF, RESET
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use streamdown_ansi::utils::visible;
+
+    #[test]
+    fn test_render_link() {
+        let style = RenderStyle::default();
+        let rendered = render_link("docs", "https://docs.rs", &style);
+        assert!(rendered.starts_with("\x1b]8;;https://docs.rs\x1b\\"));
+        assert_eq!(visible(&rendered), "docs (https://docs.rs)");
+
+        // Autolinks already show their URL
+        let rendered = render_link("www.rust-lang.org", "http://www.rust-lang.org", &style);
+        assert_eq!(visible(&rendered), "www.rust-lang.org");
+        let rendered = render_link("a@b.org", "mailto:a@b.org", &style);
+        assert_eq!(visible(&rendered), "a@b.org");
+    }
+
+    #[test]
+    fn test_render_link_unsafe_url_not_hyperlinked() {
+        let style = RenderStyle::default();
+        let rendered = render_link("click", "javascript:alert(1)", &style);
+        assert!(!rendered.contains("\x1b]8;"));
+        assert_eq!(visible(&rendered), "click (javascript:alert(1))");
+
+        let rendered = render_link("x", "https://a.com\x1b]0;pwned\x07", &style);
+        assert!(!rendered.contains("\x1b]0"));
+    }
+
+    #[test]
+    fn test_render_link_reference() {
+        let style = RenderStyle::default();
+        let rendered = render_link_reference("the docs", "docs", &style);
+        assert_eq!(visible(&rendered), "the docs[docs]");
+        assert!(rendered.starts_with(UNDERLINE_ON));
+    }
+
+    #[test]
+    fn test_render_link_definition() {
+        let style = RenderStyle::default();
+        let line = render_link_definition("docs", "https://docs.rs", "  ", &style);
+        assert_eq!(visible(&line), "  ↳ [docs]: https://docs.rs");
+        assert!(line.contains("\x1b]8;;https://docs.rs\x1b\\"));
+
+        let line = render_unresolved_reference("docs", "", &style);
+        assert!(visible(&line).contains(LINK_UNRESOLVED));
+    }
+}
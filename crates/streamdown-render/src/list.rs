@@ -1,28 +1,121 @@
 //! List rendering.
 //!
 //! Renders markdown lists with:
-//! - Bullet points (•, ◦, ▪)
-//! - Ordered numbers with configurable style
+//! - Bullet points (•, ◦, ▪), configurable per nesting level
+//! - Ordered numbers, decimal, alphabetic or roman per nesting level
+//! - Task list checkboxes (☐, ☑)
 //! - Nested indentation
 //! - Proper text wrapping for long items
 //! - Inline formatting (bold, italic, strikethrough, etc.)
 
 use crate::RenderStyle;
+use crate::fg_color;
+use crate::inline::render_spans;
 use crate::text::text_wrap;
-use crate::{bg_color, fg_color};
-use streamdown_ansi::codes::{
-    BOLD_OFF, BOLD_ON, DIM_ON, ITALIC_OFF, ITALIC_ON, RESET, STRIKEOUT_OFF, STRIKEOUT_ON,
-    UNDERLINE_OFF, UNDERLINE_ON,
-};
-use streamdown_parser::{InlineElement, InlineParser, ListBullet, decode_html_entities};
-
-/// Bullet characters for different nesting levels.
-pub const BULLETS: [&str; 4] = [
-    "•", // Level 0: Filled circle
-    "◦", // Level 1: Empty circle
-    "▪", // Level 2: Small filled square
-    "‣", // Level 3: Triangular bullet
-];
+use serde::{Deserialize, Serialize};
+use streamdown_ansi::codes::RESET;
+use streamdown_parser::{InlineSpan, ListBullet};
+
+/// How the items of an ordered list are numbered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NumberingStyle {
+    /// 1, 2, 3
+    #[default]
+    Decimal,
+    /// a, b, c
+    LowerAlpha,
+    /// A, B, C
+    UpperAlpha,
+    /// i, ii, iii
+    LowerRoman,
+    /// I, II, III
+    UpperRoman,
+}
+
+impl NumberingStyle {
+    /// Format an item number in this style.
+    ///
+    /// Alphabetic numbers continue aa, ab, ... after z; roman numerals
+    /// fall back to decimal for 0 and above 3999.
+    pub fn format(&self, number: usize) -> String {
+        match self {
+            NumberingStyle::Decimal => number.to_string(),
+            NumberingStyle::LowerAlpha => to_alpha(number),
+            NumberingStyle::UpperAlpha => to_alpha(number).to_uppercase(),
+            NumberingStyle::LowerRoman => to_roman(number),
+            NumberingStyle::UpperRoman => to_roman(number).to_uppercase(),
+        }
+    }
+}
+
+impl std::str::FromStr for NumberingStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "decimal" => Ok(Self::Decimal),
+            "lower-alpha" => Ok(Self::LowerAlpha),
+            "upper-alpha" => Ok(Self::UpperAlpha),
+            "lower-roman" => Ok(Self::LowerRoman),
+            "upper-roman" => Ok(Self::UpperRoman),
+            other => Err(format!(
+                "unknown numbering style '{}' (expected decimal, lower-alpha, upper-alpha, lower-roman or upper-roman)",
+                other
+            )),
+        }
+    }
+}
+
+/// Alphabetic item number: a..z, then aa, ab, ...
+fn to_alpha(mut number: usize) -> String {
+    if number == 0 {
+        return "0".to_string();
+    }
+    let mut letters = Vec::new();
+    while number > 0 {
+        number -= 1;
+        letters.push((b'a' + (number % 26) as u8) as char);
+        number /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Lowercase roman numeral, or decimal outside 1..=3999.
+fn to_roman(mut number: usize) -> String {
+    const NUMERALS: [(usize, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+    if !(1..=3999).contains(&number) {
+        return number.to_string();
+    }
+    let mut roman = String::new();
+    for (value, numeral) in NUMERALS {
+        while number >= value {
+            roman.push_str(numeral);
+            number -= value;
+        }
+    }
+    roman
+}
+
+/// Glyph for an unchecked task list item.
+pub const TASK_UNCHECKED: &str = "☐";
+
+/// Glyph for a checked task list item.
+pub const TASK_CHECKED: &str = "☑";
 
 /// List rendering state.
 #[derive(Debug, Clone, Default)]
@@ -31,6 +124,8 @@ pub struct ListState {
     pub stack: Vec<(usize, bool)>,
     /// Current ordered list numbers at each level
     pub numbers: Vec<usize>,
+    /// Rendered content column of the current item at each level
+    pub content_columns: Vec<usize>,
 }
 
 impl ListState {
@@ -48,12 +143,14 @@ impl ListState {
     pub fn push(&mut self, indent: usize, ordered: bool) {
         self.stack.push((indent, ordered));
         self.numbers.push(0);
+        self.content_columns.push(0);
     }
 
     /// Pop a list level.
     pub fn pop(&mut self) {
         self.stack.pop();
         self.numbers.pop();
+        self.content_columns.pop();
     }
 
     /// Get the next number for an ordered list.
@@ -77,6 +174,14 @@ impl ListState {
             }
         }
 
+        // A different kind of list at the same indent is a new list
+        if let Some(&(stack_indent, stack_ordered)) = self.stack.last()
+            && stack_indent == indent
+            && stack_ordered != ordered
+        {
+            self.pop();
+        }
+
         // Check if we need to push a new level
         let need_push = self.stack.last().map(|(i, _)| indent > *i).unwrap_or(true);
         if need_push {
@@ -93,161 +198,173 @@ impl ListState {
     pub fn reset(&mut self) {
         self.stack.clear();
         self.numbers.clear();
+        self.content_columns.clear();
     }
-}
 
-/// Render inline elements to a string with ANSI codes.
-///
-/// Parses markdown inline formatting (bold, italic, strikethrough, etc.)
-/// and renders them with appropriate ANSI escape codes.
-fn render_inline_content(content: &str, style: &RenderStyle) -> String {
-    let mut parser = InlineParser::new();
-    let elements = parser.parse(content);
-
-    let mut result = String::new();
-
-    for element in elements {
-        match element {
-            InlineElement::Text(text) => {
-                result.push_str(&decode_html_entities(&text));
-            }
-            InlineElement::Bold(text) => {
-                result.push_str(BOLD_ON);
-                result.push_str(&decode_html_entities(&text));
-                result.push_str(BOLD_OFF);
-            }
-            InlineElement::Italic(text) => {
-                result.push_str(ITALIC_ON);
-                result.push_str(&decode_html_entities(&text));
-                result.push_str(ITALIC_OFF);
-            }
-            InlineElement::BoldItalic(text) => {
-                result.push_str(BOLD_ON);
-                result.push_str(ITALIC_ON);
-                result.push_str(&decode_html_entities(&text));
-                result.push_str(ITALIC_OFF);
-                result.push_str(BOLD_OFF);
-            }
-            InlineElement::Strikeout(text) => {
-                result.push_str(STRIKEOUT_ON);
-                result.push_str(&decode_html_entities(&text));
-                result.push_str(STRIKEOUT_OFF);
-            }
-            InlineElement::Underline(text) => {
-                result.push_str(UNDERLINE_ON);
-                result.push_str(&decode_html_entities(&text));
-                result.push_str(UNDERLINE_OFF);
-            }
-            InlineElement::Code(text) => {
-                // Inline code with background
-                let bg = bg_color(&style.code_bg);
-                result.push_str(&bg);
-                result.push_str(DIM_ON);
-                result.push(' ');
-                result.push_str(&text);
-                result.push(' ');
-                result.push_str(RESET);
-            }
-            InlineElement::Link { text, url } => {
-                // Underlined text with URL in parens
-                let fg = fg_color(&style.link_url);
-                result.push_str(UNDERLINE_ON);
-                result.push_str(&decode_html_entities(&text));
-                result.push_str(UNDERLINE_OFF);
-                result.push_str(&format!(" {}({}){}", fg, url, RESET));
-            }
-            InlineElement::Image { alt, .. } => {
-                let fg = fg_color(&style.image_marker);
-                result.push_str(&format!("{}[🖼 {}]{}", fg, alt, RESET));
-            }
-            InlineElement::Footnote(text) => {
-                let fg = fg_color(&style.footnote);
-                result.push_str(&format!("{}{}{}", fg, text, RESET));
+    /// Return to the item at `indent` for blocks that continue it.
+    ///
+    /// Deeper levels are closed. Returns the item's rendered content
+    /// column, where the continued blocks are drawn.
+    pub fn continue_item(&mut self, indent: usize) -> usize {
+        while let Some((stack_indent, _)) = self.stack.last() {
+            if *stack_indent > indent {
+                self.pop();
+            } else {
+                break;
             }
         }
+        self.content_columns.last().copied().unwrap_or(0)
     }
+}
 
-    result
+/// Where a list item's lines go: the prefix of its first line, with the
+/// marker, and of the lines wrapping under its content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemLayout {
+    /// Margin, indentation and marker, followed by a space
+    pub first_prefix: String,
+    /// Margin and indentation up to the content column
+    pub next_prefix: String,
+    /// Width available to the content
+    pub content_width: usize,
 }
 
-/// Render a list item.
+/// Lay out a list item, advancing the list state to it.
 ///
-/// # Arguments
-/// * `indent` - Indentation level in spaces
-/// * `bullet` - Bullet type
-/// * `content` - Item content (may be inline-formatted)
-/// * `width` - Available width
-/// * `left_margin` - Left margin string
-/// * `style` - Render style
-/// * `list_state` - List state for tracking numbers
-///
-/// # Returns
-/// Vector of rendered lines (may be multiple if content wraps)
-pub fn render_list_item(
+/// Arguments are as for [`render_list_item`].
+pub fn layout_list_item(
     indent: usize,
     bullet: &ListBullet,
-    content: &str,
+    checked: Option<bool>,
     width: usize,
     left_margin: &str,
     style: &RenderStyle,
     list_state: &mut ListState,
-) -> Vec<String> {
+) -> ItemLayout {
     // Adjust list state for current indent
-    let ordered = matches!(bullet, ListBullet::Ordered(_));
+    let ordered = bullet.is_ordered();
     list_state.adjust_for_indent(indent, ordered);
 
     let level = list_state.level().saturating_sub(1);
 
-    // Calculate marker
+    // Calculate marker, cycling through the styles for each level
     let marker = match bullet {
-        ListBullet::Ordered(num) => {
-            format!("{}.", num)
+        ListBullet::Ordered(num) | ListBullet::OrderedParen(num) => {
+            let numbering = style
+                .numbering
+                .get(level % style.numbering.len().max(1))
+                .copied()
+                .unwrap_or_default();
+            let delimiter = if matches!(bullet, ListBullet::OrderedParen(_)) {
+                ')'
+            } else {
+                '.'
+            };
+            format!("{}{}", numbering.format(*num), delimiter)
         }
         ListBullet::PlusExpand => "⊞".to_string(), // Squared plus
-        _ => {
-            // Cycle through bullet styles based on level
-            BULLETS[level % BULLETS.len()].to_string()
-        }
+        _ => style
+            .bullets
+            .get(level % style.bullets.len().max(1))
+            .cloned()
+            .unwrap_or_else(|| "•".to_string()),
     };
 
-    // Calculate indentation
-    let indent_spaces = indent * 2;
-    let marker_width = unicode_width::UnicodeWidthStr::width(marker.as_str());
-    let content_indent = indent_spaces + marker_width + 1; // +1 for space after marker
-
     // Color the marker
     let marker_fg = fg_color(&style.bullet);
-    let colored_marker = format!("{}{}{}", marker_fg, marker, RESET);
+    let mut colored_marker = format!("{}{}{}", marker_fg, marker, RESET);
+    let mut marker_width = unicode_width::UnicodeWidthStr::width(marker.as_str());
+
+    // Task items: the checkbox replaces the bullet, or follows the number
+    if let Some(checked) = checked {
+        let (glyph, color) = if checked {
+            (TASK_CHECKED, &style.checkbox_checked)
+        } else {
+            (TASK_UNCHECKED, &style.checkbox)
+        };
+        let colored_box = format!("{}{}{}", fg_color(color), glyph, RESET);
+        let box_width = unicode_width::UnicodeWidthStr::width(glyph);
+        if bullet.is_ordered() {
+            colored_marker = format!("{} {}", colored_marker, colored_box);
+            marker_width += 1 + box_width;
+        } else {
+            colored_marker = colored_box;
+            marker_width = box_width;
+        }
+    }
 
-    // Parse and render inline content with formatting (bold, italic, strikethrough, etc.)
-    let rendered_content = render_inline_content(content, style);
+    // Calculate indentation
+    let indent_spaces = indent * 2;
+    let content_indent = indent_spaces + marker_width + 1; // +1 for space after marker
+    if let Some(column) = list_state.content_columns.last_mut() {
+        *column = content_indent;
+    }
 
     // Calculate content width (use visible length for margin with ANSI codes)
     let margin_width = streamdown_ansi::utils::visible_length(left_margin);
-    let content_width = width.saturating_sub(margin_width + content_indent);
+    ItemLayout {
+        first_prefix: format!(
+            "{}{}{} ",
+            left_margin,
+            " ".repeat(indent_spaces),
+            colored_marker
+        ),
+        next_prefix: format!("{}{}", left_margin, " ".repeat(content_indent)),
+        content_width: width.saturating_sub(margin_width + content_indent),
+    }
+}
 
-    // Wrap the content
-    let first_prefix = format!(
-        "{}{}{} ",
+/// Render a list item.
+///
+/// # Arguments
+/// * `indent` - Indentation level in spaces
+/// * `bullet` - Bullet type
+/// * `checked` - Task list state, rendered as a checkbox when set
+/// * `content` - Item content (may be inline-formatted)
+/// * `width` - Available width
+/// * `left_margin` - Left margin string
+/// * `style` - Render style
+/// * `list_state` - List state for tracking numbers
+///
+/// # Returns
+/// Vector of rendered lines (may be multiple if content wraps)
+#[allow(clippy::too_many_arguments)]
+pub fn render_list_item(
+    indent: usize,
+    bullet: &ListBullet,
+    checked: Option<bool>,
+    content: &[InlineSpan],
+    width: usize,
+    left_margin: &str,
+    style: &RenderStyle,
+    list_state: &mut ListState,
+) -> Vec<String> {
+    let layout = layout_list_item(
+        indent,
+        bullet,
+        checked,
+        width,
         left_margin,
-        " ".repeat(indent_spaces),
-        colored_marker
+        style,
+        list_state,
     );
-    let next_prefix = format!("{}{}", left_margin, " ".repeat(content_indent));
+
+    // Render inline content with formatting (bold, italic, strikethrough, etc.)
+    let rendered_content = render_spans(content, style, "");
 
     // Note: text_wrap handles ANSI codes properly via strip_ansi option
     let wrapped = text_wrap(
         &rendered_content,
-        content_width,
+        layout.content_width,
         0,
-        &first_prefix,
-        &next_prefix,
+        &layout.first_prefix,
+        &layout.next_prefix,
         false,
         true,
     );
 
     if wrapped.is_empty() {
-        vec![first_prefix]
+        vec![layout.first_prefix]
     } else {
         wrapped.lines
     }
@@ -262,6 +379,11 @@ pub fn render_list_end(list_state: &mut ListState) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use streamdown_parser::InlineParser;
+
+    fn spans(text: &str) -> Vec<InlineSpan> {
+        InlineParser::new().parse(text)
+    }
 
     fn default_style() -> RenderStyle {
         RenderStyle::default()
@@ -295,13 +417,45 @@ mod tests {
         assert_eq!(state.next_number(), 3);
     }
 
+    #[test]
+    fn test_list_state_continue_item() {
+        let mut state = ListState::new();
+        let style = default_style();
+        render_list_item(
+            0,
+            &ListBullet::Ordered(1),
+            None,
+            &spans("Step"),
+            80,
+            "",
+            &style,
+            &mut state,
+        );
+        render_list_item(
+            3,
+            &ListBullet::Dash,
+            None,
+            &spans("Detail"),
+            80,
+            "",
+            &style,
+            &mut state,
+        );
+        assert_eq!(state.level(), 2);
+
+        // Back to the outer item: "1. " is three columns wide
+        assert_eq!(state.continue_item(0), 3);
+        assert_eq!(state.level(), 1);
+    }
+
     #[test]
     fn test_render_bullet_item() {
         let mut state = ListState::new();
         let lines = render_list_item(
             0,
             &ListBullet::Dash,
-            "Item one",
+            None,
+            &spans("Item one"),
             80,
             "",
             &default_style(),
@@ -319,7 +473,8 @@ mod tests {
         let lines = render_list_item(
             0,
             &ListBullet::Ordered(1),
-            "First item",
+            None,
+            &spans("First item"),
             80,
             "",
             &default_style(),
@@ -331,6 +486,59 @@ mod tests {
         assert!(lines[0].contains("First item"));
     }
 
+    #[test]
+    fn test_render_task_items() {
+        use streamdown_ansi::utils::visible;
+
+        let mut state = ListState::new();
+        let style = default_style();
+        let open = render_list_item(
+            0,
+            &ListBullet::Dash,
+            Some(false),
+            &spans("Todo"),
+            80,
+            "",
+            &style,
+            &mut state,
+        );
+        let done = render_list_item(
+            2,
+            &ListBullet::Dash,
+            Some(true),
+            &spans("Done"),
+            80,
+            "",
+            &style,
+            &mut state,
+        );
+
+        assert_eq!(visible(&open[0]), "☐ Todo");
+        assert_eq!(visible(&done[0]), "    ☑ Done");
+        assert!(done[0].contains(&fg_color(&style.checkbox_checked)));
+    }
+
+    #[test]
+    fn test_render_ordered_task_item_wraps_after_checkbox() {
+        use streamdown_ansi::utils::visible;
+
+        let mut state = ListState::new();
+        let lines = render_list_item(
+            0,
+            &ListBullet::Ordered(3),
+            Some(false),
+            &spans("a task with enough words to wrap"),
+            20,
+            "",
+            &default_style(),
+            &mut state,
+        );
+
+        assert!(visible(&lines[0]).starts_with("3. ☐ a task"));
+        // Continuation lines align with the text after the checkbox
+        assert!(visible(&lines[1]).starts_with("     "));
+    }
+
     #[test]
     fn test_render_nested_items() {
         let mut state = ListState::new();
@@ -339,7 +547,8 @@ mod tests {
         let lines1 = render_list_item(
             0,
             &ListBullet::Dash,
-            "Level 1",
+            None,
+            &spans("Level 1"),
             80,
             "",
             &default_style(),
@@ -351,7 +560,8 @@ mod tests {
         let lines2 = render_list_item(
             2,
             &ListBullet::Dash,
-            "Level 2",
+            None,
+            &spans("Level 2"),
             80,
             "",
             &default_style(),
@@ -378,7 +588,8 @@ mod tests {
         let lines = render_list_item(
             0,
             &ListBullet::Dash,
-            long_content,
+            None,
+            &spans(long_content),
             40,
             "",
             &default_style(),
@@ -389,12 +600,77 @@ mod tests {
         assert!(lines.len() > 1);
     }
 
+    /// Visible marker of an item rendered at each of `indents` in turn.
+    fn markers(style: &RenderStyle, bullets: &[ListBullet], indents: &[usize]) -> Vec<String> {
+        let mut state = ListState::new();
+        bullets
+            .iter()
+            .zip(indents)
+            .map(|(bullet, &indent)| {
+                let lines =
+                    render_list_item(indent, bullet, None, &spans("x"), 80, "", style, &mut state);
+                let line = streamdown_ansi::utils::visible(&lines[0]);
+                line.trim().trim_end_matches('x').trim().to_string()
+            })
+            .collect()
+    }
+
     #[test]
     fn test_bullet_cycling() {
-        // Different bullet styles for different levels
-        assert_eq!(BULLETS[0], "•");
-        assert_eq!(BULLETS[1], "◦");
-        assert_eq!(BULLETS[2], "▪");
+        // Different bullet styles for different levels, repeating
+        let style = default_style();
+        let dashes = [ListBullet::Dash; 5];
+        assert_eq!(
+            markers(&style, &dashes, &[0, 2, 4, 6, 8]),
+            vec!["•", "◦", "▪", "‣", "•"]
+        );
+
+        let style = RenderStyle {
+            bullets: vec!["-".to_string(), "+".to_string()],
+            ..default_style()
+        };
+        assert_eq!(
+            markers(&style, &dashes[..3], &[0, 2, 4]),
+            vec!["-", "+", "-"]
+        );
+    }
+
+    #[test]
+    fn test_numbering_styles_per_level() {
+        let style = RenderStyle {
+            numbering: vec![
+                NumberingStyle::Decimal,
+                NumberingStyle::LowerAlpha,
+                NumberingStyle::LowerRoman,
+            ],
+            ..default_style()
+        };
+        let bullets = [
+            ListBullet::Ordered(1),
+            ListBullet::Ordered(2),
+            ListBullet::OrderedParen(4),
+            ListBullet::Ordered(2),
+        ];
+        assert_eq!(
+            markers(&style, &bullets, &[0, 2, 4, 0]),
+            vec!["1.", "b.", "iv)", "2."]
+        );
+    }
+
+    #[test]
+    fn test_numbering_style_format() {
+        assert_eq!(NumberingStyle::Decimal.format(12), "12");
+        assert_eq!(NumberingStyle::LowerAlpha.format(1), "a");
+        assert_eq!(NumberingStyle::LowerAlpha.format(26), "z");
+        assert_eq!(NumberingStyle::UpperAlpha.format(28), "AB");
+        assert_eq!(NumberingStyle::LowerRoman.format(1994), "mcmxciv");
+        assert_eq!(NumberingStyle::UpperRoman.format(9), "IX");
+        assert_eq!(NumberingStyle::UpperRoman.format(0), "0");
+        assert_eq!(
+            "Lower-Roman".parse::<NumberingStyle>(),
+            Ok(NumberingStyle::LowerRoman)
+        );
+        assert!("greek".parse::<NumberingStyle>().is_err());
     }
 
     #[test]
@@ -427,7 +703,8 @@ mod tests {
         let lines = render_list_item(
             0,
             &ListBullet::Dash,
-            content,
+            None,
+            &spans(content),
             40,
             margin,
             &default_style(),
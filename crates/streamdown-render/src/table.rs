@@ -1,13 +1,16 @@
 //! Table rendering.
 //!
 //! Renders markdown tables with full-width columns and styled borders.
+//! Column widths are computed from cell content when the rows are known
+//! up front, and split evenly when rows are streamed.
 
 use crate::RenderStyle;
+use crate::inline::render_spans;
 use crate::text::text_wrap;
 use crate::{bg_color, fg_color};
 use streamdown_ansi::codes::RESET;
-use streamdown_ansi::utils::visible_length;
-use streamdown_parser::inline::format_line;
+use streamdown_ansi::utils::{visible, visible_length};
+use streamdown_parser::{Alignment, InlineSpan, ParseEvent};
 
 /// Minimum column width (characters)
 const MIN_COL_WIDTH: usize = 8;
@@ -21,8 +24,16 @@ pub struct TableState {
     pub column_widths: Vec<usize>,
     /// Number of columns
     pub num_columns: usize,
+    /// Per-column alignment from the separator row
+    pub alignments: Vec<Alignment>,
     /// Available width for the table
     pub available_width: usize,
+    /// Whether a table has started (a header row has been seen)
+    pub active: bool,
+    /// Whether rows are held back until the table can be laid out
+    pub buffering: bool,
+    /// Table events held back while buffering
+    pub pending: Vec<ParseEvent>,
 }
 
 impl TableState {
@@ -32,7 +43,11 @@ impl TableState {
             is_header: true,
             column_widths: Vec::new(),
             num_columns: 0,
+            alignments: Vec::new(),
             available_width: 80,
+            active: false,
+            buffering: false,
+            pending: Vec::new(),
         }
     }
 
@@ -68,6 +83,44 @@ impl TableState {
             .collect();
     }
 
+    /// Calculate column widths from the content of the given rows.
+    ///
+    /// Columns whose content fits keep their natural width, and any spare
+    /// space is shared out in proportion to it. When the table is too wide,
+    /// only the widest columns are narrowed (and wrapped), down to a
+    /// minimum width that itself shrinks if the columns can't all get it.
+    pub fn calculate_content_widths(
+        &mut self,
+        rows: &[&[Vec<InlineSpan>]],
+        available_width: usize,
+    ) {
+        let num_cols = rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        self.num_columns = num_cols;
+        self.available_width = available_width;
+
+        if num_cols == 0 {
+            self.column_widths = Vec::new();
+            return;
+        }
+
+        let mut max_widths = vec![0; num_cols];
+        for row in rows {
+            for (i, cell) in row.iter().enumerate() {
+                max_widths[i] = max_widths[i].max(cell_width(cell));
+            }
+        }
+        let min_widths: Vec<usize> = max_widths
+            .iter()
+            .map(|w| (*w).clamp(1, MIN_COL_WIDTH))
+            .collect();
+
+        let separator_width = num_cols.saturating_sub(1);
+        let padding_width = num_cols * 2;
+        let content_width = available_width.saturating_sub(separator_width + padding_width);
+
+        self.column_widths = fit_widths(&max_widths, &min_widths, content_width);
+    }
+
     /// Get total table width including separators and padding
     pub fn total_width(&self) -> usize {
         let content: usize = self.column_widths.iter().sum();
@@ -86,6 +139,18 @@ impl TableState {
         self.is_header = true;
         self.column_widths.clear();
         self.num_columns = 0;
+        self.alignments.clear();
+        self.active = false;
+        self.buffering = false;
+        self.pending.clear();
+    }
+
+    /// Number of header and body rows held back.
+    pub fn pending_rows(&self) -> usize {
+        self.pending
+            .iter()
+            .filter(|e| matches!(e, ParseEvent::TableHeader(_) | ParseEvent::TableRow(_)))
+            .count()
     }
 }
 
@@ -95,9 +160,90 @@ impl Default for TableState {
     }
 }
 
+/// Visible width of a cell once its inline markdown is formatted.
+fn cell_width(cell: &[InlineSpan]) -> usize {
+    visible_length(&visible(&render_spans(cell, &RenderStyle::default(), "")))
+}
+
+/// Fit natural column widths into `content_width`.
+fn fit_widths(max_widths: &[usize], min_widths: &[usize], content_width: usize) -> Vec<usize> {
+    let total: usize = max_widths.iter().sum();
+
+    if total <= content_width {
+        // Everything fits: share the spare space proportionally
+        let spare = content_width - total;
+        let mut widths: Vec<usize> = if total == 0 {
+            max_widths
+                .iter()
+                .map(|_| spare / max_widths.len())
+                .collect()
+        } else {
+            max_widths.iter().map(|w| w + spare * w / total).collect()
+        };
+        let mut remainder = content_width.saturating_sub(widths.iter().sum());
+        for width in widths.iter_mut() {
+            if remainder == 0 {
+                break;
+            }
+            *width += 1;
+            remainder -= 1;
+        }
+        return widths;
+    }
+
+    // Too wide: every column gets its minimum width, scaled down when even
+    // those don't fit, keeping at least one character per column
+    let num_cols = max_widths.len();
+    let min_total: usize = min_widths.iter().sum();
+    let min_widths: Vec<usize> = if min_total > content_width {
+        let spare = content_width.saturating_sub(num_cols);
+        min_widths
+            .iter()
+            .map(|min| 1 + (min - 1) * spare / (min_total - num_cols).max(1))
+            .collect()
+    } else {
+        min_widths.to_vec()
+    };
+
+    // The rest goes to the columns wanting more: find the largest cap on
+    // their extra width such that capping every column at it fits, so
+    // narrow columns keep their natural width
+    let extras: Vec<usize> = max_widths
+        .iter()
+        .zip(&min_widths)
+        .map(|(max, min)| max.saturating_sub(*min))
+        .collect();
+    let mut sorted = extras.clone();
+    sorted.sort_unstable();
+    let mut remaining = content_width.saturating_sub(min_widths.iter().sum());
+    let mut uncapped = sorted.len();
+    for width in &sorted {
+        if width * uncapped > remaining {
+            break;
+        }
+        remaining -= width;
+        uncapped -= 1;
+    }
+    let cap = remaining / uncapped.max(1);
+    let mut spare = remaining % uncapped.max(1);
+
+    extras
+        .iter()
+        .zip(&min_widths)
+        .map(|(&extra, &min)| {
+            let mut width = extra.min(cap);
+            if extra > cap && spare > 0 {
+                width += 1;
+                spare -= 1;
+            }
+            min + width
+        })
+        .collect()
+}
+
 /// Render a table row with full-width columns.
 pub fn render_table_row(
-    cells: &[String],
+    cells: &[Vec<InlineSpan>],
     state: &mut TableState,
     width: usize,
     left_margin: &str,
@@ -107,7 +253,7 @@ pub fn render_table_row(
     let num_cols = cells.len();
 
     // Calculate column widths if not already done
-    if state.column_widths.is_empty() || state.num_columns != num_cols {
+    if state.column_widths.is_empty() || num_cols > state.num_columns {
         state.calculate_widths(num_cols, width);
     }
 
@@ -124,8 +270,9 @@ pub fn render_table_row(
 
     for (i, cell) in cells.iter().enumerate() {
         let col_width = state.column_widths.get(i).copied().unwrap_or(MIN_COL_WIDTH);
-        // Process inline markdown (bold, italic, code, etc.) before wrapping
-        let formatted_cell = format_line(cell, true, true);
+        // Format inline content (bold, italic, code, etc.) before wrapping,
+        // keeping the row's background behind each span
+        let formatted_cell = render_spans(cell, style, &bg);
         let wrapped = text_wrap(&formatted_cell, col_width, 0, "", "", true, true);
 
         let cell_lines = if wrapped.is_empty() {
@@ -154,9 +301,21 @@ pub fn render_table_row(
             let content = cell_lines.get(row_idx).cloned().unwrap_or_default();
             let content_len = visible_length(&content);
             let padding = col_width.saturating_sub(content_len);
-
-            // Format: bg + " " + content + padding + " "
-            line_parts.push(format!("{} {}{}", bg, content, " ".repeat(padding + 1)));
+            let alignment = state.alignments.get(col_idx).copied().unwrap_or_default();
+            let (left, right) = match alignment {
+                Alignment::Right => (padding, 0),
+                Alignment::Center => (padding / 2, padding - padding / 2),
+                Alignment::Left | Alignment::None => (0, padding),
+            };
+
+            // Format: bg + " " + padding + content + padding + " "
+            line_parts.push(format!(
+                "{} {}{}{}",
+                bg,
+                " ".repeat(left),
+                content,
+                " ".repeat(right + 1)
+            ));
         }
 
         // Join with separator
@@ -196,6 +355,7 @@ pub fn render_table_separator(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use streamdown_parser::InlineParser;
 
     fn default_style() -> RenderStyle {
         RenderStyle::default()
@@ -225,10 +385,89 @@ mod tests {
         );
     }
 
+    fn row(cells: &[&str]) -> Vec<Vec<InlineSpan>> {
+        cells.iter().map(|c| InlineParser::new().parse(c)).collect()
+    }
+
+    #[test]
+    fn test_content_widths_fill_proportionally() {
+        let rows = [row(&["ID", "Description"]), row(&["1", "short"])];
+        let rows: Vec<&[Vec<InlineSpan>]> = rows.iter().map(|r| r.as_slice()).collect();
+        let mut state = TableState::new();
+        state.calculate_content_widths(&rows, 80);
+
+        assert_eq!(state.total_width(), 80);
+        assert!(state.column_widths[0] < state.column_widths[1]);
+    }
+
+    #[test]
+    fn test_content_widths_wrap_widest_column() {
+        let long = "word ".repeat(30);
+        let rows = [
+            row(&["ID", "Name", "Description"]),
+            row(&["1", "Alice", &long]),
+        ];
+        let rows: Vec<&[Vec<InlineSpan>]> = rows.iter().map(|r| r.as_slice()).collect();
+        let mut state = TableState::new();
+        state.calculate_content_widths(&rows, 60);
+
+        // Narrow columns keep their natural width
+        assert_eq!(state.column_widths[0], 2);
+        assert_eq!(state.column_widths[1], 5);
+        assert_eq!(state.total_width(), 60);
+    }
+
+    #[test]
+    fn test_content_widths_stay_within_budget() {
+        let words = ["extraordinary", "comprehensive", "unbelievable"];
+        let rows = [
+            row(&["alpha", "beta", "gamma", "delta", "epsilon", "zeta"]),
+            row(&[words[0], words[1], words[2], words[0], words[1], words[2]]),
+        ];
+        let rows: Vec<&[Vec<InlineSpan>]> = rows.iter().map(|r| r.as_slice()).collect();
+        let mut state = TableState::new();
+        state.calculate_content_widths(&rows, 40);
+        assert!(state.total_width() <= 40, "got {}", state.total_width());
+
+        state.is_header = false;
+        let lines = render_table_row(rows[1], &mut state, 40, "", &default_style(), false);
+        for line in lines {
+            assert!(visible_length(&line) <= 40, "{:?}", visible(&line));
+        }
+    }
+
+    #[test]
+    fn test_narrow_column_truncates_wide_characters() {
+        let mut state = TableState::new();
+        state.column_widths = vec![1];
+        state.num_columns = 1;
+        let lines = render_table_row(&row(&["🎉🎉"]), &mut state, 3, "", &default_style(), false);
+        assert_eq!(visible(&lines[0]), " … ");
+    }
+
+    #[test]
+    fn test_cell_width_ignores_markup() {
+        assert_eq!(cell_width(&InlineParser::new().parse("**bold**")), 4);
+        assert_eq!(cell_width(&InlineParser::new().parse("plain text")), 10);
+    }
+
+    #[test]
+    fn test_render_table_row_alignment() {
+        let mut state = TableState::new();
+        state.column_widths = vec![6, 6, 6];
+        state.num_columns = 3;
+        state.alignments = vec![Alignment::Left, Alignment::Center, Alignment::Right];
+        let cells = row(&["ab", "cd", "ef"]);
+        let lines = render_table_row(&cells, &mut state, 80, "", &default_style(), false);
+
+        let plain = visible(&lines[0]);
+        assert_eq!(plain, " ab     │   cd   │     ef ");
+    }
+
     #[test]
     fn test_render_table_row() {
         let mut state = TableState::new();
-        let cells = vec!["A".to_string(), "B".to_string(), "C".to_string()];
+        let cells = row(&["A", "B", "C"]);
         let lines = render_table_row(&cells, &mut state, 80, "", &default_style(), false);
 
         assert!(!lines.is_empty());
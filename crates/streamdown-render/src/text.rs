@@ -49,7 +49,7 @@ pub fn split_text(text: &str) -> Vec<String> {
     for ch in text.chars() {
         if in_escape {
             escape_buf.push(ch);
-            if ch == 'm' {
+            if escape_complete(&escape_buf) {
                 // End of ANSI sequence
                 current.push_str(&escape_buf);
                 escape_buf.clear();
@@ -85,6 +85,21 @@ pub fn split_text(text: &str) -> Vec<String> {
     words
 }
 
+/// Whether a buffered escape sequence (starting with ESC) is complete.
+///
+/// CSI sequences (`ESC [ ... m`) end at their final byte; OSC sequences
+/// such as OSC 8 hyperlinks (`ESC ] ... ESC \`) end at the string
+/// terminator, so URLs inside them are not split into words.
+fn escape_complete(seq: &str) -> bool {
+    let mut chars = seq.chars().skip(1);
+    match chars.next() {
+        None => false,
+        Some('[') => seq.len() > 2 && seq.ends_with(|c: char| ('@'..='~').contains(&c)),
+        Some(']') => seq.ends_with("\x1b\\") || seq.ends_with('\x07'),
+        Some(_) => true,
+    }
+}
+
 /// Wrap text to fit within a given width.
 ///
 /// This is ANSI-aware and will preserve formatting across line breaks.
@@ -124,6 +139,18 @@ pub fn text_wrap(
     let mut prev_word = String::new();
 
     for word in words.iter().chain(std::iter::once(&String::new())) {
+        // Escape-only words (e.g. around padded inline code) stay in place,
+        // keeping the spaces around them, and never cause a wrap
+        if !word.is_empty() && visible_length(word) == 0 {
+            if visible_length(&current_line) > 0 {
+                current_line.push(' ');
+            }
+            current_line.push_str(word);
+            current_style.extend(extract_ansi_codes(word));
+            current_style = ansi_collapse(&current_style, "");
+            continue;
+        }
+
         // Extract ANSI codes from the word
         let codes = extract_ansi_codes(word);
 
@@ -166,20 +193,9 @@ pub fn text_wrap(
                 let mut line_content = format!("{}{}", prefix, current_line);
 
                 // Force truncate if needed
-                if force_truncate {
-                    while visible_length(&line_content) > width && line_content.len() > 1 {
-                        // Remove last visible character and add ellipsis
-                        let visible_part = visible(&line_content);
-                        if visible_part.len() > 1 {
-                            // Find the position to truncate
-                            let target_len = visible_part.len() - 2;
-                            line_content = truncate_to_visible(&line_content, target_len);
-                            line_content.push('…');
-                            truncated = true;
-                        } else {
-                            break;
-                        }
-                    }
+                if force_truncate && visible_length(&line_content) > width {
+                    line_content = truncate_with_ellipsis(&line_content, width);
+                    truncated = true;
                 }
 
                 // Add resetter and padding
@@ -222,18 +238,9 @@ pub fn text_wrap(
         };
         let mut line_content = format!("{}{}", prefix, current_line);
 
-        if force_truncate {
-            while visible_length(&line_content) > width && line_content.len() > 1 {
-                let visible_part = visible(&line_content);
-                if visible_part.len() > 1 {
-                    let target_len = visible_part.len() - 2;
-                    line_content = truncate_to_visible(&line_content, target_len);
-                    line_content.push('…');
-                    truncated = true;
-                } else {
-                    break;
-                }
-            }
+        if force_truncate && visible_length(&line_content) > width {
+            line_content = truncate_with_ellipsis(&line_content, width);
+            truncated = true;
         }
 
         line_content.push_str(resetter);
@@ -243,6 +250,13 @@ pub fn text_wrap(
     WrappedText { lines, truncated }
 }
 
+/// Truncate text so that it fits `width` columns including a trailing `…`.
+fn truncate_with_ellipsis(text: &str, width: usize) -> String {
+    let mut result = truncate_to_visible(text, width.saturating_sub(1));
+    result.push('…');
+    result
+}
+
 /// Truncate a string (with ANSI codes) to a visible length.
 fn truncate_to_visible(text: &str, max_visible: usize) -> String {
     let mut result = String::new();
@@ -264,12 +278,14 @@ fn truncate_to_visible(text: &str, max_visible: usize) -> String {
             continue;
         }
 
-        if visible_count >= max_visible {
+        // Stop before a character that would not fit (e.g. a wide one)
+        let ch_width = unicode_width::UnicodeWidthChar::width(ch).unwrap_or(1);
+        if visible_count + ch_width > max_visible {
             break;
         }
 
         result.push(ch);
-        visible_count += unicode_width::UnicodeWidthChar::width(ch).unwrap_or(1);
+        visible_count += ch_width;
     }
 
     result
@@ -328,6 +344,32 @@ mod tests {
         assert!(words[0].contains("\x1b[1m"));
     }
 
+    #[test]
+    fn test_split_text_keeps_osc_link_together() {
+        let text = "\x1b]8;;https://example.com/a b\x1b\\link\x1b]8;;\x1b\\ after";
+        let words = split_text(text);
+        assert_eq!(words.len(), 2);
+        assert_eq!(visible(&words[0]), "link");
+    }
+
+    #[test]
+    fn test_text_wrap_keeps_escape_only_words() {
+        // Inline code padding leaves the codes as separate words
+        let text = "a \x1b[48;2;1;2;3m code \x1b[0m b";
+        let result = text_wrap(text, 40, 0, "", "", false, false);
+        assert_eq!(result.lines.len(), 1);
+        assert!(result.lines[0].starts_with(text));
+    }
+
+    #[test]
+    fn test_text_wrap_carries_style_across_lines() {
+        let text = "plain \x1b[1mbold words that wrap\x1b[22m tail";
+        let result = text_wrap(text, 12, 0, "", "", false, false);
+        assert!(result.lines.len() > 1);
+        // The continuation line re-applies bold
+        assert!(result.lines[1].starts_with("\x1b[1m"));
+    }
+
     #[test]
     fn test_simple_wrap() {
         let lines = simple_wrap("hello world foo bar", 10);
@@ -393,6 +435,20 @@ mod tests {
         assert_eq!(truncated, "🎉🎉");
     }
 
+    #[test]
+    fn test_truncate_to_visible_wide_char_does_not_overflow() {
+        assert_eq!(truncate_to_visible("a🎉", 2), "a");
+    }
+
+    #[test]
+    fn test_text_wrap_truncates_wide_char_in_narrow_width() {
+        let result = text_wrap("🎉🎉 ab", 1, 0, "", "", true, true);
+        assert!(result.truncated);
+        for line in &result.lines {
+            assert!(visible_length(line) <= 1, "line too wide: {:?}", line);
+        }
+    }
+
     #[test]
     fn test_truncate_to_visible_zwj_emojis() {
         // 👨‍💻 = 11 bytes, 3 code points. Should not split ZWJ sequence.
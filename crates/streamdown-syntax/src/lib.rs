@@ -33,9 +33,11 @@ mod languages;
 
 pub use languages::{LANGUAGE_ALIASES, aliases_for, all_aliases, language_alias};
 
-use syntect::easy::HighlightLines;
-use syntect::highlighting::{Color, FontStyle, Style, Theme, ThemeSet};
-use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::highlighting::{
+    Color, FontStyle, HighlightIterator, HighlightState as ScopeState,
+    Highlighter as ThemeHighlighter, Style, Theme, ThemeSet,
+};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxReference, SyntaxSet};
 use syntect::util::as_24_bit_terminal_escaped;
 
 /// Reset ANSI escape code
@@ -187,7 +189,7 @@ impl Highlighter {
     /// Create a new highlight state for streaming.
     ///
     /// This is the preferred way to do line-by-line highlighting.
-    pub fn new_highlight_state(&self, language: &str) -> HighlightState<'_> {
+    pub fn new_highlight_state(&self, language: &str) -> HighlightState {
         let syntax = self
             .syntax_for_language(language)
             .unwrap_or_else(|| self.plain_text());
@@ -202,18 +204,27 @@ impl Highlighter {
     /// # Returns
     /// The highlighted line as an ANSI-escaped string (without trailing newline).
     pub fn highlight_line_with_state(&self, line: &str, state: &mut HighlightState) -> String {
-        match state.highlighter.highlight_line(line, &self.syntax_set) {
-            Ok(ranges) => {
-                if self.background_override.is_some() {
-                    // Custom rendering without background colors
-                    self.styles_to_ansi(&ranges)
-                } else {
-                    // Use syntect's built-in terminal escaping
-                    let escaped = as_24_bit_terminal_escaped(&ranges, false);
-                    format!("{}{}", escaped, RESET)
-                }
-            }
-            Err(_) => line.to_string(), // Fallback on error
+        // The syntax set expects newline-terminated lines; without the
+        // newline, line comments and similar scopes never close.
+        let line = format!("{}\n", line);
+        let ops = match state.parse_state.parse_line(&line, &self.syntax_set) {
+            Ok(ops) => ops,
+            Err(_) => return line.trim_end_matches('\n').to_string(), // Fallback on error
+        };
+
+        let highlighter = ThemeHighlighter::new(self.theme());
+        let ranges: Vec<(Style, &str)> =
+            HighlightIterator::new(&mut state.scope_state, &ops, &line, &highlighter)
+                .map(|(style, text)| (style, text.trim_end_matches('\n')))
+                .collect();
+
+        if self.background_override.is_some() {
+            // Custom rendering without background colors
+            self.styles_to_ansi(&ranges)
+        } else {
+            // Use syntect's built-in terminal escaping
+            let escaped = as_24_bit_terminal_escaped(&ranges, false);
+            format!("{}{}", escaped, RESET)
         }
     }
 
@@ -314,17 +325,24 @@ impl Highlighter {
 /// State for streaming syntax highlighting.
 ///
 /// This maintains the parse state across lines to correctly handle
-/// multi-line tokens like block comments and strings.
-pub struct HighlightState<'a> {
-    /// Syntect's HighlightLines for stateful line-by-line highlighting
-    highlighter: HighlightLines<'a>,
+/// multi-line tokens like block comments and strings. It owns its state
+/// rather than borrowing the [`Highlighter`], so it can be stored next to
+/// one (e.g. per code block in a renderer).
+#[derive(Debug, Clone)]
+pub struct HighlightState {
+    /// Syntax parse state carried from line to line
+    parse_state: ParseState,
+    /// Theme scope state carried from line to line
+    scope_state: ScopeState,
 }
 
-impl<'a> HighlightState<'a> {
+impl HighlightState {
     /// Create a new highlight state for a syntax and theme.
-    pub fn new(syntax: &'a SyntaxReference, theme: &'a Theme) -> Self {
+    pub fn new(syntax: &SyntaxReference, theme: &Theme) -> Self {
+        let highlighter = ThemeHighlighter::new(theme);
         Self {
-            highlighter: HighlightLines::new(syntax, theme),
+            parse_state: ParseState::new(syntax),
+            scope_state: ScopeState::new(&highlighter, ScopeStack::new()),
         }
     }
 }
@@ -503,6 +521,46 @@ mod tests {
         assert!(!line3.is_empty());
     }
 
+    #[test]
+    fn test_multiline_token_carries_state() {
+        let h = Highlighter::new();
+        let mut state = h.new_highlight_state("rust");
+
+        let opened = h.highlight_line_with_state("/* comment", &mut state);
+        let inside = h.highlight_line_with_state("let x = 1;", &mut state);
+
+        // Inside the comment, `let` gets the comment color, not keyword color
+        let fresh = h.highlight_line_with_state("let x = 1;", &mut h.new_highlight_state("rust"));
+        assert_ne!(inside, fresh);
+        let comment_color = &opened[..opened.find("/*").unwrap()];
+        assert!(inside.starts_with(comment_color));
+    }
+
+    #[test]
+    fn test_line_comment_does_not_leak() {
+        let h = Highlighter::new();
+        let mut state = h.new_highlight_state("rust");
+
+        h.highlight_line_with_state("// comment", &mut state);
+        let after = h.highlight_line_with_state("let x = 1;", &mut state);
+
+        let fresh = h.highlight_line_with_state("let x = 1;", &mut h.new_highlight_state("rust"));
+        assert_eq!(after, fresh);
+    }
+
+    #[test]
+    fn test_highlight_state_is_owned() {
+        let h = Highlighter::new();
+        let mut state = h.new_highlight_state("rust");
+        h.highlight_line_with_state("/* open", &mut state);
+
+        // A clone continues from the same point independently
+        let mut copy = state.clone();
+        let a = h.highlight_line_with_state("x */", &mut state);
+        let b = h.highlight_line_with_state("x */", &mut copy);
+        assert_eq!(a, b);
+    }
+
     #[test]
     fn test_background_override_styling() {
         let mut h = Highlighter::new();
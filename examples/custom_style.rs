@@ -3,7 +3,7 @@
 //! Run with: `cargo run --example custom_style`
 
 use streamdown_parser::Parser;
-use streamdown_render::{RenderStyle, Renderer};
+use streamdown_render::{NumberingStyle, RenderStyle, Renderer};
 
 fn main() {
     let markdown = r#"# Custom Styled Output
@@ -25,6 +25,10 @@ print(greet("World"))
 - *Italic text* is emphasized
 - `inline code` is highlighted
 
+1. Numbered steps
+   1. with lettered
+   2. sub-steps
+
 > A quote with custom colors!
 "#;
 
@@ -40,21 +44,36 @@ print(greet("World"))
         // Code blocks: dark blue background, cyan labels
         code_bg: "20;20;60".to_string(),
         code_label: "0;255;255".to_string(),
-        // Lists: yellow bullets
+        code_highlight_bg: "40;40;60".to_string(),
+        // Lists: yellow bullets, green checked tasks
         bullet: "255;255;0".to_string(),
+        checkbox: "255;255;0".to_string(),
+        checkbox_checked: "0;255;128".to_string(),
         // Tables: purple tones
         table_header_bg: "80;60;120".to_string(),
         table_border: "180;160;220".to_string(),
         // Borders and decorations
         blockquote_border: "0;255;255".to_string(),
+        // Alerts: blue, green, purple, amber and red by kind
+        alert_note: "0;128;255".to_string(),
+        alert_tip: "0;255;128".to_string(),
+        alert_important: "200;0;255".to_string(),
+        alert_warning: "255;200;0".to_string(),
+        alert_caution: "255;0;0".to_string(),
         think_border: "128;128;128".to_string(),
         hr: "128;128;128".to_string(),
         // Links and references
         link_url: "0;255;255".to_string(),
         image_marker: "255;255;0".to_string(),
         footnote: "180;160;220".to_string(),
+        // Highlighted text: muted purple background
+        highlight_bg: "80;60;120".to_string(),
         // Left-align headings instead of centering
         heading_centered: false,
+        margin: 0,
+        // Lists: arrow bullets, lettered sub-lists
+        bullets: vec!["▸".to_string(), "▹".to_string()],
+        numbering: vec![NumberingStyle::Decimal, NumberingStyle::LowerAlpha],
     };
 
     // Create output buffer
@@ -4,6 +4,8 @@
 
 use clap::Parser;
 use std::path::PathBuf;
+use streamdown_parser::Dialect;
+use streamdown_render::{DEFAULT_TABLE_BUFFER_ROWS, FootnoteBoundary, FrontMatterMode};
 
 /// Streamdown - A streaming markdown renderer for modern terminals.
 ///
@@ -75,6 +77,30 @@ pub struct Cli {
     #[arg(long = "savebrace")]
     pub savebrace: bool,
 
+    /// Join soft-broken paragraph lines and reflow them to the width
+    #[arg(long = "reflow")]
+    pub reflow: bool,
+
+    /// Table rows to buffer for content-aware column widths (0 = stream)
+    #[arg(long = "table-buffer", value_name = "ROWS", default_value_t = DEFAULT_TABLE_BUFFER_ROWS)]
+    pub table_buffer: usize,
+
+    /// Where to print footnotes: at the end, or before each heading
+    #[arg(long = "footnotes", value_name = "WHERE", default_value = "end")]
+    pub footnotes: FootnoteBoundary,
+
+    /// How to show front matter: hide, raw, or table
+    #[arg(long = "front-matter", value_name = "MODE", default_value = "table")]
+    pub front_matter: FrontMatterMode,
+
+    /// Markdown dialect: commonmark, gfm, or classic (overrides the config)
+    #[arg(long = "dialect", value_name = "NAME")]
+    pub dialect: Option<Dialect>,
+
+    /// Disable live rendering of partial lines
+    #[arg(long = "no-live")]
+    pub no_live: bool,
+
     /// Show configuration paths and exit
     #[arg(long = "paths")]
     pub show_paths: bool,
@@ -145,6 +171,31 @@ mod tests {
         assert_eq!(cli.width, 0);
         assert_eq!(cli.log_level, "warn");
         assert!(!cli.clipboard);
+        assert!(!cli.no_live);
+        assert_eq!(cli.footnotes, FootnoteBoundary::End);
+        assert_eq!(cli.front_matter, FrontMatterMode::Table);
+        assert_eq!(cli.dialect, None);
+    }
+
+    #[test]
+    fn test_cli_parse_footnotes() {
+        let cli = Cli::parse_from(["sd", "--footnotes", "heading"]);
+        assert_eq!(cli.footnotes, FootnoteBoundary::Heading);
+        assert!(Cli::try_parse_from(["sd", "--footnotes", "nowhere"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_front_matter() {
+        let cli = Cli::parse_from(["sd", "--front-matter", "raw"]);
+        assert_eq!(cli.front_matter, FrontMatterMode::Raw);
+        assert!(Cli::try_parse_from(["sd", "--front-matter", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_parse_dialect() {
+        let cli = Cli::parse_from(["sd", "--dialect", "gfm"]);
+        assert_eq!(cli.dialect, Some(Dialect::Gfm));
+        assert!(Cli::try_parse_from(["sd", "--dialect", "markdown"]).is_err());
     }
 
     #[test]
@@ -0,0 +1,25 @@
+//! Streamdown - A streaming markdown renderer for modern terminals.
+//!
+//! This crate ties the streamdown workspace together into a single
+//! streaming pipeline. The individual stages live in their own crates
+//! (`streamdown-parser`, `streamdown-render`, `streamdown-plugin`, ...);
+//! [`Session`] wires them up so that callers only push text in.
+//!
+//! # Example
+//!
+//! ```
+//! use streamdown::Session;
+//!
+//! let mut output = Vec::new();
+//! {
+//!     let mut session = Session::new(&mut output, 80);
+//!     session.push_str("# Hello\n\nSome **bold** ").unwrap();
+//!     session.push_str("text.\n").unwrap();
+//!     session.finish().unwrap();
+//! }
+//! assert!(!output.is_empty());
+//! ```
+
+pub mod session;
+
+pub use session::Session;
@@ -10,13 +10,13 @@ use clap::Parser as ClapParser;
 use cli::Cli;
 use log::{LevelFilter, debug, error, info, trace};
 use std::fs::File;
-use std::io::{self, BufRead, BufReader, Write};
+use std::io::{self, Read, Write};
 use std::path::Path;
 
+use streamdown::Session;
 use streamdown_config::{ComputedStyle, Config};
-use streamdown_parser::{ParseEvent, Parser as MarkdownParser};
-use streamdown_plugin::PluginManager;
-use streamdown_render::{RenderFeatures, RenderStyle, Renderer};
+use streamdown_parser::{Dialect, ParseEvent, ParserOptions};
+use streamdown_render::{RenderFeatures, is_tty};
 
 fn main() {
     let cli = <Cli as ClapParser>::parse();
@@ -74,16 +74,19 @@ fn run(cli: &Cli) -> io::Result<()> {
     let features = create_features(cli);
     debug!("Render features: {:?}", features);
 
+    let options = parser_options(cli, &config);
+    debug!("Parser options: {:?}", options);
+
     // Determine input source and process
     if let Some(ref exec_cmd) = cli.exec_cmd {
         // Wrap an external program
-        run_exec(cli, exec_cmd, &computed_style, &features)
+        run_exec(cli, exec_cmd, &computed_style, &features, &options)
     } else if cli.should_read_stdin() {
         // Read from stdin
-        run_stdin(cli, &computed_style, &features)
+        run_stdin(cli, &computed_style, &features, &options)
     } else {
         // Process files
-        run_files(cli, &computed_style, &features)
+        run_files(cli, &computed_style, &features, &options)
     }
 }
 
@@ -142,120 +145,108 @@ fn create_features(cli: &Cli) -> RenderFeatures {
         savebrace: cli.savebrace,
         fixed_width,
         width_wrap,
+        reflow: cli.reflow,
+        table_buffer_rows: cli.table_buffer,
+        footnote_boundary: cli.footnotes,
+        front_matter: cli.front_matter,
         ..Default::default()
     }
 }
 
-/// Process input from stdin.
-fn run_stdin(cli: &Cli, style: &ComputedStyle, features: &RenderFeatures) -> io::Result<()> {
-    info!("Reading from stdin");
+/// Parser options for the dialect chosen on the command line or in the
+/// config file.
+fn parser_options(cli: &Cli, config: &Config) -> ParserOptions {
+    let dialect = cli.dialect.unwrap_or_else(|| {
+        config.features.dialect.parse().unwrap_or_else(|e| {
+            error!("{}", e);
+            Dialect::default()
+        })
+    });
+    let mut options = dialect.options();
+    // CodeSpaces turns on indented code in any dialect
+    options.code_spaces |= config.features.code_spaces;
+    options
+}
 
-    let stdin = io::stdin();
-    let width = cli.effective_width();
-    let render_style = RenderStyle::from_computed(style);
-    let theme = cli.theme.clone();
-    let no_highlight = cli.no_highlight;
-
-    // Use a buffer for output
-    let mut output = Vec::new();
-    let mut parser = MarkdownParser::new();
-    let mut plugin_manager = PluginManager::with_builtins();
-    let parse_state = streamdown_core::state::ParseState::new();
-
-    // Read stdin line by line for streaming
-    for line in stdin.lock().lines() {
-        let line = line?;
-        trace!("Input line: {}", line);
-
-        // Check plugins first
-        if let Some(plugin_output) = plugin_manager.process_line(&line, &parse_state, style) {
-            for output_line in plugin_output {
-                writeln!(output, "{}", output_line)?;
-            }
-            // Flush output
-            io::stdout().write_all(&output)?;
-            io::stdout().flush()?;
-            output.clear();
-            continue;
-        }
+/// Build a session writing to stdout, configured from the CLI.
+fn create_session(
+    cli: &Cli,
+    style: &ComputedStyle,
+    features: &RenderFeatures,
+    options: &ParserOptions,
+) -> Session<io::Stdout> {
+    let mut session = Session::with_style(io::stdout(), cli.effective_width(), style);
+    session.renderer_mut().set_features(features.clone());
+    session.parser_mut().set_options(*options);
+    if !cli.no_highlight {
+        session.renderer_mut().set_theme(&cli.theme);
+    }
+    // Redrawing partial lines only makes sense on a terminal
+    session.set_live(!cli.no_live && is_tty());
 
-        // Parse and render
-        {
-            let mut renderer = Renderer::with_style(&mut output, width, render_style.clone());
-            renderer.set_features(features.clone());
-            if !no_highlight {
-                renderer.set_theme(&theme);
-            }
-            emit_line(&line, &mut parser, &mut renderer, cli)?;
+    // Trace events and handle code scraping if enabled
+    let scrape_dir = cli.scrape.clone();
+    session.set_event_hook(move |event| {
+        trace!("Parse event: {:?}", event);
+        match scrape_dir {
+            Some(ref dir) => scrape_code(event, dir),
+            None => Ok(()),
         }
+    });
 
-        // Flush output
-        io::stdout().write_all(&output)?;
-        io::stdout().flush()?;
-        output.clear();
-    }
+    session
+}
+
+/// Process input from stdin.
+fn run_stdin(
+    cli: &Cli,
+    style: &ComputedStyle,
+    features: &RenderFeatures,
+    options: &ParserOptions,
+) -> io::Result<()> {
+    info!("Reading from stdin");
 
-    // Flush any remaining plugin content
-    let plugin_output = plugin_manager.flush();
-    for line in plugin_output {
-        writeln!(io::stdout(), "{}", line)?;
+    let mut stdin = io::stdin().lock();
+    let mut session = create_session(cli, style, features, options);
+    let mut buf = [0u8; 4096];
+
+    // Read stdin in whatever chunks arrive so partial lines show up live
+    loop {
+        let n = match stdin.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        trace!("Input chunk: {} bytes", n);
+        session.push_bytes(&buf[..n])?;
     }
 
-    io::stdout().flush()?;
-    Ok(())
+    session.finish()
 }
 
 /// Process input files.
-fn run_files(cli: &Cli, style: &ComputedStyle, features: &RenderFeatures) -> io::Result<()> {
-    let width = cli.effective_width();
-    let render_style = RenderStyle::from_computed(style);
-    let theme = cli.theme.clone();
-    let no_highlight = cli.no_highlight;
-
+fn run_files(
+    cli: &Cli,
+    style: &ComputedStyle,
+    features: &RenderFeatures,
+    options: &ParserOptions,
+) -> io::Result<()> {
     for path in &cli.files {
         info!("Processing file: {}", path.display());
 
-        let file = File::open(path)?;
-        let reader = BufReader::new(file);
-
-        let mut output = Vec::new();
-        let mut parser = MarkdownParser::new();
-        let mut plugin_manager = PluginManager::with_builtins();
-        let parse_state = streamdown_core::state::ParseState::new();
-
-        for line in reader.lines() {
-            let line = line?;
-
-            // Check plugins first
-            if let Some(plugin_output) = plugin_manager.process_line(&line, &parse_state, style) {
-                for output_line in plugin_output {
-                    writeln!(output, "{}", output_line)?;
-                }
-                continue;
-            }
-
-            // Parse and render
-            {
-                let mut renderer = Renderer::with_style(&mut output, width, render_style.clone());
-                renderer.set_features(features.clone());
-                if !no_highlight {
-                    renderer.set_theme(&theme);
-                }
-                emit_line(&line, &mut parser, &mut renderer, cli)?;
-            }
-        }
+        let content = std::fs::read_to_string(path)?;
 
-        // Flush remaining plugin content
-        let plugin_output = plugin_manager.flush();
-        for line in plugin_output {
-            writeln!(output, "{}", line)?;
+        // Each file is its own document. The whole file is available, so
+        // reference links can resolve against definitions further down.
+        let mut session = create_session(cli, style, features, options);
+        session.parser_mut().scan_link_definitions(&content);
+        for line in content.lines() {
+            session.push_line(line)?;
         }
-
-        // Write all output
-        io::stdout().write_all(&output)?;
+        session.finish()?;
     }
 
-    io::stdout().flush()?;
     Ok(())
 }
 
@@ -265,6 +256,7 @@ fn run_exec(
     exec_cmd: &str,
     style: &ComputedStyle,
     features: &RenderFeatures,
+    options: &ParserOptions,
 ) -> io::Result<()> {
     use pty::{PollResult, PtySession};
     use regex::Regex;
@@ -277,100 +269,62 @@ fn run_exec(
 
     info!("Executing with PTY: {}", exec_cmd);
 
-    let width = cli.effective_width();
-    let render_style = RenderStyle::from_computed(style);
-    let theme = cli.theme.clone();
-    let no_highlight = cli.no_highlight;
-
     // Compile prompt regex
     let prompt_regex =
         Regex::new(&cli.prompt).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
 
     // Spawn PTY session
-    let mut session = PtySession::spawn(exec_cmd)?;
+    let mut pty_session = PtySession::spawn(exec_cmd)?;
     info!("PTY session started");
 
-    let mut output = Vec::new();
-    let mut parser = MarkdownParser::new();
-    let mut plugin_manager = PluginManager::with_builtins();
-    let parse_state = streamdown_core::state::ParseState::new();
+    let mut session = create_session(cli, style, features, options);
+    let is_prompt = {
+        let prompt_regex = prompt_regex.clone();
+        move |line: &str| prompt_regex.is_match(&streamdown_ansi::utils::visible(line))
+    };
+    session.set_passthrough(is_prompt.clone());
 
-    // Line buffer for accumulating output
-    let mut line_buffer = String::new();
     let timeout = Duration::from_millis(100);
 
     // Main loop
-    while session.is_alive() {
-        match session.poll(timeout) {
+    while pty_session.is_alive() {
+        match pty_session.poll(timeout) {
             PollResult::Stdin | PollResult::Both => {
                 // Keyboard input from user
-                if let Some(byte) = session.read_stdin_byte()? {
+                if let Some(byte) = pty_session.read_stdin_byte()? {
                     // Forward to subprocess
-                    session.write_master_byte(byte)?;
+                    pty_session.write_master_byte(byte)?;
 
                     // Handle newline
                     if byte == b'\n' || byte == b'\r' {
-                        // Flush current line
-                        if !line_buffer.is_empty() {
-                            line_buffer.clear();
-                        }
+                        // Drop the current line
+                        session.parser_mut().state_mut().take_partial_line();
                         println!();
-                        session.reset_keyboard_count();
+                        pty_session.reset_keyboard_count();
                     }
                 }
 
                 // Also check for subprocess output if Both
                 if matches!(
-                    session.poll(Duration::ZERO),
+                    pty_session.poll(Duration::ZERO),
                     PollResult::Master | PollResult::Both
                 ) {
-                    process_master_output(
-                        &mut session,
-                        &mut line_buffer,
-                        &mut output,
-                        &mut parser,
-                        &mut plugin_manager,
-                        &parse_state,
-                        style,
-                        &prompt_regex,
-                        width,
-                        &render_style,
-                        &theme,
-                        no_highlight,
-                        features,
-                        cli,
-                    )?;
+                    process_master_output(&mut pty_session, &mut session)?;
                 }
             }
             PollResult::Master => {
                 // Output from subprocess
-                process_master_output(
-                    &mut session,
-                    &mut line_buffer,
-                    &mut output,
-                    &mut parser,
-                    &mut plugin_manager,
-                    &parse_state,
-                    style,
-                    &prompt_regex,
-                    width,
-                    &render_style,
-                    &theme,
-                    no_highlight,
-                    features,
-                    cli,
-                )?;
+                process_master_output(&mut pty_session, &mut session)?;
             }
             PollResult::Timeout => {
                 // Check if there's a partial line that might be a prompt
-                if !line_buffer.is_empty() {
-                    let visible = streamdown_ansi::utils::visible(&line_buffer);
-                    if prompt_regex.is_match(&visible) {
-                        // This looks like a prompt, emit it directly
-                        print!("{}", line_buffer);
-                        io::stdout().flush()?;
-                        line_buffer.clear();
-                    }
+                let line = session.parser().partial_line();
+                if !line.is_empty() && is_prompt(&line) {
+                    // This looks like a prompt, emit it directly
+                    session.clear_preview()?;
+                    print!("{}", line);
+                    io::stdout().flush()?;
+                    session.parser_mut().state_mut().take_partial_line();
                 }
             }
             PollResult::Error => {
@@ -380,132 +334,46 @@ fn run_exec(
     }
 
     // Flush remaining content
-    if !line_buffer.is_empty() {
-        println!("{}", line_buffer);
-    }
-
-    let plugin_output = plugin_manager.flush();
-    for line in plugin_output {
-        writeln!(io::stdout(), "{}", line)?;
+    session.clear_preview()?;
+    if let Some(line) = session.parser_mut().state_mut().take_partial_line() {
+        println!("{}", line);
     }
 
-    io::stdout().flush()?;
+    session.finish()?;
 
     // Wait for child
-    let exit_code = session.wait()?;
+    let exit_code = pty_session.wait()?;
     debug!("Child exited with: {}", exit_code);
 
     Ok(())
 }
 
 /// Process output from the master side of the PTY.
-#[allow(clippy::too_many_arguments)]
 fn process_master_output(
-    session: &mut pty::PtySession,
-    line_buffer: &mut String,
-    output: &mut Vec<u8>,
-    parser: &mut MarkdownParser,
-    plugin_manager: &mut PluginManager,
-    parse_state: &streamdown_core::state::ParseState,
-    style: &ComputedStyle,
-    prompt_regex: &regex::Regex,
-    width: usize,
-    render_style: &RenderStyle,
-    theme: &str,
-    no_highlight: bool,
-    features: &RenderFeatures,
-    cli: &Cli,
+    pty_session: &mut pty::PtySession,
+    session: &mut Session<io::Stdout>,
 ) -> io::Result<()> {
     let mut buf = [0u8; 1024];
 
     loop {
-        let n = session.read_master(&mut buf)?;
+        let n = pty_session.read_master(&mut buf)?;
         if n == 0 {
             break;
         }
 
         // If user is typing, echo the output directly
-        if session.keyboard_count() > 0 {
+        if pty_session.keyboard_count() > 0 {
+            session.clear_preview()?;
             io::stdout().write_all(&buf[..n])?;
             io::stdout().flush()?;
             continue;
         }
 
-        // Process bytes into lines
-        for &byte in &buf[..n] {
-            if byte == b'\n' {
-                // Complete line
-                let line = std::mem::take(line_buffer);
-                trace!("PTY line: {}", line);
-
-                // Check for prompt
-                let visible = streamdown_ansi::utils::visible(&line);
-                if prompt_regex.is_match(&visible) {
-                    // Pass through prompt directly
-                    println!("{}", line);
-                    io::stdout().flush()?;
-                    continue;
-                }
-
-                // Check plugins
-                if let Some(plugin_output) = plugin_manager.process_line(&line, parse_state, style)
-                {
-                    for output_line in plugin_output {
-                        writeln!(output, "{}", output_line)?;
-                    }
-                    io::stdout().write_all(output)?;
-                    io::stdout().flush()?;
-                    output.clear();
-                    continue;
-                }
-
-                // Parse and render
-                {
-                    let mut renderer =
-                        Renderer::with_style(&mut *output, width, render_style.clone());
-                    renderer.set_features(features.clone());
-                    if !no_highlight {
-                        renderer.set_theme(theme);
-                    }
-                    emit_line(&line, parser, &mut renderer, cli)?;
-                }
-
-                // Flush output
-                io::stdout().write_all(output)?;
-                io::stdout().flush()?;
-                output.clear();
-            } else if byte == b'\r' {
-                // Ignore carriage returns
-            } else {
-                line_buffer.push(byte as char);
-            }
-        }
-    }
-
-    Ok(())
-}
-
-/// Emit a single line through the parser and renderer.
-fn emit_line<W: Write>(
-    line: &str,
-    parser: &mut MarkdownParser,
-    renderer: &mut Renderer<W>,
-    cli: &Cli,
-) -> io::Result<()> {
-    // Parse the line and get events
-    let events = parser.parse_line(line);
-
-    // Process all events
-    for event in events {
-        trace!("Parse event: {:?}", event);
-
-        // Handle code scraping if enabled
-        if let Some(ref scrape_dir) = cli.scrape {
-            scrape_code(&event, scrape_dir)?;
-        }
-
-        // Render the event
-        renderer.render_event(&event)?;
+        // Carriage returns are ignored; the session splits lines and keeps
+        // multi-byte characters split across reads intact
+        let bytes: Vec<u8> = buf[..n].iter().copied().filter(|&b| b != b'\r').collect();
+        trace!("PTY output: {} bytes", bytes.len());
+        session.push_bytes(&bytes)?;
     }
 
     Ok(())
@@ -0,0 +1,391 @@
+//! Streaming render session.
+//!
+//! A [`Session`] owns the plugin manager, the markdown parser and a single
+//! long-lived [`Renderer`], so block state (tables, lists, code blocks)
+//! survives from one input line to the next.
+
+use std::io::{self, Write};
+
+use streamdown_ansi::codes::{SYNC_END, SYNC_START};
+use streamdown_config::{ComputedStyle, Config};
+use streamdown_parser::{ParseEvent, Parser};
+use streamdown_plugin::PluginManager;
+use streamdown_render::{RenderStyle, Renderer};
+
+/// Callback invoked for every parse event before it is rendered.
+type EventHook = Box<dyn FnMut(&ParseEvent) -> io::Result<()>>;
+
+/// Predicate selecting lines that bypass the pipeline.
+type Passthrough = Box<dyn Fn(&str) -> bool>;
+
+/// A complete streamdown pipeline: plugins → parser → renderer.
+///
+/// Feed input with [`push_line`](Session::push_line) for complete lines or
+/// [`push_str`](Session::push_str) for arbitrary chunks, then call
+/// [`finish`](Session::finish) once the stream ends to close any open blocks.
+pub struct Session<W: Write> {
+    /// Plugin manager (LaTeX, etc.)
+    plugins: PluginManager,
+    /// Markdown parser
+    parser: Parser,
+    /// Renderer, kept alive for the whole stream
+    renderer: Renderer<W>,
+    /// Computed style handed to plugins
+    style: ComputedStyle,
+    /// Optional per-event callback (e.g. for code scraping)
+    event_hook: Option<EventHook>,
+    /// Optional filter for lines written as-is (e.g. shell prompts)
+    passthrough: Option<Passthrough>,
+    /// Whether to draw the in-progress line before its newline arrives
+    live: bool,
+}
+
+impl<W: Write> Session<W> {
+    /// Create a session with the default style and built-in plugins.
+    pub fn new(writer: W, width: usize) -> Self {
+        Self {
+            plugins: PluginManager::with_builtins(),
+            parser: Parser::new(),
+            renderer: Renderer::new(writer, width),
+            style: Config::default().computed_style(),
+            event_hook: None,
+            passthrough: None,
+            live: false,
+        }
+    }
+
+    /// Create a session whose renderer and plugins use a computed style.
+    pub fn with_style(writer: W, width: usize, style: &ComputedStyle) -> Self {
+        Self {
+            plugins: PluginManager::with_builtins(),
+            parser: Parser::new(),
+            renderer: Renderer::with_style(writer, width, RenderStyle::from_computed(style)),
+            style: style.clone(),
+            event_hook: None,
+            passthrough: None,
+            live: false,
+        }
+    }
+
+    /// Get the parser.
+    pub fn parser(&self) -> &Parser {
+        &self.parser
+    }
+
+    /// Get the parser mutably (e.g. to toggle `set_code_spaces`).
+    pub fn parser_mut(&mut self) -> &mut Parser {
+        &mut self.parser
+    }
+
+    /// Get the renderer.
+    pub fn renderer(&self) -> &Renderer<W> {
+        &self.renderer
+    }
+
+    /// Get the renderer mutably (e.g. to set the theme or features).
+    pub fn renderer_mut(&mut self) -> &mut Renderer<W> {
+        &mut self.renderer
+    }
+
+    /// Get the plugin manager mutably (e.g. to register custom plugins).
+    pub fn plugins_mut(&mut self) -> &mut PluginManager {
+        &mut self.plugins
+    }
+
+    /// Get a mutable reference to the underlying writer.
+    pub fn writer_mut(&mut self) -> &mut W {
+        self.renderer.writer_mut()
+    }
+
+    /// Install a callback that sees every parse event before it is rendered.
+    pub fn set_event_hook<F>(&mut self, hook: F)
+    where
+        F: FnMut(&ParseEvent) -> io::Result<()> + 'static,
+    {
+        self.event_hook = Some(Box::new(hook));
+    }
+
+    /// Install a filter for lines that are written unrendered.
+    ///
+    /// Matching lines skip plugins and the parser entirely; exec mode uses
+    /// this to pass shell prompts through.
+    pub fn set_passthrough<F>(&mut self, filter: F)
+    where
+        F: Fn(&str) -> bool + 'static,
+    {
+        self.passthrough = Some(Box::new(filter));
+    }
+
+    /// Enable or disable live rendering of the in-progress line.
+    ///
+    /// When enabled, [`push_bytes`](Session::push_bytes) draws the partial
+    /// line after every chunk and redraws it in place as more arrives. Only
+    /// useful when writing to a terminal, so it also lets setext headings
+    /// retract their already-drawn line; otherwise the parser holds each
+    /// paragraph's first line back until the next one arrives.
+    pub fn set_live(&mut self, enabled: bool) {
+        self.live = enabled;
+        self.parser.set_setext_lookahead(!enabled);
+    }
+
+    /// Whether live rendering is enabled.
+    pub fn is_live(&self) -> bool {
+        self.live
+    }
+
+    /// Draw `partial` as the in-progress line without committing it.
+    ///
+    /// Replaces any previous preview. Parser and renderer state are not
+    /// changed; the line is rendered for real once it is pushed complete.
+    pub fn preview(&mut self, partial: &str) -> io::Result<()> {
+        write!(self.renderer.writer_mut(), "{}", SYNC_START)?;
+        self.draw_preview(partial)?;
+        write!(self.renderer.writer_mut(), "{}", SYNC_END)?;
+        self.renderer.writer_mut().flush()
+    }
+
+    /// Erase the current preview, if any.
+    pub fn clear_preview(&mut self) -> io::Result<()> {
+        self.renderer.clear_preview()?;
+        self.renderer.writer_mut().flush()
+    }
+
+    /// Process one complete line of input (without the trailing newline).
+    pub fn push_line(&mut self, line: &str) -> io::Result<()> {
+        // The committed line replaces its preview
+        self.renderer.clear_preview()?;
+
+        if self.passthrough.as_ref().is_some_and(|filter| filter(line)) {
+            let writer = self.renderer.writer_mut();
+            writeln!(writer, "{}", line)?;
+            return writer.flush();
+        }
+
+        // Plugins get first look at every line
+        if let Some(lines) = self
+            .plugins
+            .process_line(line, self.parser.state(), &self.style)
+        {
+            let writer = self.renderer.writer_mut();
+            for output_line in lines {
+                writeln!(writer, "{}", output_line)?;
+            }
+            return writer.flush();
+        }
+
+        let events = self.parser.parse_line(line);
+        self.render_events(&events)
+    }
+
+    /// Process an arbitrary chunk of text.
+    ///
+    /// Complete lines are rendered immediately; a trailing partial line is
+    /// held back until its newline arrives or [`finish`](Session::finish)
+    /// is called.
+    pub fn push_str(&mut self, chunk: &str) -> io::Result<()> {
+        self.push_bytes(chunk.as_bytes())
+    }
+
+    /// Process an arbitrary chunk of raw bytes.
+    ///
+    /// Like [`push_str`](Session::push_str), but the chunk may also end in
+    /// the middle of a UTF-8 codepoint. In live mode the partial line is
+    /// drawn immediately.
+    pub fn push_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.parser.state_mut().push_bytes(bytes);
+
+        if !self.live {
+            while let Some(line) = self.parser.state_mut().take_line() {
+                self.push_line(&line)?;
+            }
+            return Ok(());
+        }
+
+        // Commit and redraw in one block so the old preview never flashes
+        write!(self.renderer.writer_mut(), "{}", SYNC_START)?;
+        while let Some(line) = self.parser.state_mut().take_line() {
+            self.push_line(&line)?;
+        }
+        let partial = self.parser.partial_line();
+        self.draw_preview(&partial)?;
+        write!(self.renderer.writer_mut(), "{}", SYNC_END)?;
+        self.renderer.writer_mut().flush()
+    }
+
+    /// End the stream: render any partial line, close open blocks and
+    /// flush buffered plugin output.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if let Some(line) = self.parser.state_mut().take_partial_line() {
+            self.push_line(&line)?;
+        }
+
+        let events = self.parser.finalize();
+        self.render_events(&events)?;
+        self.renderer.finish()?;
+
+        let writer = self.renderer.writer_mut();
+        for line in self.plugins.flush() {
+            writeln!(writer, "{}", line)?;
+        }
+        writer.flush()
+    }
+
+    /// Consume the session and return the underlying writer.
+    pub fn into_writer(self) -> W {
+        self.renderer.into_writer()
+    }
+
+    /// Redraw the preview without synchronized output; callers provide it.
+    fn draw_preview(&mut self, partial: &str) -> io::Result<()> {
+        // Plugins buffer their own blocks, so there is nothing to preview
+        if partial.is_empty() || self.plugins.has_active_plugin() {
+            return self.renderer.clear_preview();
+        }
+
+        let events = self.parser.preview_line(partial);
+        self.renderer.render_preview(&events)
+    }
+
+    /// Run events through the hook and the renderer.
+    fn render_events(&mut self, events: &[ParseEvent]) -> io::Result<()> {
+        for event in events {
+            if let Some(hook) = self.event_hook.as_mut() {
+                hook(event)?;
+            }
+            self.renderer.render_event(event)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_lines(lines: &[&str]) -> String {
+        let mut session = Session::new(Vec::new(), 80);
+        for line in lines {
+            session.push_line(line).unwrap();
+        }
+        session.finish().unwrap();
+        String::from_utf8(session.into_writer()).unwrap()
+    }
+
+    #[test]
+    fn test_push_line_renders() {
+        let output = render_lines(&["# Title"]);
+        assert!(output.contains("Title"));
+    }
+
+    #[test]
+    fn test_ordered_list_numbering_survives_lines() {
+        let output = render_lines(&["1. one", "2. two", "3. three"]);
+        let visible = streamdown_ansi::utils::visible(&output);
+        assert!(visible.contains("1."));
+        assert!(visible.contains("2."));
+        assert!(visible.contains("3."));
+    }
+
+    #[test]
+    fn test_table_state_survives_lines() {
+        let output = render_lines(&["| A | B |", "|---|---|", "| 1 | 2 |"]);
+        // The body row uses the code background, not the header background,
+        // which only happens when the separator state carried over.
+        let header_bg = streamdown_render::bg_color(&RenderStyle::default().table_header_bg);
+        let body_line = output
+            .lines()
+            .find(|l| streamdown_ansi::utils::visible(l).contains('1'))
+            .unwrap();
+        assert!(!body_line.contains(&header_bg));
+    }
+
+    #[test]
+    fn test_push_str_chunks_match_lines() {
+        let by_lines = render_lines(&["# Title", "", "Some **bold** text"]);
+
+        let mut session = Session::new(Vec::new(), 80);
+        for chunk in ["# Ti", "tle\n\nSome **bo", "ld** te", "xt"] {
+            session.push_str(chunk).unwrap();
+        }
+        session.finish().unwrap();
+        let by_chunks = String::from_utf8(session.into_writer()).unwrap();
+
+        assert_eq!(by_lines, by_chunks);
+    }
+
+    #[test]
+    fn test_push_bytes_split_codepoint() {
+        let by_lines = render_lines(&["Arrows → and ✓"]);
+
+        let input = "Arrows → and ✓\n".as_bytes();
+        let mut session = Session::new(Vec::new(), 80);
+        for byte in input {
+            session.push_bytes(std::slice::from_ref(byte)).unwrap();
+        }
+        session.finish().unwrap();
+        let by_bytes = String::from_utf8(session.into_writer()).unwrap();
+
+        assert_eq!(by_lines, by_bytes);
+    }
+
+    #[test]
+    fn test_live_preview_matches_final_output() {
+        let by_lines = render_lines(&["Some **bold** text"]);
+
+        let mut session = Session::new(Vec::new(), 80);
+        session.set_live(true);
+        session.push_str("Some **bo").unwrap();
+        assert!(session.renderer().has_preview());
+        session.push_str("ld** text\n").unwrap();
+        assert!(!session.renderer().has_preview());
+        session.finish().unwrap();
+        let live = String::from_utf8(session.into_writer()).unwrap();
+
+        // The preview is drawn and erased, then the line is committed
+        assert!(live.starts_with(SYNC_START));
+        let committed = format!("\r{}{}", streamdown_ansi::codes::CLEAR_LINE, by_lines);
+        assert!(live.ends_with(&format!("{}{}", committed, SYNC_END)));
+    }
+
+    #[test]
+    fn test_setext_heading_not_retracted_by_default() {
+        let mut output = Vec::new();
+        let mut session = Session::new(&mut output, 60);
+        session.push_str("Title\n=====\n").unwrap();
+        session.finish().unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(!output.contains(streamdown_ansi::codes::CURSOR_UP));
+        assert_eq!(
+            streamdown_ansi::utils::visible(&output)
+                .matches("Title")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_live_off_draws_no_preview() {
+        let mut session = Session::new(Vec::new(), 80);
+        session.push_str("partial").unwrap();
+        assert!(!session.renderer().has_preview());
+        assert!(session.writer_mut().is_empty());
+    }
+
+    #[test]
+    fn test_finish_closes_code_block() {
+        let mut session = Session::new(Vec::new(), 80);
+        let ended = std::rc::Rc::new(std::cell::Cell::new(false));
+        let flag = ended.clone();
+        session.set_event_hook(move |event| {
+            if matches!(event, ParseEvent::CodeBlockEnd) {
+                flag.set(true);
+            }
+            Ok(())
+        });
+        session.push_str("```rust\nlet x = 1;\n").unwrap();
+        assert!(!ended.get());
+        session.finish().unwrap();
+        assert!(ended.get());
+    }
+}
@@ -6,7 +6,7 @@
 use std::fs;
 use std::path::PathBuf;
 
-use streamdown_parser::{ListBullet, ParseEvent, Parser};
+use streamdown_parser::{Extensions, ListBullet, ParseEvent, Parser, ParserOptions};
 use streamdown_render::{RenderStyle, Renderer};
 
 /// Get the path to Python Streamdown test files.
@@ -56,24 +56,32 @@ fn parse_document(content: &str) -> Vec<ParseEvent> {
     for line in content.lines() {
         events.extend(parser.parse_line(line));
     }
+    events.extend(parser.finalize());
 
     events
 }
 
 /// Helper to render a document to a string.
 fn render_to_string(content: &str, width: usize) -> String {
+    render_with_parser(Parser::new(), content, width)
+}
+
+/// Helper to render a document to a string with a configured parser.
+fn render_with_parser(mut parser: Parser, content: &str, width: usize) -> String {
     let mut output = Vec::new();
-    let mut parser = Parser::new();
 
     {
         let mut renderer = Renderer::new(&mut output, width);
 
         for line in content.lines() {
-            let events = parser.parse_line(line);
-            for event in events {
+            for event in parser.parse_line(line) {
                 renderer.render_event(&event).unwrap();
             }
         }
+        for event in parser.finalize() {
+            renderer.render_event(&event).unwrap();
+        }
+        renderer.finish().unwrap();
     }
 
     String::from_utf8(output).unwrap()
@@ -638,7 +646,7 @@ fn test_loose_list_then_paragraph() {
         .position(|e| match e {
             ParseEvent::Text(t) if t.contains("Some paragraph") => true,
             ParseEvent::InlineElements(elems) => elems.iter().any(|el| {
-                matches!(el, streamdown_parser::InlineElement::Text(t) if t.contains("Some paragraph"))
+                matches!(&el.element, streamdown_parser::InlineElement::Text(t) if t.contains("Some paragraph"))
             }),
             _ => false,
         })
@@ -668,16 +676,28 @@ fn test_render_with_custom_style() {
         h6: "#888888".to_string(),
         code_bg: "#111111".to_string(),
         code_label: "#ff0000".to_string(),
+        code_highlight_bg: "#333333".to_string(),
         bullet: "#0000ff".to_string(),
+        checkbox: "#0000ff".to_string(),
+        checkbox_checked: "#00ff00".to_string(),
         table_header_bg: "#333333".to_string(),
         table_border: "#888888".to_string(),
         blockquote_border: "#888888".to_string(),
+        alert_note: "#0000ff".to_string(),
+        alert_tip: "#00ff00".to_string(),
+        alert_important: "#ff00ff".to_string(),
+        alert_warning: "#ffff00".to_string(),
+        alert_caution: "#ff0000".to_string(),
         think_border: "#888888".to_string(),
         hr: "#888888".to_string(),
         link_url: "#ff0000".to_string(),
         image_marker: "#0000ff".to_string(),
         footnote: "#ff0000".to_string(),
+        highlight_bg: "#444444".to_string(),
         heading_centered: true,
+        margin: 0,
+        bullets: vec!["-".to_string()],
+        numbering: vec![streamdown_render::NumberingStyle::UpperRoman],
     };
 
     {
@@ -716,6 +736,37 @@ fn test_render_code_block_padding() {
     assert!(output.contains('▄') || output.contains('▀') || !output.is_empty());
 }
 
+// =============================================================================
+// Dialect Tests
+// =============================================================================
+
+#[test]
+fn test_commonmark_dialect_in_blocks() {
+    use streamdown_ansi::codes::UNDERLINE_ON;
+
+    let content = "# __Head__ ~~h~~\n\n- __Item__ ~~i~~\n\n| __Cell__ ~~c~~ |\n|---|\n| x |\n";
+    // CommonMark has no tables, so enable just those to reach a cell
+    let options = ParserOptions {
+        extensions: Extensions {
+            tables: true,
+            ..Extensions::commonmark()
+        },
+        ..ParserOptions::commonmark()
+    };
+    let output = render_with_parser(Parser::with_options(options), content, 80);
+    let plain = streamdown_ansi::utils::visible(&output);
+
+    // `__text__` bolds and `~~text~~` stays literal in every block
+    assert!(!output.contains(UNDERLINE_ON), "no underline: {:?}", output);
+    for text in ["Head ~~h~~", "Item ~~i~~", "Cell ~~c~~"] {
+        assert!(plain.contains(text), "missing {:?} in {:?}", text, plain);
+    }
+
+    let classic = render_with_parser(Parser::new(), content, 80);
+    assert!(classic.contains(UNDERLINE_ON));
+    assert!(!streamdown_ansi::utils::visible(&classic).contains("~~"));
+}
+
 // =============================================================================
 // Plugin Tests
 // =============================================================================
@@ -159,6 +159,79 @@ proptest! {
     }
 }
 
+// =============================================================================
+// Chunked Feed Property Tests
+// =============================================================================
+
+/// Generate markdown with multi-byte characters mixed in.
+fn unicode_markdown() -> impl Strategy<Value = String> {
+    let block = prop_oneof![
+        heading(),
+        code_block(),
+        list(),
+        prop::string::string_regex(r"[\x20-\x7E→✓é日本🎉*_`|]{0,80}").unwrap(),
+        Just("| A | B |\n|---|---|\n| 1 | 2 |".to_string()),
+    ];
+    prop::collection::vec(block, 0..8).prop_map(|blocks| blocks.join("\n"))
+}
+
+/// Render input by handing whole lines to `parse_line`.
+fn render_by_lines(input: &str) -> Vec<u8> {
+    let mut output = Vec::new();
+    let mut parser = Parser::new();
+    {
+        let mut renderer = Renderer::new(&mut output, 80);
+        for event in parser.parse_document(input) {
+            renderer.render_event(&event).unwrap();
+        }
+    }
+    output
+}
+
+/// Render input by feeding raw byte chunks split at the given offsets.
+fn render_by_chunks(input: &str, mut cuts: Vec<usize>) -> Vec<u8> {
+    let bytes = input.as_bytes();
+    cuts.retain(|&cut| cut < bytes.len());
+    cuts.sort_unstable();
+    cuts.dedup();
+
+    let mut output = Vec::new();
+    let mut parser = Parser::new();
+    {
+        let mut renderer = Renderer::new(&mut output, 80);
+        let mut start = 0;
+        for end in cuts.into_iter().chain(std::iter::once(bytes.len())) {
+            for event in parser.feed(&bytes[start..end]) {
+                renderer.render_event(&event).unwrap();
+            }
+            start = end;
+        }
+        for event in parser.finalize() {
+            renderer.render_event(&event).unwrap();
+        }
+    }
+    output
+}
+
+proptest! {
+    /// Rendering must not depend on how the input bytes were chunked,
+    /// even when a chunk boundary falls inside a UTF-8 codepoint.
+    #[test]
+    fn feed_is_chunking_invariant(
+        input in unicode_markdown(),
+        cuts in prop::collection::vec(0..1024usize, 0..32),
+    ) {
+        prop_assert_eq!(render_by_lines(&input), render_by_chunks(&input, cuts));
+    }
+
+    /// Feeding one byte at a time is the most extreme chunking.
+    #[test]
+    fn feed_byte_by_byte(input in unicode_markdown()) {
+        let cuts = (1..input.len()).collect();
+        prop_assert_eq!(render_by_lines(&input), render_by_chunks(&input, cuts));
+    }
+}
+
 // =============================================================================
 // ANSI Utility Property Tests
 // =============================================================================
@@ -19,6 +19,10 @@ fn render(input: &str, width: usize) -> String {
                 renderer.render_event(&event).unwrap();
             }
         }
+        for event in parser.finalize() {
+            renderer.render_event(&event).unwrap();
+        }
+        renderer.finish().unwrap();
     }
 
     // Strip ANSI codes for cleaner snapshots
@@ -54,6 +58,20 @@ fn test_snapshot_heading_all_levels() {
     insta::assert_snapshot!(output);
 }
 
+#[test]
+fn test_snapshot_setext_headings() {
+    let input = r#"Main Title
+==========
+
+Intro paragraph.
+
+Section
+-------
+Body text."#;
+    let output = render(input, 80);
+    insta::assert_snapshot!(output);
+}
+
 // =============================================================================
 // Code Block Snapshots
 // =============================================================================
@@ -89,6 +107,19 @@ code block
     insta::assert_snapshot!(output);
 }
 
+#[test]
+fn test_snapshot_code_block_info_string() {
+    let input = r#"````markdown title="README.md" linenos {2}
+# Example
+
+```bash
+sd README.md
+```
+````"#;
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
 // =============================================================================
 // List Snapshots
 // =============================================================================
@@ -111,6 +142,19 @@ fn test_snapshot_ordered_list() {
     insta::assert_snapshot!(output);
 }
 
+#[test]
+fn test_snapshot_ordered_list_start_and_delimiter() {
+    let input = r#"- A bullet list
+- then an ordered one
+
+3) Starts at three
+4) with parentheses
+   1. nested
+   2. numbers"#;
+    let output = render(input, 80);
+    insta::assert_snapshot!(output);
+}
+
 #[test]
 fn test_snapshot_nested_list() {
     let input = r#"- Level 1
@@ -122,6 +166,61 @@ fn test_snapshot_nested_list() {
     insta::assert_snapshot!(output);
 }
 
+#[test]
+fn test_snapshot_task_list() {
+    let input = r#"- [x] Parse the input
+- [ ] Render output
+  - [ ] Nested step
+
+Ordered:
+
+1. [x] First
+2. [ ] Second"#;
+    let output = render(input, 80);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_snapshot_code_block_in_list_item() {
+    let input = r#"1. Install the binary:
+
+   ```bash
+   cargo install streamdown
+   ```
+
+2. Pipe some markdown in.
+
+   The output is rendered as it streams.
+
+3. Done"#;
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_snapshot_list_item_continuation() {
+    let input = "- First item, whose text\n  \
+                 continues on an indented line\n\
+                 and on a lazy one\n\
+                 - Second item ends with a break  \n  \
+                 so this starts a new line\n";
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_snapshot_quote_in_list_item() {
+    let input = r#"- First item
+  > A quote inside
+  > the first item
+- Second item
+  1. Nested
+     > Quoted under the nested item
+  2. Back in the nested list"#;
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
 // =============================================================================
 // Table Snapshots
 // =============================================================================
@@ -146,6 +245,49 @@ fn test_snapshot_wide_table() {
     insta::assert_snapshot!(output);
 }
 
+#[test]
+fn test_snapshot_aligned_table() {
+    let input = r#"| Model | Params | Score |
+|:------|:------:|------:|
+| small | 7B | 61.2 |
+| large | 70B | 79.85 |"#;
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_snapshot_gfm_table_without_outer_pipes() {
+    let input = r#"Flag | Meaning
+--- | ---
+`a|b` | pipe inside code
+x \| y | escaped pipe | extra cell
+short"#;
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_snapshot_table_followed_by_heading() {
+    let input = r#"| a | b |
+|---|---|
+| 1 | 2 |
+# Head
+After"#;
+    let output = render(input, 40);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_snapshot_table_followed_by_list() {
+    let input = r#"| a | b |
+|---|---|
+| 1 | 2 |
+1. one
+2. two"#;
+    let output = render(input, 40);
+    insta::assert_snapshot!(output);
+}
+
 // =============================================================================
 // Inline Formatting Snapshots
 // =============================================================================
@@ -174,6 +316,37 @@ fn test_snapshot_mixed_inline() {
     insta::assert_snapshot!(output);
 }
 
+#[test]
+fn test_snapshot_nested_inline() {
+    let input = "# A *very* **`fast`** parser\n\n\
+                 - ***Both** then italic* and [the **docs**](https://docs.rs)\n\n\
+                 | Name | Note |\n\
+                 |------|------|\n\
+                 | **`sd`** | *fast **and** small* |\n";
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_snapshot_escapes_and_entities() {
+    let input = "# Not \\*italic\\* &copy; 2024\n\n\
+                 \\*a\\* \\_b\\_ \\`c\\` \\# and [x &amp; y](https://x.com) `&amp; \\*`\n\n\
+                 | Sign | Code |\n\
+                 |------|------|\n\
+                 | &lt;&gt; \\* | `&lt;` |\n";
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_snapshot_extended_inline() {
+    let input = "# Release :rocket:\n\n\
+                 ==**Breaking:** see [docs](https://docs.rs)== for x^2^ and H~2~O :tada:\n\n\
+                 Unknown :shortcodes: and ~~struck~~ text stay as written.\n";
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
 // =============================================================================
 // Block Quote Snapshots
 // =============================================================================
@@ -195,6 +368,132 @@ fn test_snapshot_nested_blockquote() {
     insta::assert_snapshot!(output);
 }
 
+#[test]
+fn test_snapshot_blocks_inside_blockquote() {
+    let input = r#"> Steps:
+>
+> 1. Install
+> 2. Run
+>
+> ```bash
+> sd README.md
+> ```
+>
+> | Flag | Meaning |
+> |------|---------|
+> | `-w` | width   |
+>
+> > Nested note
+>
+> Back to level 1"#;
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
+#[test]
+fn test_snapshot_alerts() {
+    let input = r#"> [!NOTE]
+> Useful information.
+
+> [!TIP] Faster builds
+> Use `cargo check` while iterating.
+
+> [!IMPORTANT]
+> Read this first.
+
+> [!WARNING]
+> > [!CAUTION]
+> > Nested alerts keep their own colour."#;
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
+// =============================================================================
+// Front Matter Snapshots
+// =============================================================================
+
+#[test]
+fn test_snapshot_front_matter() {
+    let input = r#"---
+title: Getting started
+date: 2024-05-01
+tags: [intro, setup]
+---
+Welcome to the docs."#;
+    let output = render(input, 50);
+    insta::assert_snapshot!(output);
+}
+
+// =============================================================================
+// Autolink Snapshots
+// =============================================================================
+
+#[test]
+fn test_snapshot_autolinks() {
+    let input = r#"Docs live at <https://docs.rs/streamdown>, mail <team@example.com>.
+See https://en.wikipedia.org/wiki/Rust_(programming_language), or (www.rust-lang.org).
+Not in code: `https://example.com/api`."#;
+    let output = render(input, 100);
+    insta::assert_snapshot!(output);
+}
+
+// =============================================================================
+// Reference Link Snapshots
+// =============================================================================
+
+#[test]
+fn test_snapshot_reference_links() {
+    let input = r#"[home]: https://example.com
+
+[Home][home] is defined above and resolves in place. [The docs][docs]
+are filled in once their definition arrives, and [this][nowhere] is flagged.
+
+[docs]: https://docs.rs "Docs"
+
+After the definition, [docs][] is an ordinary link."#;
+    let output = render(input, 80);
+    insta::assert_snapshot!(output);
+}
+
+// =============================================================================
+// HTML Snapshots
+// =============================================================================
+
+#[test]
+fn test_snapshot_inline_html() {
+    let input = r#"<!-- generated by a model -->
+Press <kbd>Ctrl</kbd>+<kbd>C</kbd> to stop.<br>E = mc<sup>2</sup>, H<sub>2</sub>O and x<sup>th</sup>.
+Unknown <span>tags</span> are kept. `<kbd>code</kbd>` is untouched.
+
+<details>
+<summary>Show **details**</summary>
+
+- hidden item
+- another
+
+<!--
+a comment spanning
+several lines
+-->
+</details>"#;
+    let output = render(input, 60);
+    insta::assert_snapshot!(output);
+}
+
+// =============================================================================
+// Footnote Snapshots
+// =============================================================================
+
+#[test]
+fn test_snapshot_footnotes() {
+    let input = r#"Streaming parsers[^1] render as text arrives[^2].
+
+[^1]: They never see the whole document, so **definitions** are collected until the notes section is printed.
+[^3]: Defined but never referenced."#;
+    let output = render(input, 50);
+    insta::assert_snapshot!(output);
+}
+
 // =============================================================================
 // Think Block Snapshots
 // =============================================================================